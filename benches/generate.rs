@@ -0,0 +1,77 @@
+//! Wall-clock and memory benchmarks for the parse/generate pipeline.
+//!
+//! Run with `cargo bench --features bench`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use dbschema::bench::{SYNTHETIC_ROOT, synthetic_loader};
+use dbschema::frontend::env::EnvVars;
+
+/// Global allocator wrapper that tracks live and peak heap usage.
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let now = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(now, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const SIZES: [usize; 3] = [50, 200, 500];
+
+fn load_and_generate(loader: &dbschema::MapLoader) -> String {
+    let cfg = dbschema::load_config(Path::new(SYNTHETIC_ROOT), loader, EnvVars::default())
+        .expect("synthetic config loads");
+    dbschema::generate_with_backend("postgres", &cfg, false).expect("generation succeeds")
+}
+
+/// Report the peak heap growth of a single load + generate run.
+fn report_peak_memory(tables: usize) {
+    let loader = synthetic_loader(tables);
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    black_box(load_and_generate(&loader));
+    let peak = PEAK.load(Ordering::Relaxed).saturating_sub(baseline);
+    eprintln!(
+        "memory/load_and_generate/{tables}: peak {:.1} KiB",
+        peak as f64 / 1024.0
+    );
+}
+
+fn bench_generate(c: &mut Criterion) {
+    for tables in SIZES {
+        report_peak_memory(tables);
+    }
+
+    let mut group = c.benchmark_group("load_and_generate");
+    group.sample_size(20);
+    for tables in SIZES {
+        let loader = synthetic_loader(tables);
+        group.bench_with_input(BenchmarkId::from_parameter(tables), &loader, |b, loader| {
+            b.iter(|| black_box(load_and_generate(loader)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_generate);
+criterion_main!(benches);
@@ -0,0 +1,4 @@
+locals {
+  x = -9223372036854775808
+  y = -local.x
+}
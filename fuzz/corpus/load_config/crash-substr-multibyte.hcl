@@ -0,0 +1,3 @@
+locals {
+  s = substr("héllo", 1, 2)
+}
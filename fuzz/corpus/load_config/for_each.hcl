@@ -0,0 +1,8 @@
+locals {
+  names = [for n in ["a", "b", "c"] : "t_${n}"]
+}
+
+schema "s" {
+  for_each = local.names
+  name     = each.value
+}
@@ -0,0 +1,11 @@
+schema "app" {}
+
+table "users" {
+  schema = "app"
+  column "id" {
+    type = "bigint"
+  }
+  primary_key {
+    columns = ["id"]
+  }
+}
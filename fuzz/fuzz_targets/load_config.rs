@@ -0,0 +1,23 @@
+//! Feed arbitrary HCL to `load_config`. Any input may be rejected, but the
+//! frontend must report it as an error rather than panic.
+
+#![no_main]
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use dbschema::MapLoader;
+use dbschema::frontend::env::EnvVars;
+use libfuzzer_sys::fuzz_target;
+
+const ROOT: &str = "/fuzz/main.hcl";
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(src) = std::str::from_utf8(data) else {
+        return;
+    };
+    let loader = MapLoader {
+        files: HashMap::from([(PathBuf::from(ROOT), src.to_string())]),
+    };
+    let _ = dbschema::load_config(Path::new(ROOT), &loader, EnvVars::default());
+});
@@ -2,9 +2,18 @@ use anyhow::Result;
 use serde_json::json;
 
 use super::Backend;
-use crate::ir::Config;
+use crate::ir::{Config, REDACTED};
 
-pub struct JsonBackend;
+/// Version of the JSON document layout, bumped whenever consumers would need
+/// to change how they read it.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Serializes the config as JSON. Top-level collections are sorted so that
+/// generating the same config twice gives byte-identical output.
+pub struct JsonBackend {
+    /// Indent the output; registered as `json`, or `json-compact` when off.
+    pub pretty: bool,
+}
 
 impl Backend for JsonBackend {
     fn name(&self) -> &'static str {
@@ -13,11 +22,95 @@ impl Backend for JsonBackend {
     fn file_extension(&self) -> &'static str {
         "json"
     }
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
     fn generate(&self, cfg: &Config, _strict: bool) -> Result<String> {
-        let output = json!({
-            "backend": self.name(),
-            "config": cfg,
+        let output = document(self.name(), cfg)?;
+        if self.pretty {
+            serde_json::to_string_pretty(&output).map_err(Into::into)
+        } else {
+            serde_json::to_string(&output).map_err(Into::into)
+        }
+    }
+}
+
+/// The document the JSON and YAML backends serialize: the format version,
+/// the backend name and the config with sensitive values redacted and
+/// collections sorted.
+pub(crate) fn document(backend: &str, cfg: &Config) -> Result<serde_json::Value> {
+    let mut config = serde_json::to_value(cfg)?;
+    redact_sensitive(&mut config, cfg);
+    sort_collections(&mut config);
+    Ok(json!({
+        "version": FORMAT_VERSION,
+        "backend": backend,
+        "config": config,
+    }))
+}
+
+/// Sort each top-level collection by schema, table and name, so the output
+/// doesn't depend on declaration or module load order. Nested lists such as
+/// columns keep their order, which is meaningful.
+fn sort_collections(config: &mut serde_json::Value) {
+    let Some(map) = config.as_object_mut() else {
+        return;
+    };
+    for items in map.values_mut().filter_map(|v| v.as_array_mut()) {
+        items.sort_by_cached_key(|item| {
+            ["schema", "table", "name"].map(|key| {
+                item.get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            })
         });
-        serde_json::to_string_pretty(&output).map_err(Into::into)
+    }
+}
+
+/// Replace every string equal to a sensitive value with [`REDACTED`].
+fn redact_sensitive(value: &mut serde_json::Value, cfg: &Config) {
+    match value {
+        serde_json::Value::String(s) if cfg.sensitive_values.contains(s) => {
+            *s = REDACTED.to_string()
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| redact_sensitive(v, cfg)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| redact_sensitive(v, cfg)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::RoleSpec;
+
+    #[test]
+    fn redacts_only_exact_sensitive_strings() {
+        let role = RoleSpec {
+            name: "app".into(),
+            alt_name: None,
+            login: true,
+            superuser: false,
+            createdb: false,
+            createrole: false,
+            replication: false,
+            password: Some("s3cr3t".into()),
+            valid_until: None,
+            in_role: Vec::new(),
+            config: hcl::Map::from_iter([("lock_timeout".to_string(), hcl::Value::from(420))]),
+            comment: Some("rotate s3cr3t monthly".into()),
+        };
+        let cfg = Config {
+            roles: vec![role],
+            sensitive_values: vec!["s3cr3t".into()],
+            ..Default::default()
+        };
+        let doc = document("json", &cfg).unwrap();
+        let role = &doc["config"]["roles"][0];
+        assert_eq!(role["password"], REDACTED);
+        assert_eq!(role["comment"], "rotate s3cr3t monthly");
+        assert_eq!(role["config"]["lock_timeout"], 420);
+        assert_eq!(role["login"], true);
     }
 }
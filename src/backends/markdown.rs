@@ -0,0 +1,246 @@
+use super::Backend;
+use crate::ir::{Config, TableSpec};
+
+use anyhow::Result;
+
+/// Renders a data dictionary: one section per table with its comment, a
+/// table of columns and lists of indexes and foreign keys. Tables are sorted
+/// by qualified name and the output carries no timestamp.
+pub struct MarkdownBackend;
+
+impl Backend for MarkdownBackend {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+    fn file_extension(&self) -> &'static str {
+        "md"
+    }
+    fn content_type(&self) -> &'static str {
+        "text/markdown"
+    }
+    fn generate(&self, cfg: &Config, _strict: bool) -> Result<String> {
+        let mut tables: Vec<&TableSpec> = cfg.tables.iter().collect();
+        tables.sort_by_key(|t| qualified_name(t));
+
+        let mut out = String::from("# Data dictionary\n");
+        for t in tables {
+            out.push('\n');
+            out.push_str(&section(t, cfg));
+        }
+        Ok(out)
+    }
+}
+
+fn qualified_name(t: &TableSpec) -> String {
+    let schema = t.schema.as_deref().unwrap_or("public");
+    let name = t.alt_name.as_deref().unwrap_or(&t.name);
+    format!("{schema}.{name}")
+}
+
+fn section(t: &TableSpec, cfg: &Config) -> String {
+    let mut out = format!("## {}\n\n", qualified_name(t));
+    if let Some(comment) = &t.comment {
+        out.push_str(comment.trim());
+        out.push_str("\n\n");
+    }
+
+    out.push_str("| Column | Type | Nullable | Default | Comment |\n");
+    out.push_str("| --- | --- | --- | --- | --- |\n");
+    for c in &t.columns {
+        let nullable = if c.nullable { "yes" } else { "no" };
+        let default = c.default.as_deref().map(code).unwrap_or_default();
+        let comment = c.comment.as_deref().map(cell).unwrap_or_default();
+        out.push_str(&format!(
+            "| {} | {} | {nullable} | {default} | {comment} |\n",
+            code(&c.name),
+            code(&c.r#type)
+        ));
+    }
+
+    if let Some(pk) = &t.primary_key {
+        out.push_str(&format!("\n**Primary key:** {}\n", code_list(&pk.columns)));
+    }
+
+    let schema = t.schema.as_deref().unwrap_or("public");
+    let mut indexes: Vec<String> = t
+        .indexes
+        .iter()
+        .map(|ix| {
+            let keys = if ix.expressions.is_empty() {
+                &ix.columns
+            } else {
+                &ix.expressions
+            };
+            index_line(ix.name.as_deref(), ix.unique, keys, ix.r#where.as_deref())
+        })
+        .collect();
+    indexes.extend(
+        cfg.indexes
+            .iter()
+            .filter(|ix| ix.table == t.name && ix.schema.as_deref().unwrap_or("public") == schema)
+            .map(|ix| {
+                let keys = if ix.expressions.is_empty() {
+                    &ix.columns
+                } else {
+                    &ix.expressions
+                };
+                index_line(Some(&ix.name), ix.unique, keys, ix.r#where.as_deref())
+            }),
+    );
+    if !indexes.is_empty() {
+        out.push_str("\n**Indexes**\n\n");
+        for line in indexes {
+            out.push_str(&format!("- {line}\n"));
+        }
+    }
+
+    if !t.foreign_keys.is_empty() {
+        out.push_str("\n**Foreign keys**\n\n");
+        for fk in &t.foreign_keys {
+            let target = format!(
+                "{}.{}",
+                fk.ref_schema.as_deref().unwrap_or("public"),
+                fk.ref_table
+            );
+            let mut line = format!(
+                "- {} → {} ({})",
+                code_list(&fk.columns),
+                code(&target),
+                code_list(&fk.ref_columns)
+            );
+            if let Some(name) = &fk.name {
+                line.push_str(&format!(" as {}", code(name)));
+            }
+            if let Some(action) = &fk.on_delete {
+                line.push_str(&format!(", on delete {}", action.to_lowercase()));
+            }
+            if let Some(action) = &fk.on_update {
+                line.push_str(&format!(", on update {}", action.to_lowercase()));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn index_line(name: Option<&str>, unique: bool, keys: &[String], r#where: Option<&str>) -> String {
+    let mut line = match name {
+        Some(name) => format!("{}: ", code(name)),
+        None => String::new(),
+    };
+    line.push_str(&code_list(keys));
+    if unique {
+        line.push_str(" (unique)");
+    }
+    if let Some(predicate) = r#where {
+        line.push_str(&format!(" where {}", code(predicate)));
+    }
+    line
+}
+
+fn code_list(items: &[String]) -> String {
+    items.iter().map(|i| code(i)).collect::<Vec<_>>().join(", ")
+}
+
+/// Inline code inside a table cell; backticks and pipes would end it early.
+fn code(s: &str) -> String {
+    format!("`{}`", cell(s).replace('`', "'"))
+}
+
+/// Text that stays inside one Markdown table cell.
+fn cell(s: &str) -> String {
+    s.trim().replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generate_with_backend;
+    use crate::test_support::load_hcl;
+
+    #[test]
+    fn sections_list_columns_indexes_and_foreign_keys() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              comment = "Everyone who can sign in."
+              column "id" {
+                type     = "serial"
+                nullable = false
+              }
+              column "email" {
+                type     = "text"
+                nullable = false
+                comment  = "Login | contact address"
+              }
+              primary_key {
+                columns = ["id"]
+              }
+              index "users_email_key" {
+                columns = ["email"]
+                unique  = true
+              }
+            }
+
+            table "posts" {
+              column "id" {
+                type     = "serial"
+                nullable = false
+              }
+              column "author_id" {
+                type = "integer"
+              }
+              column "created_at" {
+                type    = "timestamptz"
+                default = "now()"
+              }
+              foreign_key {
+                columns = ["author_id"]
+                ref {
+                  table   = "users"
+                  columns = ["id"]
+                }
+                on_delete = "CASCADE"
+              }
+            }
+            "#,
+        );
+        let markdown = generate_with_backend("markdown", &cfg, false).unwrap();
+        assert_eq!(
+            markdown,
+            r#"# Data dictionary
+
+## public.posts
+
+| Column | Type | Nullable | Default | Comment |
+| --- | --- | --- | --- | --- |
+| `id` | `serial` | no |  |  |
+| `author_id` | `integer` | yes |  |  |
+| `created_at` | `timestamptz` | yes | `now()` |  |
+
+**Foreign keys**
+
+- `author_id` → `public.users` (`id`), on delete cascade
+
+## public.users
+
+Everyone who can sign in.
+
+| Column | Type | Nullable | Default | Comment |
+| --- | --- | --- | --- | --- |
+| `id` | `serial` | no |  |  |
+| `email` | `text` | no |  | Login \| contact address |
+
+**Primary key:** `id`
+
+**Indexes**
+
+- `users_email_key`: `email` (unique)
+"#
+        );
+        assert_eq!(
+            markdown,
+            generate_with_backend("markdown", &cfg, false).unwrap()
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use super::Backend;
+use crate::ir::{Config, TableSpec};
+
+use anyhow::Result;
+
+/// Renders tables and their foreign keys as a Mermaid `erDiagram`. Output is
+/// sorted and carries no timestamp so that diffs stay stable.
+pub struct MermaidBackend;
+
+impl Backend for MermaidBackend {
+    fn name(&self) -> &'static str {
+        "mermaid"
+    }
+    fn file_extension(&self) -> &'static str {
+        "mmd"
+    }
+    fn content_type(&self) -> &'static str {
+        "text/vnd.mermaid"
+    }
+    fn generate(&self, cfg: &Config, _strict: bool) -> Result<String> {
+        let mut tables: Vec<&TableSpec> = cfg.tables.iter().collect();
+        tables.sort_by_key(|t| entity_name(t));
+
+        let mut out = String::from("erDiagram\n");
+        for t in &tables {
+            out.push_str(&entity(t));
+        }
+
+        let mut relationships: Vec<String> =
+            tables.iter().flat_map(|t| relationships(t, cfg)).collect();
+        relationships.sort();
+        for r in relationships {
+            out.push_str(&format!("    {r}\n"));
+        }
+        Ok(out)
+    }
+}
+
+/// The entity name for a table: its SQL name, prefixed with the schema
+/// outside `public`.
+fn entity_name(t: &TableSpec) -> String {
+    let name = t.alt_name.as_deref().unwrap_or(&t.name);
+    match t.schema.as_deref() {
+        Some(s) if s != "public" => sanitize(&format!("{s}_{name}")),
+        _ => sanitize(name),
+    }
+}
+
+fn entity(t: &TableSpec) -> String {
+    let mut out = format!("    {} {{\n", entity_name(t));
+    for c in &t.columns {
+        let mut keys = Vec::new();
+        if t.primary_key
+            .as_ref()
+            .is_some_and(|pk| pk.columns.contains(&c.name))
+        {
+            keys.push("PK");
+        }
+        if t.foreign_keys.iter().any(|fk| fk.columns.contains(&c.name)) {
+            keys.push("FK");
+        }
+        if t.indexes
+            .iter()
+            .any(|ix| ix.unique && ix.columns == [c.name.clone()])
+        {
+            keys.push("UK");
+        }
+        let mut line = format!("        {} {}", sanitize(&c.r#type), sanitize(&c.name));
+        if !keys.is_empty() {
+            line.push_str(&format!(" {}", keys.join(", ")));
+        }
+        if let Some(comment) = &c.comment {
+            line.push_str(&format!(" \"{}\"", comment.replace('"', "'")));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.push_str("    }\n");
+    out
+}
+
+/// One relationship line per foreign key, from the referenced table to `t`.
+/// The referenced side is exactly one row, or zero or one when the key
+/// columns are nullable; the referencing side is many rows, or at most one
+/// when the key columns are unique.
+fn relationships(t: &TableSpec, cfg: &Config) -> Vec<String> {
+    t.foreign_keys
+        .iter()
+        .map(|fk| {
+            let parent = cfg
+                .tables
+                .iter()
+                .find(|other| {
+                    (other.name == fk.ref_table || other.alt_name.as_ref() == Some(&fk.ref_table))
+                        && other.schema.as_deref().unwrap_or("public")
+                            == fk.ref_schema.as_deref().unwrap_or("public")
+                })
+                .map(entity_name)
+                .unwrap_or_else(|| sanitize(&fk.ref_table));
+            let nullable = t
+                .columns
+                .iter()
+                .any(|c| fk.columns.contains(&c.name) && c.nullable);
+            let unique = t
+                .primary_key
+                .as_ref()
+                .is_some_and(|pk| pk.columns == fk.columns)
+                || t.indexes
+                    .iter()
+                    .any(|ix| ix.unique && ix.columns == fk.columns);
+            let left = if nullable { "|o" } else { "||" };
+            let right = if unique { "o|" } else { "o{" };
+            let label = fk.name.clone().unwrap_or_else(|| fk.columns.join(", "));
+            format!(
+                "{parent} {left}--{right} {} : \"{}\"",
+                entity_name(t),
+                label.replace('"', "'")
+            )
+        })
+        .collect()
+}
+
+/// Mermaid names and types cannot contain spaces or punctuation other than
+/// `_`, `-`, `()` and `[]`, so `numeric(10,2)` becomes `numeric(10_2)`.
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "_-()[]".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generate_with_backend;
+    use crate::test_support::load_hcl;
+
+    #[test]
+    fn entities_and_relationships() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "id" {
+                type     = "serial"
+                nullable = false
+              }
+              column "email" {
+                type     = "varchar(255)"
+                nullable = false
+              }
+              column "balance" {
+                type = "numeric(10,2)"
+              }
+              primary_key {
+                columns = ["id"]
+              }
+              index "users_email_key" {
+                columns = ["email"]
+                unique  = true
+              }
+            }
+
+            table "posts" {
+              column "id" {
+                type     = "serial"
+                nullable = false
+              }
+              column "author_id" {
+                type     = "integer"
+                nullable = false
+              }
+              column "editor_id" {
+                type = "integer"
+              }
+              column "published_at" {
+                type = "timestamp with time zone"
+              }
+              primary_key {
+                columns = ["id"]
+              }
+              foreign_key {
+                columns = ["author_id"]
+                ref {
+                  table   = "users"
+                  columns = ["id"]
+                }
+              }
+              foreign_key {
+                name    = "posts_editor_fk"
+                columns = ["editor_id"]
+                ref {
+                  table   = "users"
+                  columns = ["id"]
+                }
+              }
+            }
+            "#,
+        );
+        let diagram = generate_with_backend("mermaid", &cfg, false).unwrap();
+        assert_eq!(
+            diagram,
+            r#"erDiagram
+    posts {
+        serial id PK
+        integer author_id FK
+        integer editor_id FK
+        timestamp_with_time_zone published_at
+    }
+    users {
+        serial id PK
+        varchar(255) email UK
+        numeric(10_2) balance
+    }
+    users |o--o{ posts : "posts_editor_fk"
+    users ||--o{ posts : "author_id"
+"#
+        );
+        assert_eq!(
+            diagram,
+            generate_with_backend("mermaid", &cfg, false).unwrap()
+        );
+    }
+}
@@ -1,17 +1,114 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use chrono::Local;
 use std::collections::HashMap;
 
 use crate::ir::Config;
+use crate::provider::postgres::version::PgVersion;
 
 pub mod json;
+pub mod markdown;
+pub mod mermaid;
+pub mod mysql;
 pub mod postgres;
 pub mod prisma;
+pub mod yaml;
 
 pub trait Backend {
     fn name(&self) -> &'static str;
     fn file_extension(&self) -> &'static str;
+    /// MIME type of the generated artifact, for tooling that serves or
+    /// uploads it.
+    fn content_type(&self) -> &'static str {
+        "text/plain"
+    }
+    /// Whether the artifact is binary. Textual artifacts are UTF-8 without a
+    /// byte order mark and may be printed to stdout.
+    fn is_binary(&self) -> bool {
+        false
+    }
     fn generate(&self, cfg: &Config, strict: bool) -> Result<String>;
+    /// What [`Backend::generate`] prints after its header, as statements,
+    /// plus warnings about input the backend could not represent. Backends
+    /// that don't split their output return all of it as one transactional
+    /// statement.
+    fn generate_artifact(&self, cfg: &Config, strict: bool) -> Result<GeneratedArtifact> {
+        Ok(GeneratedArtifact {
+            statements: vec![Statement::new(self.generate(cfg, strict)?)],
+            warnings: Vec::new(),
+        })
+    }
+    /// The statements of [`Backend::generate_artifact`], with how each may be
+    /// executed.
+    fn statements(&self, cfg: &Config, strict: bool) -> Result<Vec<Statement>> {
+        Ok(self.generate_artifact(cfg, strict)?.statements)
+    }
+    /// Generate the statements that undo [`Backend::generate`]. Backends that
+    /// cannot tear a schema down keep the default, which errors.
+    fn generate_down(&self, _cfg: &Config, _strict: bool) -> Result<String> {
+        bail!("backend '{}' does not support down migrations", self.name())
+    }
+    /// Like [`Backend::generate`], but wrapped in a single transaction.
+    /// Statements that cannot run in one follow the `COMMIT`.
+    fn generate_in_transaction(&self, _cfg: &Config, _strict: bool) -> Result<String> {
+        bail!(
+            "backend '{}' does not support wrapping output in a transaction",
+            self.name()
+        )
+    }
+}
+
+/// One step of a generated migration: a statement, or a few that belong
+/// together, and how it may be executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    pub sql: String,
+    /// Whether it may run inside a transaction block. False for statements
+    /// such as `CREATE INDEX CONCURRENTLY` and `ALTER TYPE ... ADD VALUE`.
+    pub transactional: bool,
+    /// Whether it builds without blocking writes (`CONCURRENTLY`).
+    pub concurrent: bool,
+}
+
+impl Statement {
+    pub fn new(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            transactional: true,
+            concurrent: false,
+        }
+    }
+
+    pub fn non_transactional(sql: impl Into<String>) -> Self {
+        Self {
+            transactional: false,
+            ..Self::new(sql)
+        }
+    }
+
+    /// A concurrent build, which can never run in a transaction block.
+    pub fn concurrent(sql: impl Into<String>) -> Self {
+        Self {
+            concurrent: true,
+            ..Self::non_transactional(sql)
+        }
+    }
+}
+
+/// The structured result of [`Backend::generate_artifact`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GeneratedArtifact {
+    pub statements: Vec<Statement>,
+    /// Input that was skipped or approximated, e.g. a function on a backend
+    /// that has no functions.
+    pub warnings: Vec<String>,
+}
+
+/// Join statements the way backends print them, each followed by a blank line.
+pub fn join_statements(statements: &[Statement]) -> String {
+    statements
+        .iter()
+        .map(|s| format!("{}\n\n", s.sql))
+        .collect()
 }
 
 /// Registry for managing backends provided by providers.
@@ -99,9 +196,17 @@ pub fn get_default_backend_registry() -> BackendRegistry {
         }
     }
 
-    // Register standalone backends (json, prisma)
-    registry.register(Box::new(json::JsonBackend));
-    registry.register(Box::new(prisma::PrismaBackend));
+    // Register standalone backends (json, markdown, mermaid, mysql, prisma, yaml)
+    registry.register(Box::new(json::JsonBackend { pretty: true }));
+    registry.register_alias(
+        "json-compact",
+        Box::new(json::JsonBackend { pretty: false }),
+    );
+    registry.register(Box::new(markdown::MarkdownBackend));
+    registry.register(Box::new(mermaid::MermaidBackend));
+    registry.register(Box::new(mysql::MySqlBackend));
+    registry.register(Box::new(prisma::PrismaBackend::default()));
+    registry.register(Box::new(yaml::YamlBackend));
 
     registry
 }
@@ -113,9 +218,95 @@ pub fn get_backend(name: &str) -> Option<Box<dyn Backend>> {
     // We need to return a Box<dyn Backend>, so we create backends on-demand
     // This matches the original behavior and avoids lifetime issues
     match name.to_lowercase().as_str() {
-        "postgres" | "pg" => Some(Box::new(postgres::PostgresBackend)),
-        "json" => Some(Box::new(json::JsonBackend)),
-        "prisma" => Some(Box::new(prisma::PrismaBackend)),
+        "postgres" | "pg" => Some(Box::new(postgres::PostgresBackend::default())),
+        "json" => Some(Box::new(json::JsonBackend { pretty: true })),
+        "json-compact" => Some(Box::new(json::JsonBackend { pretty: false })),
+        "markdown" | "md" => Some(Box::new(markdown::MarkdownBackend)),
+        "mermaid" => Some(Box::new(mermaid::MermaidBackend)),
+        "mysql" => Some(Box::new(mysql::MySqlBackend)),
+        "prisma" => Some(Box::new(prisma::PrismaBackend::default())),
+        "yaml" | "yml" => Some(Box::new(yaml::YamlBackend)),
         _ => None,
     }
 }
+
+/// Like [`get_backend`], but the PostgreSQL backend generates SQL for
+/// `target_version`. Other backends ignore it.
+pub fn get_backend_for_version(name: &str, target_version: PgVersion) -> Option<Box<dyn Backend>> {
+    match name.to_lowercase().as_str() {
+        "postgres" | "pg" => Some(Box::new(postgres::PostgresBackend { target_version })),
+        _ => get_backend(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::load_hcl;
+
+    #[test]
+    fn backends_report_extension_and_content_type() {
+        let postgres = get_backend("postgres").unwrap();
+        assert_eq!(postgres.file_extension(), "sql");
+        assert_eq!(postgres.content_type(), "text/sql");
+        assert!(!postgres.is_binary());
+
+        let json = get_backend("json").unwrap();
+        assert_eq!(json.file_extension(), "json");
+        assert_eq!(json.content_type(), "application/json");
+
+        let registry = get_default_backend_registry();
+        for name in registry.list_backends() {
+            assert!(!registry.get(name).unwrap().is_binary(), "{name}");
+        }
+    }
+
+    #[test]
+    fn statements_carry_transactional_flags() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "email" {
+                type = "text"
+              }
+
+              index "users_email_idx" {
+                columns      = ["email"]
+                concurrently = true
+              }
+            }
+            "#,
+        );
+        let backend = get_backend("postgres").unwrap();
+        let statements = backend.statements(&cfg, false).unwrap();
+
+        let table = statements
+            .iter()
+            .find(|s| s.sql.starts_with("CREATE TABLE"))
+            .unwrap();
+        assert!(table.transactional);
+        assert!(!table.concurrent);
+
+        let index = statements
+            .iter()
+            .find(|s| s.sql.starts_with("CREATE INDEX"))
+            .unwrap();
+        assert!(index.sql.contains("CONCURRENTLY"));
+        assert!(!index.transactional);
+        assert!(index.concurrent);
+
+        let generated = backend.generate(&cfg, false).unwrap();
+        assert!(generated.ends_with(&join_statements(&statements)));
+    }
+
+    #[test]
+    fn unsplit_backends_return_one_statement() {
+        let cfg = Config::default();
+        let json = get_backend("json").unwrap();
+        let statements = json.statements(&cfg, false).unwrap();
+        assert_eq!(
+            statements,
+            [Statement::new(json.generate(&cfg, false).unwrap())]
+        );
+    }
+}
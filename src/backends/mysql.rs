@@ -0,0 +1,532 @@
+use super::{Backend, CommentStyle, generate_header_comment};
+use crate::ir::{ColumnSpec, Config, EnumSpec, IndexSpec, TableSpec};
+use crate::passes::validate::find_enum_for_type;
+
+use anyhow::{Result, bail};
+use log::warn;
+
+/// Renders the subset of the config MySQL understands: schemas, tables with
+/// their keys, indexes and checks, and enums inlined as `ENUM(...)` columns.
+pub struct MySqlBackend;
+
+impl Backend for MySqlBackend {
+    fn name(&self) -> &'static str {
+        "mysql"
+    }
+    fn file_extension(&self) -> &'static str {
+        "sql"
+    }
+    fn content_type(&self) -> &'static str {
+        "text/sql"
+    }
+    fn generate(&self, cfg: &Config, strict: bool) -> Result<String> {
+        let skipped = unsupported(cfg);
+        if !skipped.is_empty() {
+            let list = skipped.join(", ");
+            if strict {
+                bail!("mysql backend cannot render {list}");
+            }
+            warn!("mysql: skipping {list}");
+        }
+
+        let mut out = generate_header_comment("MySQL", CommentStyle::Sql);
+        let mut statements = Vec::new();
+        for s in &cfg.schemas {
+            let name = s.alt_name.as_deref().unwrap_or(&s.name);
+            if name != "public" {
+                statements.push(format!("CREATE SCHEMA IF NOT EXISTS {};", ident(name)));
+            }
+        }
+        for t in &cfg.tables {
+            statements.push(table_sql(t, cfg, strict)?);
+        }
+
+        let has_foreign_keys = cfg.tables.iter().any(|t| !t.foreign_keys.is_empty());
+        if has_foreign_keys {
+            // Tables may reference ones created later in the file.
+            out.push_str("SET FOREIGN_KEY_CHECKS = 0;\n\n");
+        }
+        out.push_str(&statements.join("\n\n"));
+        out.push('\n');
+        if has_foreign_keys {
+            out.push_str("\nSET FOREIGN_KEY_CHECKS = 1;\n");
+        }
+        Ok(out)
+    }
+}
+
+/// One `N kind(s)` entry per resource kind MySQL has no equivalent for.
+fn unsupported(cfg: &Config) -> Vec<String> {
+    let exclusions: usize = cfg.tables.iter().map(|t| t.exclusions.len()).sum();
+    let kinds = [
+        ("policy", cfg.policies.len()),
+        ("domain", cfg.domains.len()),
+        ("event trigger", cfg.event_triggers.len()),
+        ("composite type", cfg.types.len()),
+        ("exclusion constraint", exclusions),
+        ("extension", cfg.extensions.len()),
+        ("sequence", cfg.sequences.len()),
+        ("view", cfg.views.len()),
+        ("materialized view", cfg.materialized.len()),
+        ("function", cfg.functions.len()),
+        ("procedure", cfg.procedures.len()),
+        ("aggregate", cfg.aggregates.len()),
+        ("operator", cfg.operators.len()),
+        ("trigger", cfg.triggers.len()),
+        ("rule", cfg.rules.len()),
+        ("collation", cfg.collations.len()),
+        ("statistics", cfg.statistics.len()),
+        ("role", cfg.roles.len()),
+        ("tablespace", cfg.tablespaces.len()),
+        ("grant", cfg.grants.len()),
+        ("foreign data wrapper", cfg.foreign_data_wrappers.len()),
+        ("foreign server", cfg.foreign_servers.len()),
+        ("foreign table", cfg.foreign_tables.len()),
+        (
+            "text search object",
+            cfg.text_search_dictionaries.len()
+                + cfg.text_search_configurations.len()
+                + cfg.text_search_templates.len()
+                + cfg.text_search_parsers.len(),
+        ),
+        ("publication", cfg.publications.len()),
+        ("subscription", cfg.subscriptions.len()),
+    ];
+    kinds
+        .into_iter()
+        .filter(|(_, n)| *n > 0)
+        .map(|(kind, n)| format!("{n} {kind}(s)"))
+        .collect()
+}
+
+fn table_sql(t: &TableSpec, cfg: &Config, strict: bool) -> Result<String> {
+    let name = t.alt_name.as_deref().unwrap_or(&t.name);
+
+    // Standalone `index` blocks targeting this table become inline keys.
+    let mut indexes: Vec<IndexSpec> = t.indexes.clone();
+    for ix in &cfg.indexes {
+        if ix.table == t.name || ix.table == name {
+            indexes.push(IndexSpec {
+                name: Some(ix.name.clone()),
+                columns: ix.columns.clone(),
+                expressions: ix.expressions.clone(),
+                r#where: ix.r#where.clone(),
+                orders: ix.orders.clone(),
+                operator_classes: ix.operator_classes.clone(),
+                unique: ix.unique,
+                nulls_not_distinct: ix.nulls_not_distinct,
+                concurrently: ix.concurrently,
+                method: ix.method.clone(),
+                storage_parameters: ix.storage_parameters.clone(),
+                tablespace: ix.tablespace.clone(),
+            });
+        }
+    }
+    if indexes.iter().any(|ix| ix.r#where.is_some()) {
+        if strict {
+            bail!(
+                "mysql backend cannot render partial indexes (table '{}')",
+                t.name
+            );
+        }
+        warn!("mysql: skipping partial indexes on table '{}'", t.name);
+        indexes.retain(|ix| ix.r#where.is_none());
+    }
+
+    // MySQL cannot index TEXT without a prefix length, so keyed text
+    // columns become VARCHAR(255).
+    let keyed: Vec<&String> = t
+        .primary_key
+        .iter()
+        .flat_map(|pk| &pk.columns)
+        .chain(indexes.iter().flat_map(|ix| &ix.columns))
+        .chain(t.foreign_keys.iter().flat_map(|fk| &fk.columns))
+        .collect();
+
+    let mut lines = Vec::new();
+    for c in &t.columns {
+        let is_keyed = keyed.contains(&&c.name);
+        lines.push(column_sql(c, t, &cfg.enums, is_keyed, strict)?);
+    }
+    if let Some(pk) = &t.primary_key {
+        lines.push(format!("PRIMARY KEY ({})", ident_list(&pk.columns)));
+    }
+    for ix in &indexes {
+        let key_name = ix.name.clone().unwrap_or_else(|| {
+            let suffix = if ix.unique { "uniq" } else { "idx" };
+            format!("{}_{}_{}", name, ix.columns.join("_"), suffix)
+        });
+        let parts = ix
+            .columns
+            .iter()
+            .map(|c| ident(c))
+            .chain(ix.expressions.iter().map(|e| format!("({e})")))
+            .enumerate()
+            .map(|(i, part)| match ix.orders.get(i) {
+                Some(ord) if !ord.is_empty() => format!("{part} {ord}"),
+                _ => part,
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let kind = if ix.unique { "UNIQUE KEY" } else { "KEY" };
+        lines.push(format!("{kind} {} ({parts})", ident(&key_name)));
+    }
+    for check in &t.checks {
+        let constraint = match &check.name {
+            Some(n) => format!("CONSTRAINT {} ", ident(n)),
+            None => String::new(),
+        };
+        lines.push(format!("{constraint}CHECK ({})", check.expression));
+    }
+    for fk in &t.foreign_keys {
+        let ref_table = cfg
+            .tables
+            .iter()
+            .find(|other| other.name == fk.ref_table)
+            .and_then(|other| other.alt_name.clone())
+            .unwrap_or_else(|| fk.ref_table.clone());
+        let mut line = String::new();
+        if let Some(n) = &fk.name {
+            line.push_str(&format!("CONSTRAINT {} ", ident(n)));
+        }
+        line.push_str(&format!(
+            "FOREIGN KEY ({}) REFERENCES {} ({})",
+            ident_list(&fk.columns),
+            qualified(fk.ref_schema.as_deref(), &ref_table),
+            ident_list(&fk.ref_columns)
+        ));
+        if let Some(od) = &fk.on_delete {
+            line.push_str(&format!(" ON DELETE {od}"));
+        }
+        if let Some(ou) = &fk.on_update {
+            line.push_str(&format!(" ON UPDATE {ou}"));
+        }
+        lines.push(line);
+    }
+    if t.partition_by.is_some() {
+        warn!("mysql: ignoring partitioning of table '{}'", t.name);
+    }
+
+    let body = lines
+        .iter()
+        .map(|l| format!("  {l}"))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    let mut sql = format!(
+        "CREATE TABLE IF NOT EXISTS {} (\n{body}\n)",
+        qualified(t.schema.as_deref(), name)
+    );
+    if let Some(comment) = &t.comment {
+        sql.push_str(&format!(" COMMENT = {}", literal(comment)));
+    }
+    sql.push(';');
+    Ok(sql)
+}
+
+fn column_sql(
+    c: &ColumnSpec,
+    t: &TableSpec,
+    enums: &[EnumSpec],
+    keyed: bool,
+    strict: bool,
+) -> Result<String> {
+    let ty = match find_enum_for_type(enums, &c.r#type, t.schema.as_deref()) {
+        Some(e) => {
+            let values = e
+                .values
+                .iter()
+                .map(|v| literal(v))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("ENUM({values})")
+        }
+        // `db_type` is a PostgreSQL type too, so it is mapped the same way.
+        None => mysql_type(c.db_type.as_deref().unwrap_or(&c.r#type), keyed, strict)
+            .map_err(|e| e.context(format!("table '{}' column '{}'", t.name, c.name)))?,
+    };
+
+    let mut sql = format!("{} {ty}", ident(&c.name));
+    if !c.nullable {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = c.default.as_deref().and_then(mysql_default) {
+        sql.push_str(&format!(" DEFAULT {default}"));
+    }
+    if let Some(comment) = &c.comment {
+        sql.push_str(&format!(" COMMENT {}", literal(comment)));
+    }
+    Ok(sql)
+}
+
+/// Map a PostgreSQL column type onto its closest MySQL equivalent.
+fn mysql_type(pg: &str, keyed: bool, strict: bool) -> Result<String> {
+    let t = pg.trim().to_lowercase();
+    if t.ends_with("[]") {
+        if strict {
+            bail!("mysql has no array types ('{pg}')");
+        }
+        warn!("mysql: storing array type '{pg}' as JSON");
+        return Ok("JSON".into());
+    }
+    let (base, args) = match t.split_once('(') {
+        Some((b, a)) => (b.trim(), Some(format!("({}", a))),
+        None => (t.as_str(), None),
+    };
+    let with_args = |name: &str| format!("{name}{}", args.as_deref().unwrap_or(""));
+    let with_precision = |name: &str| format!("{name}{}", args.as_deref().unwrap_or("(6)"));
+    Ok(match base {
+        "serial" | "serial4" => "INT AUTO_INCREMENT".into(),
+        "bigserial" | "serial8" => "BIGINT AUTO_INCREMENT".into(),
+        "smallserial" | "serial2" => "SMALLINT AUTO_INCREMENT".into(),
+        "integer" | "int" | "int4" => "INT".into(),
+        "bigint" | "int8" => "BIGINT".into(),
+        "smallint" | "int2" => "SMALLINT".into(),
+        "text" | "citext" if keyed => "VARCHAR(255)".into(),
+        "text" | "citext" => "TEXT".into(),
+        "varchar" | "character varying" => with_args("VARCHAR"),
+        "char" | "character" => with_args("CHAR"),
+        "boolean" | "bool" => "BOOLEAN".into(),
+        "uuid" => "CHAR(36)".into(),
+        "json" | "jsonb" => "JSON".into(),
+        "numeric" | "decimal" => with_args("DECIMAL"),
+        "real" | "float4" => "FLOAT".into(),
+        "double precision" | "float8" => "DOUBLE".into(),
+        "bytea" => "BLOB".into(),
+        "date" => "DATE".into(),
+        "time" | "time without time zone" => "TIME".into(),
+        // TIMESTAMP is stored as UTC and converted to the session time zone,
+        // which is what timestamptz means; DATETIME has no time zone.
+        "timestamptz" | "timestamp with time zone" => with_precision("TIMESTAMP"),
+        "timestamp" | "timestamp without time zone" => with_precision("DATETIME"),
+        _ => t.to_uppercase(),
+    })
+}
+
+/// Translate a PostgreSQL default expression. Sequence defaults are dropped
+/// since `AUTO_INCREMENT` already covers them.
+fn mysql_default(pg: &str) -> Option<String> {
+    let d = pg.trim();
+    let lower = d.to_lowercase();
+    if lower.starts_with("nextval(") {
+        return None;
+    }
+    Some(match lower.as_str() {
+        "now()" | "current_timestamp" | "localtimestamp" => "CURRENT_TIMESTAMP(6)".into(),
+        "gen_random_uuid()" | "uuid_generate_v4()" => "(UUID())".into(),
+        // Drop casts such as `'draft'::text`, looking after the closing
+        // quote so a literal like `'a::b'` stays whole.
+        _ => match quoted_literal_end(d) {
+            Some(end) if d[end..].trim_start().starts_with("::") => d[..end].to_string(),
+            _ => d.to_string(),
+        },
+    })
+}
+
+/// The byte index just past the closing quote when `s` starts with a SQL
+/// string literal, where `''` is an escaped quote.
+fn quoted_literal_end(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    if bytes.first() != Some(&b'\'') {
+        return None;
+    }
+    let mut i = 1;
+    while i < bytes.len() {
+        if bytes[i] == b'\'' {
+            if bytes.get(i + 1) == Some(&b'\'') {
+                i += 2;
+                continue;
+            }
+            return Some(i + 1);
+        }
+        i += 1;
+    }
+    None
+}
+
+fn ident(s: &str) -> String {
+    format!("`{}`", s.replace('`', "``"))
+}
+
+fn ident_list(cols: &[String]) -> String {
+    cols.iter().map(|c| ident(c)).collect::<Vec<_>>().join(", ")
+}
+
+/// `schema`.`name`, leaving out PostgreSQL's default `public` schema.
+fn qualified(schema: Option<&str>, name: &str) -> String {
+    match schema {
+        Some(s) if s != "public" => format!("{}.{}", ident(s), ident(name)),
+        _ => ident(name),
+    }
+}
+
+fn literal(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generate_with_backend;
+    use crate::test_support::load_hcl;
+
+    #[test]
+    fn users_table() {
+        let cfg = load_hcl(
+            r#"
+            enum "role" {
+              values = ["user", "admin"]
+            }
+
+            table "users" {
+              column "id" {
+                type     = "serial"
+                nullable = false
+              }
+              column "email" {
+                type     = "text"
+                nullable = false
+              }
+              column "bio" {
+                type = "text"
+              }
+              column "role" {
+                type     = "role"
+                nullable = false
+                default  = "'user'::role"
+              }
+              column "created_at" {
+                type     = "timestamptz"
+                nullable = false
+                default  = "now()"
+              }
+              primary_key {
+                columns = ["id"]
+              }
+              index "users_email_key" {
+                columns = ["email"]
+                unique  = true
+              }
+            }
+
+            table "posts" {
+              column "id" {
+                type     = "bigserial"
+                nullable = false
+              }
+              column "author_id" {
+                type     = "integer"
+                nullable = false
+              }
+              primary_key {
+                columns = ["id"]
+              }
+              foreign_key {
+                columns = ["author_id"]
+                ref {
+                  table   = "users"
+                  columns = ["id"]
+                }
+                on_delete = "CASCADE"
+              }
+            }
+            "#,
+        );
+        let sql = generate_with_backend("mysql", &cfg, true).unwrap();
+        let body = &sql[sql.find("SET FOREIGN_KEY_CHECKS").unwrap()..];
+        assert_eq!(
+            body,
+            "SET FOREIGN_KEY_CHECKS = 0;
+
+CREATE TABLE IF NOT EXISTS `users` (
+  `id` INT AUTO_INCREMENT NOT NULL,
+  `email` VARCHAR(255) NOT NULL,
+  `bio` TEXT,
+  `role` ENUM('user', 'admin') NOT NULL DEFAULT 'user',
+  `created_at` TIMESTAMP(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `users_email_key` (`email`)
+);
+
+CREATE TABLE IF NOT EXISTS `posts` (
+  `id` BIGINT AUTO_INCREMENT NOT NULL,
+  `author_id` INT NOT NULL,
+  PRIMARY KEY (`id`),
+  FOREIGN KEY (`author_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+);
+
+SET FOREIGN_KEY_CHECKS = 1;
+"
+        );
+        assert!(sql.starts_with("-- WARNING: This file is auto-generated"));
+    }
+
+    #[test]
+    fn db_types_and_cast_defaults_are_translated() {
+        let cfg = load_hcl(
+            r#"
+            table "events" {
+              column "happened_at" {
+                type    = "timestamptz"
+                db_type = "timestamptz(3)"
+              }
+              column "email" {
+                type    = "text"
+                db_type = "citext"
+              }
+              column "payload" {
+                type    = "json"
+                db_type = "jsonb"
+              }
+              column "label" {
+                type    = "text"
+                default = "'a::b'::text"
+              }
+              column "quoted" {
+                type    = "text"
+                default = "'it''s'::text"
+              }
+            }
+            "#,
+        );
+        let sql = generate_with_backend("mysql", &cfg, false).unwrap();
+        for expected in [
+            "`happened_at` TIMESTAMP(3),",
+            "`email` TEXT,",
+            "`payload` JSON,",
+            "`label` TEXT DEFAULT 'a::b',",
+            "`quoted` TEXT DEFAULT 'it''s'\n",
+        ] {
+            assert!(sql.contains(expected), "missing {expected}:\n{sql}");
+        }
+    }
+
+    #[test]
+    fn unsupported_resources_error_only_in_strict_mode() {
+        let cfg = load_hcl(
+            r#"
+            domain "email" {
+              type = "text"
+            }
+
+            table "users" {
+              column "id" {
+                type = "integer"
+              }
+            }
+
+            policy "own_rows" {
+              table = "users"
+              using = "true"
+            }
+            "#,
+        );
+        let err = generate_with_backend("mysql", &cfg, true)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "mysql backend cannot render 1 policy(s), 1 domain(s)");
+
+        let sql = generate_with_backend("mysql", &cfg, false).unwrap();
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS `users` (\n  `id` INT\n);"));
+        assert!(!sql.contains("email"));
+        assert!(!sql.contains("own_rows"));
+    }
+}
@@ -1,11 +1,38 @@
-use super::{Backend, CommentStyle, generate_header_comment};
-use crate::ir::{ColumnSpec, Config, EnumSpec, TableSpec};
+use super::{Backend, CommentStyle, GeneratedArtifact, Statement, generate_header_comment};
+use crate::ir::{ColumnSpec, Config, EnumSpec, ForeignKeySpec, TableSpec, ViewSpec};
 use crate::passes::validate::{find_enum_for_type, is_likely_enum};
 use crate::prisma as ps;
 
 use anyhow::{Result, bail};
+use log::warn;
+use pg_query::NodeEnum;
+use std::collections::HashMap;
 
-pub struct PrismaBackend;
+/// Settings for the `datasource db` and `generator client` blocks written at
+/// the top of the schema.
+#[derive(Debug, Clone)]
+pub struct PrismaBackend {
+    /// Datasource provider, e.g. `postgresql`.
+    pub provider: String,
+    /// Environment variable holding the connection URL.
+    pub url_env: String,
+    /// Generator provider, e.g. `prisma-client-js`.
+    pub generator_provider: String,
+    /// Prisma scalar per Postgres type (lowercase, e.g. `citext` or
+    /// `varchar(20)`), consulted before the built-in mapping.
+    pub type_map: HashMap<String, String>,
+}
+
+impl Default for PrismaBackend {
+    fn default() -> Self {
+        Self {
+            provider: "postgresql".to_string(),
+            url_env: "DATABASE_URL".to_string(),
+            generator_provider: "prisma-client-js".to_string(),
+            type_map: HashMap::new(),
+        }
+    }
+}
 
 impl Backend for PrismaBackend {
     fn name(&self) -> &'static str {
@@ -16,29 +43,187 @@ impl Backend for PrismaBackend {
     }
     fn generate(&self, cfg: &Config, strict: bool) -> Result<String> {
         let header = generate_header_comment("Prisma", CommentStyle::Prisma);
+        let artifact = self.generate_artifact(cfg, strict)?;
+        for w in &artifact.warnings {
+            warn!("prisma: {w}");
+        }
+        Ok(format!("{}{}", header, artifact.statements[0].sql))
+    }
+    fn generate_artifact(&self, cfg: &Config, strict: bool) -> Result<GeneratedArtifact> {
+        let mut warnings = skipped_resources(cfg);
         let mut schema = ps::Schema::default();
+        self.add_config_blocks(&mut schema);
         for e in &cfg.enums {
             schema.enums.push(enum_to_ast(e));
         }
         for t in &cfg.tables {
-            schema.models.push(model_to_ast(t, &cfg.enums, strict)?);
+            schema
+                .models
+                .push(model_to_ast(t, cfg, &self.type_map, strict, &mut warnings)?);
+        }
+        for v in &cfg.views {
+            schema.views.push(view_to_ast(
+                &ViewSource::from(v),
+                cfg,
+                &self.type_map,
+                &mut warnings,
+            ));
         }
-        Ok(format!("{}{}", header, schema.to_string()))
+        for m in &cfg.materialized {
+            let source = ViewSource {
+                name: &m.name,
+                alt_name: m.alt_name.as_deref(),
+                columns: &[],
+                sql: &m.sql,
+                comment: m.comment.as_deref(),
+            };
+            schema
+                .views
+                .push(view_to_ast(&source, cfg, &self.type_map, &mut warnings));
+        }
+        if !schema.views.is_empty() {
+            schema.generators[0].properties.push(ps::ConfigProperty {
+                documentation: Some("Prisma views are a preview feature".to_string()),
+                ..config_property("previewFeatures", "[\"views\"]".to_string())
+            });
+        }
+        add_multi_schema(cfg, &mut schema);
+        Ok(GeneratedArtifact {
+            statements: vec![Statement::new(schema.to_string())],
+            warnings,
+        })
+    }
+}
+
+impl PrismaBackend {
+    fn add_config_blocks(&self, schema: &mut ps::Schema) {
+        schema.datasources.push(ps::ConfigBlock {
+            kind: ps::ConfigBlockKind::Datasource,
+            name: ps::Identifier::from("db"),
+            properties: vec![
+                config_property("provider", format!("\"{}\"", self.provider)),
+                config_property("url", format!("env(\"{}\")", self.url_env)),
+            ],
+            documentation: None,
+        });
+        schema.generators.push(ps::ConfigBlock {
+            kind: ps::ConfigBlockKind::Generator,
+            name: ps::Identifier::from("client"),
+            properties: vec![config_property(
+                "provider",
+                format!("\"{}\"", self.generator_provider),
+            )],
+            documentation: None,
+        });
+    }
+}
+
+fn config_property(name: &str, value: String) -> ps::ConfigProperty {
+    ps::ConfigProperty {
+        name: ps::Identifier::from(name),
+        value: Some(value),
+        documentation: None,
+    }
+}
+
+/// One warning per resource a Prisma schema cannot express. Only tables,
+/// enums, views and materialized views have a Prisma counterpart.
+fn skipped_resources(cfg: &Config) -> Vec<String> {
+    let mut skipped: Vec<(&str, &String)> = Vec::new();
+    skipped.extend(cfg.functions.iter().map(|r| ("function", &r.name)));
+    skipped.extend(cfg.procedures.iter().map(|r| ("procedure", &r.name)));
+    skipped.extend(cfg.aggregates.iter().map(|r| ("aggregate", &r.name)));
+    skipped.extend(cfg.triggers.iter().map(|r| ("trigger", &r.name)));
+    skipped.extend(
+        cfg.event_triggers
+            .iter()
+            .map(|r| ("event_trigger", &r.name)),
+    );
+    skipped.extend(cfg.policies.iter().map(|r| ("policy", &r.name)));
+    skipped.extend(cfg.domains.iter().map(|r| ("domain", &r.name)));
+    skipped.extend(cfg.types.iter().map(|r| ("type", &r.name)));
+    skipped.extend(cfg.sequences.iter().map(|r| ("sequence", &r.name)));
+    skipped.extend(cfg.roles.iter().map(|r| ("role", &r.name)));
+    skipped
+        .into_iter()
+        .map(|(kind, name)| format!("{kind} '{name}' skipped: Prisma schemas cannot express it"))
+        .collect()
+}
+
+/// Prisma's multi-schema support: once any table or enum lives outside
+/// `public`, every model and enum gets `@@schema(...)` and the datasource
+/// lists all schemas in use.
+fn add_multi_schema(cfg: &Config, schema: &mut ps::Schema) {
+    let schema_of = |s: &Option<String>| s.clone().unwrap_or_else(|| "public".to_string());
+    let table_schemas: Vec<String> = cfg.tables.iter().map(|t| schema_of(&t.schema)).collect();
+    let enum_schemas: Vec<String> = cfg.enums.iter().map(|e| schema_of(&e.schema)).collect();
+    let view_schemas: Vec<String> = cfg
+        .views
+        .iter()
+        .map(|v| schema_of(&v.schema))
+        .chain(cfg.materialized.iter().map(|m| schema_of(&m.schema)))
+        .collect();
+    let mut used: Vec<String> = table_schemas
+        .iter()
+        .chain(&enum_schemas)
+        .chain(&view_schemas)
+        .cloned()
+        .collect();
+    used.sort();
+    used.dedup();
+    if used.iter().all(|s| s == "public") {
+        return;
+    }
+
+    for (model, s) in schema.models.iter_mut().zip(table_schemas) {
+        model.attributes.push(ps::BlockAttribute::Schema(s));
+    }
+    for (e, s) in schema.enums.iter_mut().zip(enum_schemas) {
+        e.attributes.push(ps::BlockAttribute::Schema(s));
+    }
+    for (v, s) in schema.views.iter_mut().zip(view_schemas) {
+        v.attributes.push(ps::BlockAttribute::Schema(s));
+    }
+
+    let list = used
+        .iter()
+        .map(|s| format!("\"{s}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    for datasource in &mut schema.datasources {
+        datasource
+            .properties
+            .push(config_property("schemas", format!("[{list}]")));
     }
 }
 
-fn model_to_ast(t: &TableSpec, enums: &[EnumSpec], strict: bool) -> Result<ps::Model> {
+fn model_to_ast(
+    t: &TableSpec,
+    cfg: &Config,
+    type_map: &HashMap<String, String>,
+    strict: bool,
+    warnings: &mut Vec<String>,
+) -> Result<ps::Model> {
     let model_name = to_model_name(t.alt_name.as_ref().unwrap_or(&t.name));
     let mut model = ps::Model {
         name: ps::Identifier::from(model_name),
         fields: Vec::new(),
         attributes: Vec::new(),
-        documentation: None,
+        documentation: model_documentation(t, warnings),
     };
 
     for c in &t.columns {
-        let fields = column_to_fields(c, t, enums, strict)?;
-        model.fields.extend(fields);
+        model
+            .fields
+            .push(column_to_field(c, t, &cfg.enums, type_map, strict)?);
+        // Each relation field follows the last of its foreign key's columns.
+        for fk in t
+            .foreign_keys
+            .iter()
+            .filter(|fk| fk.columns.last() == Some(&c.name))
+        {
+            model.fields.push(relation_field(fk, t));
+        }
     }
 
     for br in &t.back_references {
@@ -73,17 +258,54 @@ fn model_to_ast(t: &TableSpec, enums: &[EnumSpec], strict: bool) -> Result<ps::M
         }
     }
 
-    for ix in &t.indexes {
-        if ix.unique {
-            if ix.columns.len() > 1 {
-                model
-                    .attributes
-                    .push(ps::BlockAttribute::Unique(to_ident_list(&ix.columns)));
-            }
-        } else {
+    // Single-column unique indexes declared on the table are field-level
+    // `@unique`; standalone `index` resources always become block attributes.
+    let schema = t.schema.as_deref().unwrap_or("public");
+    let inline = t.indexes.iter().map(|ix| {
+        (
+            ix.name.as_deref(),
+            ix.unique,
+            &ix.columns,
+            &ix.expressions,
+            ix.method.as_deref(),
+            true,
+        )
+    });
+    let standalone = cfg
+        .indexes
+        .iter()
+        .filter(|ix| ix.table == t.name && ix.schema.as_deref().unwrap_or("public") == schema)
+        .map(|ix| {
+            (
+                Some(ix.name.as_str()),
+                ix.unique,
+                &ix.columns,
+                &ix.expressions,
+                ix.method.as_deref(),
+                false,
+            )
+        });
+    for (name, unique, columns, expressions, method, inline) in inline.chain(standalone) {
+        if !expressions.is_empty() {
+            warnings.push(format!(
+                "index '{}' on table '{}' skipped: Prisma cannot express expression indexes",
+                name.unwrap_or("<unnamed>"),
+                t.name
+            ));
+        } else if let Some(method) = method.filter(|m| !m.eq_ignore_ascii_case("btree")) {
+            warnings.push(format!(
+                "index '{}' on table '{}' skipped: Prisma cannot express {method} indexes",
+                name.unwrap_or("<unnamed>"),
+                t.name
+            ));
+        } else if !unique {
+            model
+                .attributes
+                .push(ps::BlockAttribute::Index(to_ident_list(columns)));
+        } else if columns.len() > 1 || !inline {
             model
                 .attributes
-                .push(ps::BlockAttribute::Index(to_ident_list(&ix.columns)));
+                .push(ps::BlockAttribute::Unique(to_ident_list(columns)));
         }
     }
 
@@ -98,16 +320,190 @@ fn model_to_ast(t: &TableSpec, enums: &[EnumSpec], strict: bool) -> Result<ps::M
     Ok(model)
 }
 
-fn column_to_fields(
+/// The parts of a `view` or `materialized` resource a Prisma view is built
+/// from.
+struct ViewSource<'a> {
+    name: &'a str,
+    alt_name: Option<&'a str>,
+    columns: &'a [String],
+    sql: &'a str,
+    comment: Option<&'a str>,
+}
+
+impl<'a> From<&'a ViewSpec> for ViewSource<'a> {
+    fn from(v: &'a ViewSpec) -> Self {
+        Self {
+            name: &v.name,
+            alt_name: v.alt_name.as_deref(),
+            columns: &v.columns,
+            sql: &v.sql,
+            comment: v.comment.as_deref(),
+        }
+    }
+}
+
+/// A Prisma `view` block. Field names come from the view's `columns` list,
+/// or else from the SELECT list; a field selected straight from a column of
+/// a table in `cfg` gets that column's type, anything else is
+/// `Unsupported("unknown")`.
+fn view_to_ast(
+    v: &ViewSource,
+    cfg: &Config,
+    type_map: &HashMap<String, String>,
+    warnings: &mut Vec<String>,
+) -> ps::View {
+    let parsed = pg_query::parse(v.sql).ok();
+    let selected = parsed.as_ref().map(select_list).unwrap_or_default();
+    let tables = parsed.map(|p| p.tables()).unwrap_or_default();
+    let names: Vec<String> = if v.columns.is_empty() {
+        selected.iter().map(|(name, _)| name.clone()).collect()
+    } else {
+        v.columns.to_vec()
+    };
+    if names.is_empty() {
+        warnings.push(format!(
+            "view '{}': could not infer its columns; set `columns` to list them",
+            v.name
+        ));
+    }
+
+    let fields = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let source = selected.get(i).and_then(|(_, source)| source.as_deref());
+            let field = source.and_then(|source| {
+                cfg.tables
+                    .iter()
+                    .filter(|t| references_table(&tables, t))
+                    .find_map(|t| {
+                        let c = t.columns.iter().find(|c| c.name == source)?;
+                        column_to_field(c, t, &cfg.enums, type_map, false).ok()
+                    })
+            });
+            let (r#type, attributes) = match field {
+                Some(f) => (
+                    f.r#type,
+                    f.attributes
+                        .into_iter()
+                        .filter(|a| matches!(a, ps::FieldAttribute::DbNative(_)))
+                        .collect(),
+                ),
+                None => {
+                    warnings.push(format!(
+                        "view '{}' column '{name}': could not infer its type",
+                        v.name
+                    ));
+                    let r#type = ps::Type {
+                        name: "Unsupported(\"unknown\")".to_string(),
+                        optional: true,
+                        list: false,
+                    };
+                    (r#type, Vec::new())
+                }
+            };
+            ps::Field {
+                name: ps::Identifier::from(name.clone()),
+                r#type,
+                attributes,
+                documentation: None,
+            }
+        })
+        .collect();
+
+    let mut attributes = Vec::new();
+    if let Some(alt) = v.alt_name {
+        attributes.push(ps::BlockAttribute::Map(alt.to_string()));
+    }
+    ps::View {
+        name: ps::Identifier::from(to_model_name(v.alt_name.unwrap_or(v.name))),
+        fields,
+        attributes,
+        documentation: v.comment.map(str::to_string),
+    }
+}
+
+/// Whether `tables` (the relations a view reads) includes table `t`,
+/// qualified or not.
+fn references_table(tables: &[String], t: &TableSpec) -> bool {
+    let name = t.name.to_lowercase();
+    let qualified = format!("{}.{name}", t.schema.as_deref().unwrap_or("public"));
+    tables.iter().any(|r| {
+        let r = r.to_lowercase();
+        r == name || r == qualified
+    })
+}
+
+/// The output name of each item in the SELECT list of `parsed`, with the
+/// column it reads when the item is a plain (possibly qualified) column
+/// reference. Set operations take their names from the leftmost SELECT.
+/// Returns nothing for `*`, or for an unaliased expression, since those
+/// names cannot be known.
+fn select_list(parsed: &pg_query::ParseResult) -> Vec<(String, Option<String>)> {
+    let stmt = parsed.protobuf.stmts.first().and_then(|s| s.stmt.as_ref());
+    let Some(NodeEnum::SelectStmt(select)) = stmt.and_then(|n| n.node.as_ref()) else {
+        return Vec::new();
+    };
+    let mut select = select.as_ref();
+    while let Some(larg) = &select.larg {
+        select = larg;
+    }
+
+    let mut out = Vec::new();
+    for item in &select.target_list {
+        let Some(NodeEnum::ResTarget(target)) = &item.node else {
+            return Vec::new();
+        };
+        let column = match target.val.as_ref().and_then(|v| v.node.as_ref()) {
+            Some(NodeEnum::ColumnRef(c)) => match c.fields.last().and_then(|f| f.node.as_ref()) {
+                Some(NodeEnum::String(s)) => Some(s.sval.clone()),
+                _ => return Vec::new(),
+            },
+            _ => None,
+        };
+        let name = match (target.name.as_str(), &column) {
+            ("", Some(column)) => column.clone(),
+            ("", None) => return Vec::new(),
+            (alias, _) => alias.to_string(),
+        };
+        out.push((name, column));
+    }
+    out
+}
+
+/// The table comment plus one `CHECK: ...` line per check constraint. Prisma
+/// has no way to express checks, so they are kept as documentation rather
+/// than dropped.
+fn model_documentation(t: &TableSpec, warnings: &mut Vec<String>) -> Option<String> {
+    let mut lines: Vec<String> = t.comment.iter().cloned().collect();
+    for check in &t.checks {
+        warnings.push(format!(
+            "check constraint {}on table '{}' cannot be represented; emitting it as a doc comment",
+            check
+                .name
+                .as_ref()
+                .map(|n| format!("'{n}' "))
+                .unwrap_or_default(),
+            t.name
+        ));
+        lines.push(format!("CHECK: {}", check.expression));
+    }
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+fn column_to_field(
     c: &ColumnSpec,
     t: &TableSpec,
     enums: &[EnumSpec],
+    type_map: &HashMap<String, String>,
     strict: bool,
-) -> Result<Vec<ps::Field>> {
+) -> Result<ps::Field> {
+    let found_enum = find_enum_for_type(enums, &c.r#type, t.schema.as_deref());
     let (ptype, db_attr) = {
-        let found_enum = find_enum_for_type(enums, &c.r#type, t.schema.as_deref());
         if let Some(e) = found_enum {
             (e.alt_name.as_deref().unwrap_or(&e.name).to_string(), None)
+        } else if let Some(mapped) = mapped_type(type_map, c) {
+            (mapped.clone(), None)
         } else if strict {
             bail!(
                 "Enum type '{}' not found in HCL and strict mode is enabled",
@@ -122,38 +518,26 @@ fn column_to_fields(
 
     let mut attrs: Vec<ps::FieldAttribute> = Vec::new();
 
-    if let Some(def) = &c.default {
-        let dv = if def.trim().eq_ignore_ascii_case("now()") {
-            ps::DefaultValue::Now
-        } else if def.trim().eq_ignore_ascii_case("uuid_generate_v4()")
-            || def.trim().eq_ignore_ascii_case("gen_random_uuid()")
-        {
-            ps::DefaultValue::Uuid
-        } else if def.to_lowercase().contains("nextval(")
-            || def.to_lowercase().contains("autoincrement")
-        {
-            ps::DefaultValue::AutoIncrement
-        } else {
-            ps::DefaultValue::DbGenerated(def.replace('\\', "\\\\").replace('"', "\\\""))
-        };
-        attrs.push(ps::FieldAttribute::Default(dv));
+    // Prisma sets `@updatedAt` fields itself, so the default is left out.
+    if c.updated_at {
+        attrs.push(ps::FieldAttribute::Raw("@updatedAt".to_string()));
+    } else if let Some(def) = &c.default {
+        attrs.push(ps::FieldAttribute::Default(default_value(
+            def,
+            &ptype,
+            found_enum.is_some(),
+        )));
     }
 
     if let Some(pk) = &t.primary_key {
         if pk.columns.len() == 1 && pk.columns[0] == c.name {
             attrs.push(ps::FieldAttribute::Id);
-            if is_serial(&c.r#type)
-                && !attrs.iter().any(|a| {
-                    matches!(
-                        a,
-                        ps::FieldAttribute::Default(ps::DefaultValue::AutoIncrement)
-                    )
-                })
-            {
-                attrs.push(ps::FieldAttribute::Default(ps::DefaultValue::AutoIncrement));
-            }
         }
     }
+    // `serial` columns are backed by a sequence even without a `default`.
+    if c.default.is_none() && is_serial(&c.r#type) {
+        attrs.push(ps::FieldAttribute::Default(ps::DefaultValue::AutoIncrement));
+    }
 
     if t.indexes
         .iter()
@@ -166,8 +550,7 @@ fn column_to_fields(
         attrs.push(ps::FieldAttribute::DbNative(db));
     }
 
-    let mut fields = Vec::new();
-    fields.push(ps::Field {
+    Ok(ps::Field {
         name: ps::Identifier::from(c.name.clone()),
         r#type: ps::Type {
             name: ptype,
@@ -175,40 +558,169 @@ fn column_to_fields(
             list: false,
         },
         attributes: attrs,
+        documentation: c.comment.clone(),
+    })
+}
+
+/// The user-configured Prisma type for `c`: its `db_type` or `type`,
+/// matched whole (`varchar(20)`) and then without arguments (`varchar`).
+fn mapped_type<'a>(type_map: &'a HashMap<String, String>, c: &ColumnSpec) -> Option<&'a String> {
+    if type_map.is_empty() {
+        return None;
+    }
+    c.db_type.iter().chain([&c.r#type]).find_map(|ty| {
+        let ty = ty.trim().to_lowercase();
+        let base = ty.split('(').next().unwrap_or(&ty).trim();
+        type_map.get(&ty).or_else(|| type_map.get(base))
+    })
+}
+
+/// Maps a SQL column default to Prisma's `@default(...)` argument. Functions
+/// Prisma knows become `now()`, `uuid()` and `autoincrement()`. Literals
+/// (also quoted or with a `::type` cast) are written in the form the field's
+/// Prisma type takes: a number for `Int`, `BigInt`, `Float` and `Decimal`,
+/// `true`/`false` for `Boolean`, an ISO-8601 string for `DateTime`, a quoted
+/// string for `String` and `Json`, and the value for enum fields. Anything
+/// else, including a literal that does not fit the type and any default of
+/// an `Unsupported(...)` field, is kept verbatim as `dbgenerated("...")`.
+fn default_value(def: &str, ptype: &str, is_enum: bool) -> ps::DefaultValue {
+    let trimmed = def.trim();
+    let lower = trimmed.to_lowercase();
+    match lower.as_str() {
+        _ if ptype.starts_with("Unsupported(") => {
+            return ps::DefaultValue::DbGenerated(escape_string(trimmed));
+        }
+        "now()" | "current_timestamp" | "current_timestamp()" => return ps::DefaultValue::Now,
+        "gen_random_uuid()" | "uuid_generate_v4()" => return ps::DefaultValue::Uuid,
+        "autoincrement()" => return ps::DefaultValue::AutoIncrement,
+        s if s.starts_with("nextval(") => return ps::DefaultValue::AutoIncrement,
+        _ => {}
+    }
+
+    // Literals may carry a cast such as `'draft'::text` or `0::bigint`.
+    let literal = match trimmed.rfind("::") {
+        Some(i) if !trimmed[i + 2..].contains('\'') => trimmed[..i].trim(),
+        _ => trimmed,
+    };
+    let quoted = literal
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .filter(|s| !s.replace("''", "").contains('\''))
+        .map(|s| s.replace("''", "'"));
+    let value = quoted.as_deref().unwrap_or(literal);
+
+    let expression = match ptype {
+        _ if is_enum => quoted.as_deref().map(|s| prisma_enum_variant(s).0),
+        "String" | "Json" => quoted
+            .as_deref()
+            .map(|s| format!("\"{}\"", escape_string(s))),
+        "Int" | "BigInt" | "Float" | "Decimal" => {
+            let is_number = value
+                .chars()
+                .all(|ch| ch.is_ascii_digit() || matches!(ch, '-' | '+' | '.' | 'e' | 'E'))
+                && value.parse::<f64>().is_ok()
+                && (matches!(ptype, "Float" | "Decimal") || value.parse::<i64>().is_ok());
+            is_number.then(|| value.to_lowercase())
+        }
+        "Boolean" => (value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false"))
+            .then(|| value.to_lowercase()),
+        "DateTime" => iso_datetime(value).map(|s| format!("\"{s}\"")),
+        _ => None,
+    };
+    match expression {
+        Some(e) => ps::DefaultValue::Expression(e),
+        None => ps::DefaultValue::DbGenerated(escape_string(trimmed)),
+    }
+}
+
+/// `value` as the ISO-8601 timestamp Prisma expects for a `DateTime`
+/// default. Dates and timestamps without an offset are taken as UTC.
+fn iso_datetime(value: &str) -> Option<String> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.to_rfc3339());
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%d %H:%M:%S%.f%:z"] {
+        if let Ok(dt) = chrono::DateTime::parse_from_str(value, format) {
+            return Some(dt.to_rfc3339());
+        }
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"] {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value, format) {
+            return Some(dt.and_utc().to_rfc3339());
+        }
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().to_rfc3339())
+}
+
+/// Escape `s` for use inside a Prisma string literal.
+fn escape_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The relation field for `fk` on the owning model, e.g.
+/// `author User @relation(fields: [authorId], references: [id])`. It is
+/// optional when any of the key columns is nullable.
+fn relation_field(fk: &ForeignKeySpec, t: &TableSpec) -> ps::Field {
+    let optional = t
+        .columns
+        .iter()
+        .any(|c| fk.columns.contains(&c.name) && c.nullable);
+    let rel_attr = ps::RelationAttribute {
+        name: t.relation_name(fk),
+        fields: to_ident_list(&fk.columns),
+        references: to_ident_list(&fk.ref_columns),
+        map: fk.name.clone(),
+        on_delete: fk.on_delete.as_ref().map(|s| map_fk_action(s).to_string()),
+        on_update: fk.on_update.as_ref().map(|s| map_fk_action(s).to_string()),
+    };
+    ps::Field {
+        name: ps::Identifier::from(relation_field_name(fk, t)),
+        r#type: ps::Type {
+            name: to_model_name(&fk.ref_table),
+            optional,
+            list: false,
+        },
+        attributes: vec![ps::FieldAttribute::Relation(rel_attr)],
         documentation: None,
-    });
+    }
+}
 
-    if let Some(fk) = t
+/// The constraint name when set, else the single key column without its
+/// `_id`/`Id` suffix (`authorId` -> `author`), else the referenced table in
+/// lower camel case (`post`). That last form gets the key columns appended
+/// (`user_created_by`) when it would clash with a column or when the table
+/// has several foreign keys to the same table.
+fn relation_field_name(fk: &ForeignKeySpec, t: &TableSpec) -> String {
+    if let Some(name) = &fk.name {
+        return name.clone();
+    }
+    let stripped = match fk.columns.as_slice() {
+        [column] => ["_id", "Id", "_ID"]
+            .iter()
+            .find_map(|suffix| column.strip_suffix(suffix))
+            .filter(|base| !base.is_empty() && !t.columns.iter().any(|c| c.name == *base)),
+        _ => None,
+    };
+    if let Some(base) = stripped {
+        return base.to_string();
+    }
+    let mut chars = fk.ref_table.chars();
+    let base = match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect::<String>(),
+        None => String::new(),
+    };
+    let shared = t
         .foreign_keys
         .iter()
-        .find(|fk| fk.columns.len() == 1 && fk.columns[0] == c.name)
-    {
-        let rel_attr = ps::RelationAttribute {
-            name: fk.back_reference_name.clone(),
-            fields: vec![ps::Identifier::from(c.name.clone())],
-            references: fk
-                .ref_columns
-                .iter()
-                .cloned()
-                .map(ps::Identifier::from)
-                .collect(),
-            map: fk.name.clone(),
-            on_delete: fk.on_delete.as_ref().map(|s| map_fk_action(s).to_string()),
-            on_update: fk.on_update.as_ref().map(|s| map_fk_action(s).to_string()),
-        };
-        fields.push(ps::Field {
-            name: ps::Identifier::from(fk.name.clone().unwrap_or(fk.ref_table.clone())),
-            r#type: ps::Type {
-                name: to_model_name(&fk.ref_table),
-                optional: c.nullable,
-                list: false,
-            },
-            attributes: vec![ps::FieldAttribute::Relation(rel_attr)],
-            documentation: None,
-        });
+        .filter(|other| other.ref_table == fk.ref_table)
+        .count()
+        > 1;
+    if shared || t.columns.iter().any(|c| c.name == base) {
+        format!("{base}_{}", fk.columns.join("_"))
+    } else {
+        base
     }
-
-    Ok(fields)
 }
 
 fn enum_to_ast(e: &EnumSpec) -> ps::Enum {
@@ -229,7 +741,7 @@ fn enum_to_ast(e: &EnumSpec) -> ps::Enum {
         name: ps::Identifier::from(name),
         values,
         attributes: Vec::new(),
-        documentation: None,
+        documentation: e.comment.clone(),
     }
 }
 
@@ -264,20 +776,8 @@ fn prisma_enum_variant(db_value: &str) -> (String, Option<String>) {
 
 fn prisma_type(pg: &str, db_specific: Option<&str>) -> (String, Option<String>) {
     // If we have a specific database type annotation, use it
-    if let Some(db_type) = db_specific {
-        let dt = db_type.to_lowercase();
-        if dt.starts_with("char(") {
-            return ("String".into(), Some(format!("@db.Char{}", &db_type[4..])));
-        } else if dt.starts_with("varchar(") {
-            return (
-                "String".into(),
-                Some(format!("@db.VarChar{}", &db_type[7..])),
-            );
-        } else if dt == "text" {
-            return ("String".into(), Some("@db.Text".into()));
-        } else if dt == "uuid" {
-            return ("String".into(), Some("@db.Uuid".into()));
-        }
+    if let Some((scalar, attr)) = db_specific.and_then(native_type) {
+        return (scalar.into(), Some(attr));
     }
 
     // Fall back to type-based inference
@@ -318,6 +818,74 @@ fn prisma_type(pg: &str, db_specific: Option<&str>) -> (String, Option<String>)
     }
 }
 
+/// Postgres types with a Prisma native type attribute: the Postgres spelling,
+/// the Prisma scalar it maps to and the `@db.*` attribute name.
+const NATIVE_TYPES: &[(&str, &str, &str)] = &[
+    ("text", "String", "Text"),
+    ("varchar", "String", "VarChar"),
+    ("character varying", "String", "VarChar"),
+    ("char", "String", "Char"),
+    ("character", "String", "Char"),
+    ("bpchar", "String", "Char"),
+    ("uuid", "String", "Uuid"),
+    ("xml", "String", "Xml"),
+    ("inet", "String", "Inet"),
+    ("citext", "String", "Citext"),
+    ("bit", "String", "Bit"),
+    ("varbit", "String", "VarBit"),
+    ("bit varying", "String", "VarBit"),
+    ("boolean", "Boolean", "Boolean"),
+    ("bool", "Boolean", "Boolean"),
+    ("smallint", "Int", "SmallInt"),
+    ("int2", "Int", "SmallInt"),
+    ("integer", "Int", "Integer"),
+    ("int", "Int", "Integer"),
+    ("int4", "Int", "Integer"),
+    ("oid", "Int", "Oid"),
+    ("bigint", "BigInt", "BigInt"),
+    ("int8", "BigInt", "BigInt"),
+    ("real", "Float", "Real"),
+    ("float4", "Float", "Real"),
+    ("double precision", "Float", "DoublePrecision"),
+    ("float8", "Float", "DoublePrecision"),
+    ("numeric", "Decimal", "Decimal"),
+    ("decimal", "Decimal", "Decimal"),
+    ("money", "Decimal", "Money"),
+    ("timestamp", "DateTime", "Timestamp"),
+    ("timestamp without time zone", "DateTime", "Timestamp"),
+    ("timestamptz", "DateTime", "Timestamptz"),
+    ("timestamp with time zone", "DateTime", "Timestamptz"),
+    ("date", "DateTime", "Date"),
+    ("time", "DateTime", "Time"),
+    ("time without time zone", "DateTime", "Time"),
+    ("timetz", "DateTime", "Timetz"),
+    ("time with time zone", "DateTime", "Timetz"),
+    ("json", "Json", "Json"),
+    ("jsonb", "Json", "JsonB"),
+    ("bytea", "Bytes", "ByteA"),
+];
+
+/// Maps a concrete Postgres type such as `numeric(10, 2)` or
+/// `timestamp(3) with time zone` to its Prisma scalar and `@db.*` attribute.
+fn native_type(db_type: &str) -> Option<(&'static str, String)> {
+    let db_type = db_type.trim().to_lowercase();
+    let (base, args) = match (db_type.find('('), db_type.find(')')) {
+        (Some(open), Some(close)) if open < close => {
+            let args: Vec<&str> = db_type[open + 1..close].split(',').map(str::trim).collect();
+            let base = format!("{} {}", &db_type[..open], &db_type[close + 1..]);
+            (base, Some(args.join(",")))
+        }
+        _ => (db_type.clone(), None),
+    };
+    let base = base.split_whitespace().collect::<Vec<_>>().join(" ");
+    let (_, scalar, native) = NATIVE_TYPES.iter().find(|(pg, _, _)| *pg == base)?;
+    let attr = match args {
+        Some(args) => format!("@db.{native}({args})"),
+        None => format!("@db.{native}"),
+    };
+    Some((scalar, attr))
+}
+
 fn parse_length(s: &str, prefix: &str) -> Option<String> {
     if let Some(start) = s.find(prefix) {
         let rest = &s[start + prefix.len()..];
@@ -363,3 +931,826 @@ fn map_fk_action(s: &str) -> &str {
         _ => "NoAction",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::frontend::env::EnvVars;
+    use crate::test_support::load_hcl;
+    use crate::{MapLoader, generate_with_backend, load_config, prisma};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    /// Prisma schema exercising the structures the importer and exporter
+    /// both understand.
+    const FIXTURE: &str = r#"
+enum Role {
+  USER
+  ADMIN
+}
+
+model User {
+  id       Int       @id @default(autoincrement())
+  email    String    @unique
+  name     String?
+  role     Role
+  posts    Post[]
+  comments Comment[]
+}
+
+model Post {
+  id        Int       @id @default(autoincrement())
+  slug      String    @unique
+  title     String
+  published Boolean
+  rating    Float?
+  createdAt DateTime
+  authorId  Int
+  author    User      @relation(fields: [authorId], references: [id])
+  comments  Comment[]
+}
+
+model Comment {
+  id       Int     @id @default(autoincrement())
+  body     String
+  postId   Int
+  post     Post    @relation(fields: [postId], references: [id])
+  authorId Int?
+  author   User?   @relation(fields: [authorId], references: [id])
+}
+"#;
+
+    /// Imports every model of `data.prisma_schema.app` as a table. Scalar
+    /// fields become columns; relation fields become foreign keys whose
+    /// back reference is the list field on the referenced model.
+    fn table_hcl(model: &str) -> String {
+        format!(
+            r#"
+table "{model}" {{
+  dynamic "column" {{
+    for_each = {{ for k, f in data.prisma_schema.app.models.{model}.fields : k => f if !f.type.list && join("", [for m, _ in data.prisma_schema.app.models : m if m == f.type.name]) == "" }}
+    labels   = [each.key]
+    content {{
+      type     = contains(join(" ", each.value.attributes.raw), "@default(autoincrement())") ? "serial" : each.value.type.name == "Int" ? "integer" : each.value.type.name == "String" ? "text" : each.value.type.name == "Boolean" ? "boolean" : each.value.type.name == "DateTime" ? "timestamptz" : each.value.type.name == "Float" ? "double precision" : each.value.type.name
+      nullable = each.value.type.optional
+    }}
+  }}
+
+  primary_key {{
+    columns = [for k, f in data.prisma_schema.app.models.{model}.fields : k if f.is_id]
+  }}
+
+  dynamic "unique" {{
+    for_each = {{ for k, f in data.prisma_schema.app.models.{model}.fields : k => f if f.is_unique }}
+    content {{
+      columns = [each.key]
+    }}
+  }}
+
+  dynamic "foreign_key" {{
+    for_each = {{ for k, f in data.prisma_schema.app.models.{model}.fields : k => f if f.is_relation }}
+    content {{
+      name                = each.key
+      columns             = each.value.attributes.relation.fields
+      back_reference_name = join("", [for k, g in data.prisma_schema.app.models[each.value.type.name].fields : k if g.type.list && g.type.name == "{model}"])
+      ref {{
+        table   = each.value.type.name
+        columns = each.value.attributes.relation.references
+      }}
+    }}
+  }}
+}}
+"#
+        )
+    }
+
+    fn round_trip(source: &str) -> (prisma::Schema, String) {
+        let original = prisma::parse_schema_str(source).unwrap();
+        let mut hcl = r#"
+data "prisma_schema" "app" {
+  file = "/root/schema.prisma"
+}
+"#
+        .to_string();
+        for e in &original.enums {
+            hcl.push_str(&format!(
+                "\nenum \"{0}\" {{\n  values = [for k, v in data.prisma_schema.app.enums.{0}.values : v.name]\n}}\n",
+                e.name
+            ));
+        }
+        for m in &original.models {
+            hcl.push_str(&table_hcl(&m.name.to_string()));
+        }
+
+        let mut files = HashMap::new();
+        files.insert(PathBuf::from("/root/main.hcl"), hcl);
+        files.insert(PathBuf::from("/root/schema.prisma"), source.to_string());
+        let loader = MapLoader { files };
+        let cfg = load_config(
+            &PathBuf::from("/root/main.hcl"),
+            &loader,
+            EnvVars::default(),
+        )
+        .unwrap();
+        crate::validate(&cfg, false).unwrap();
+        let output = generate_with_backend("prisma", &cfg, false).unwrap();
+        (original, output)
+    }
+
+    fn find_model<'a>(schema: &'a prisma::Schema, name: &str) -> &'a prisma::Model {
+        schema
+            .models
+            .iter()
+            .find(|m| m.name.to_string() == name)
+            .unwrap_or_else(|| panic!("model {name} is missing"))
+    }
+
+    fn relation(field: &prisma::Field) -> Option<&prisma::RelationAttribute> {
+        field.attributes.iter().find_map(|a| match a {
+            prisma::FieldAttribute::Relation(rel) => Some(rel),
+            _ => None,
+        })
+    }
+
+    fn has(field: &prisma::Field, attr: fn(&prisma::FieldAttribute) -> bool) -> bool {
+        field.attributes.iter().any(attr)
+    }
+
+    #[test]
+    fn round_trip_preserves_schema_structure() {
+        let (original, output) = round_trip(FIXTURE);
+        let regenerated = prisma::parse_schema_str(&output).unwrap();
+
+        let names = |s: &prisma::Schema| {
+            let mut models: Vec<String> = s.models.iter().map(|m| m.name.to_string()).collect();
+            models.sort();
+            models
+        };
+        assert_eq!(names(&original), names(&regenerated), "{output}");
+
+        for e in &original.enums {
+            let r = regenerated
+                .enums
+                .iter()
+                .find(|r| r.name == e.name)
+                .unwrap_or_else(|| panic!("enum {} is missing:\n{output}", e.name));
+            let values = |e: &prisma::Enum| {
+                e.values
+                    .iter()
+                    .map(|v| v.name.to_string())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(values(e), values(r));
+        }
+
+        for model in &original.models {
+            let regen = find_model(&regenerated, &model.name.to_string());
+            for field in &model.fields {
+                let r = regen
+                    .fields
+                    .iter()
+                    .find(|f| f.name == field.name)
+                    .unwrap_or_else(|| {
+                        panic!("{}.{} is missing:\n{output}", model.name, field.name)
+                    });
+                let context = format!("{}.{}", model.name, field.name);
+                assert_eq!(r.r#type.name, field.r#type.name, "{context}");
+                assert_eq!(r.r#type.optional, field.r#type.optional, "{context}");
+                assert_eq!(r.r#type.list, field.r#type.list, "{context}");
+                for check in [
+                    |a: &prisma::FieldAttribute| matches!(a, prisma::FieldAttribute::Id),
+                    |a: &prisma::FieldAttribute| matches!(a, prisma::FieldAttribute::Unique),
+                ] {
+                    assert_eq!(has(r, check), has(field, check), "{context}");
+                }
+                if let Some(rel) = relation(field).filter(|rel| !rel.fields.is_empty()) {
+                    let r = relation(r).unwrap_or_else(|| panic!("{context} lost @relation"));
+                    assert_eq!(r.fields, rel.fields, "{context}");
+                    assert_eq!(r.references, rel.references, "{context}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_names_relations_after_back_references() {
+        // Known lossy case: the importer names each relation after its back
+        // reference and the foreign key after the relation field, so the
+        // regenerated schema gains `name:` and `map:` arguments.
+        let (_, output) = round_trip(FIXTURE);
+        assert!(
+            output.contains("posts Post[] @relation(name: \"posts\")"),
+            "{output}"
+        );
+        assert!(
+            output.contains(
+                "author User @relation(name: \"posts\", fields: [authorId], references: [id], map: \"author\")"
+            ),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn artifact_reports_skipped_functions() {
+        let cfg = load_hcl(
+            r#"
+            function "touch" {
+              language = "sql"
+              returns  = "integer"
+              body     = "SELECT 1"
+            }
+
+            table "users" {
+              column "id" {
+                type = "integer"
+              }
+            }
+            "#,
+        );
+        let artifact = crate::backends::get_backend("prisma")
+            .unwrap()
+            .generate_artifact(&cfg, false)
+            .unwrap();
+        assert_eq!(
+            artifact.warnings,
+            ["function 'touch' skipped: Prisma schemas cannot express it"]
+        );
+        assert_eq!(artifact.statements.len(), 1);
+        assert!(artifact.statements[0].sql.contains("model Users {"));
+    }
+
+    #[test]
+    fn db_type_becomes_native_type_attribute() {
+        let cfg = load_hcl(
+            r#"
+            table "products" {
+              column "name" {
+                type    = "text"
+                db_type = "varchar(255)"
+              }
+              column "price" {
+                type     = "numeric"
+                db_type  = "NUMERIC(10, 2)"
+                nullable = false
+              }
+              column "seen_at" {
+                type    = "timestamptz"
+                db_type = "timestamp(3) with time zone"
+              }
+              column "shape" {
+                type    = "text"
+                db_type = "polygon"
+              }
+            }
+            "#,
+        );
+        let schema = generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(schema.contains("name String? @db.VarChar(255)"), "{schema}");
+        assert!(
+            schema.contains("price Decimal @db.Decimal(10,2)"),
+            "{schema}"
+        );
+        assert!(
+            schema.contains("seen_at DateTime? @db.Timestamptz(3)"),
+            "{schema}"
+        );
+        assert!(schema.contains("shape String?\n"), "{schema}");
+    }
+
+    #[test]
+    fn column_defaults_become_prisma_defaults() {
+        let cfg = load_hcl(
+            r#"
+            enum "status" {
+              values = ["draft", "published"]
+            }
+
+            table "posts" {
+              column "id" {
+                type     = "serial"
+                nullable = false
+              }
+              column "legacy_id" {
+                type    = "bigint"
+                default = "nextval('posts_legacy_id_seq'::regclass)"
+              }
+              column "uuid" {
+                type    = "uuid"
+                default = "gen_random_uuid()"
+              }
+              column "created_at" {
+                type    = "timestamptz"
+                default = "now()"
+              }
+              column "updated_at" {
+                type    = "timestamptz"
+                default = "CURRENT_TIMESTAMP"
+              }
+              column "views" {
+                type    = "integer"
+                default = "0"
+              }
+              column "score" {
+                type    = "double precision"
+                default = "-1.5"
+              }
+              column "pinned" {
+                type    = "boolean"
+                default = "FALSE"
+              }
+              column "title" {
+                type    = "text"
+                default = "'it''s \"new\"'::text"
+              }
+              column "status" {
+                type    = "status"
+                default = "'draft'::status"
+              }
+              column "slug" {
+                type    = "text"
+                default = "lower(md5(random()::text))"
+              }
+              column "published_at" {
+                type    = "timestamptz"
+                default = "'2024-01-02 03:04:05+00'::timestamptz"
+              }
+              column "starts_on" {
+                type    = "date"
+                default = "'2024-01-02'"
+              }
+              column "priority" {
+                type    = "integer"
+                default = "'3'::integer"
+              }
+              column "price" {
+                type    = "numeric(10, 2)"
+                default = "'9.99'"
+              }
+              column "archived" {
+                type    = "boolean"
+                default = "'true'::boolean"
+              }
+              column "rank" {
+                type    = "integer"
+                default = "'high'"
+              }
+              primary_key {
+                columns = ["id"]
+              }
+            }
+            "#,
+        );
+        let schema = generate_with_backend("prisma", &cfg, false).unwrap();
+        for expected in [
+            "id Int @id @default(autoincrement())",
+            "legacy_id BigInt? @default(autoincrement()) @db.BigInt",
+            "uuid String? @default(uuid()) @db.Uuid",
+            "created_at DateTime? @default(now()) @db.Timestamptz",
+            "updated_at DateTime? @default(now()) @db.Timestamptz",
+            "views Int? @default(0) @db.Integer",
+            "score Float? @default(-1.5)",
+            "pinned Boolean? @default(false)",
+            "title String? @default(\"it's \\\"new\\\"\")",
+            "status status? @default(draft)",
+            "slug String? @default(dbgenerated(\"lower(md5(random()::text))\"))",
+            "published_at DateTime? @default(\"2024-01-02T03:04:05+00:00\") @db.Timestamptz",
+            "starts_on DateTime? @default(\"2024-01-02T00:00:00+00:00\") @db.Date",
+            "priority Int? @default(3) @db.Integer",
+            "price Decimal? @default(9.99)",
+            "archived Boolean? @default(true)",
+            "rank Int? @default(dbgenerated(\"'high'\")) @db.Integer",
+        ] {
+            assert!(schema.contains(expected), "missing {expected}:\n{schema}");
+        }
+    }
+
+    #[test]
+    fn composite_keys_and_indexes_become_block_attributes() {
+        let cfg = load_hcl(
+            r#"
+            table "memberships" {
+              column "org_id" {
+                type     = "integer"
+                nullable = false
+              }
+              column "user_id" {
+                type     = "integer"
+                nullable = false
+              }
+              column "email" {
+                type = "text"
+              }
+              column "role" {
+                type = "text"
+              }
+              primary_key {
+                columns = ["org_id", "user_id"]
+              }
+              index "memberships_org_email_key" {
+                columns = ["org_id", "email"]
+                unique  = true
+              }
+              index "memberships_email_key" {
+                columns = ["email"]
+                unique  = true
+              }
+            }
+
+            index "memberships_role_idx" {
+              table   = "memberships"
+              columns = ["role", "org_id"]
+            }
+
+            index "memberships_lower_email_idx" {
+              table       = "memberships"
+              columns     = []
+              expressions = ["lower(email)"]
+            }
+            "#,
+        );
+        let artifact = crate::backends::get_backend("prisma")
+            .unwrap()
+            .generate_artifact(&cfg, false)
+            .unwrap();
+        let schema = &artifact.statements[0].sql;
+        assert!(schema.contains("@@id([org_id, user_id])"), "{schema}");
+        assert!(schema.contains("@@unique([org_id, email])"), "{schema}");
+        assert!(schema.contains("@@index([role, org_id])"), "{schema}");
+        assert!(schema.contains("email String? @unique"), "{schema}");
+        assert!(!schema.contains("@@unique([email])"), "{schema}");
+        assert!(!schema.contains("@id "), "{schema}");
+        assert!(!schema.contains("@@index([])"), "{schema}");
+        assert_eq!(
+            artifact.warnings,
+            [
+                "index 'memberships_lower_email_idx' on table 'memberships' skipped: Prisma cannot express expression indexes"
+            ]
+        );
+    }
+
+    #[test]
+    fn foreign_keys_become_relations_on_both_models() {
+        let cfg = load_hcl(
+            r#"
+            table "User" {
+              column "id" {
+                type     = "integer"
+                nullable = false
+              }
+              primary_key {
+                columns = ["id"]
+              }
+            }
+
+            table "Post" {
+              column "id" {
+                type     = "integer"
+                nullable = false
+              }
+              column "authorId" {
+                type     = "integer"
+                nullable = false
+              }
+              primary_key {
+                columns = ["id"]
+              }
+              foreign_key {
+                columns = ["authorId"]
+                ref {
+                  table   = "User"
+                  columns = ["id"]
+                }
+                back_reference_name = "posts"
+              }
+            }
+
+            table "Edition" {
+              column "postId" {
+                type = "integer"
+              }
+              column "authorId" {
+                type = "integer"
+              }
+              foreign_key {
+                columns = ["postId", "authorId"]
+                ref {
+                  table   = "Post"
+                  columns = ["id", "authorId"]
+                }
+              }
+            }
+            "#,
+        );
+        let schema = generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(
+            schema.contains("posts Post[] @relation(name: \"posts\")"),
+            "{schema}"
+        );
+        assert!(
+            schema.contains(
+                "author User @relation(name: \"posts\", fields: [authorId], references: [id])"
+            ),
+            "{schema}"
+        );
+        assert!(
+            schema.contains(
+                "post Post? @relation(fields: [postId, authorId], references: [id, authorId])"
+            ),
+            "{schema}"
+        );
+        assert!(schema.contains("editions Edition[]"), "{schema}");
+    }
+
+    #[test]
+    fn foreign_keys_to_the_same_table_get_distinct_relations() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "id" {
+                type     = "integer"
+                nullable = false
+              }
+              primary_key {
+                columns = ["id"]
+              }
+            }
+
+            table "posts" {
+              column "author_id" { type = "integer" }
+              column "editor_id" { type = "integer" }
+              column "created_by" { type = "integer" }
+              column "reviewed_by" { type = "integer" }
+              foreign_key {
+                columns = ["author_id"]
+                ref {
+                  table   = "users"
+                  columns = ["id"]
+                }
+              }
+              foreign_key {
+                columns = ["editor_id"]
+                ref {
+                  table   = "users"
+                  columns = ["id"]
+                }
+              }
+              foreign_key {
+                columns = ["created_by"]
+                ref {
+                  table   = "users"
+                  columns = ["id"]
+                }
+              }
+              foreign_key {
+                columns = ["reviewed_by"]
+                ref {
+                  table   = "users"
+                  columns = ["id"]
+                }
+              }
+            }
+            "#,
+        );
+        let schema = generate_with_backend("prisma", &cfg, false).unwrap();
+        for (field, columns) in [
+            ("author", "author_id"),
+            ("editor", "editor_id"),
+            ("users_created_by", "created_by"),
+            ("users_reviewed_by", "reviewed_by"),
+        ] {
+            assert!(
+                schema.contains(&format!(
+                    "{field} Users? @relation(name: \"posts_{columns}\", fields: [{columns}], references: [id])"
+                )),
+                "{schema}"
+            );
+            assert!(
+                schema.contains(&format!(
+                    "posts_{columns} Posts[] @relation(name: \"posts_{columns}\")"
+                )),
+                "{schema}"
+            );
+        }
+    }
+
+    #[test]
+    fn type_map_overrides_builtin_types() {
+        let cfg = load_hcl(
+            r#"
+            table "accounts" {
+              column "email" {
+                type     = "citext"
+                db_type  = "citext"
+                nullable = false
+              }
+              column "balance" { type = "numeric(12, 2)" }
+              column "path" { type = "ltree" }
+            }
+            "#,
+        );
+
+        let default = generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(default.contains("email String @db.Citext\n"), "{default}");
+        assert!(default.contains("balance Decimal?\n"), "{default}");
+        assert!(
+            default.contains("path Unsupported(\"ltree\")?\n"),
+            "{default}"
+        );
+
+        let backend = super::PrismaBackend {
+            type_map: HashMap::from([
+                ("citext".to_string(), "String".to_string()),
+                ("numeric".to_string(), "Float".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let schema = crate::backends::Backend::generate(&backend, &cfg, false).unwrap();
+        assert!(schema.contains("email String\n"), "{schema}");
+        assert!(schema.contains("balance Float?\n"), "{schema}");
+        // Unmapped types still fall back to the built-in mapping.
+        assert!(
+            schema.contains("path Unsupported(\"ltree\")?\n"),
+            "{schema}"
+        );
+    }
+
+    #[test]
+    fn updated_at_flag_becomes_prisma_updated_at() {
+        let hcl = |ty: &str| {
+            format!(
+                r#"
+                table "posts" {{
+                  column "updated_at" {{
+                    type       = "{ty}"
+                    nullable   = false
+                    default    = "now()"
+                    updated_at = true
+                  }}
+                  column "touched_at" {{
+                    type    = "timestamptz"
+                    default = "now()"
+                  }}
+                }}
+                "#
+            )
+        };
+        let path = PathBuf::from("/root/main.hcl");
+        let loader = MapLoader {
+            files: HashMap::from([(path.clone(), hcl("timestamptz"))]),
+        };
+        let cfg = load_config(&path, &loader, EnvVars::default()).unwrap();
+        crate::validate(&cfg, false).unwrap();
+        let schema = generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(
+            schema.contains("updated_at DateTime @updatedAt @db.Timestamptz\n"),
+            "{schema}"
+        );
+        // Without the flag a timestamp default stays a default.
+        assert!(
+            schema.contains("touched_at DateTime? @default(now()) @db.Timestamptz\n"),
+            "{schema}"
+        );
+
+        let loader = MapLoader {
+            files: HashMap::from([(path.clone(), hcl("text"))]),
+        };
+        let cfg = load_config(&path, &loader, EnvVars::default()).unwrap();
+        let err = crate::validate(&cfg, false).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("sets updated_at but has type 'text'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn views_become_prisma_views() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "id" {
+                type     = "integer"
+                nullable = false
+              }
+              column "email" { type = "text" }
+              column "active" { type = "boolean" }
+            }
+
+            view "active_users" {
+              comment = "Users that can sign in"
+              sql     = "SELECT u.id, u.email AS address, lower(u.email) AS domain FROM public.users u WHERE u.active"
+            }
+
+            view "user_ids" {
+              columns = ["user_id"]
+              sql     = "SELECT id FROM users"
+            }
+
+            materialized "user_count" {
+              sql = "SELECT count(*) AS total FROM users"
+            }
+            "#,
+        );
+        let artifact = crate::backends::get_backend("prisma")
+            .unwrap()
+            .generate_artifact(&cfg, false)
+            .unwrap();
+        let schema = &artifact.statements[0].sql;
+        assert!(
+            schema.contains(
+                "generator client {\n  provider = \"prisma-client-js\"\n  /// Prisma views are a preview feature\n  previewFeatures = [\"views\"]\n}"
+            ),
+            "{schema}"
+        );
+        assert!(
+            schema.contains(
+                "/// Users that can sign in\nview ActiveUsers {\n  id Int @db.Integer\n  address String?\n  domain Unsupported(\"unknown\")?\n}"
+            ),
+            "{schema}"
+        );
+        assert!(
+            schema.contains("view UserIds {\n  user_id Int @db.Integer\n}"),
+            "{schema}"
+        );
+        assert!(
+            schema.contains("view UserCount {\n  total Unsupported(\"unknown\")?\n}"),
+            "{schema}"
+        );
+        assert_eq!(
+            artifact.warnings,
+            [
+                "view 'active_users' column 'domain': could not infer its type",
+                "view 'user_count' column 'total': could not infer its type",
+            ]
+        );
+    }
+
+    #[test]
+    fn view_columns_come_from_the_parsed_select() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "email" { type = "text" }
+            }
+
+            view "emails" {
+              sql = <<-SQL
+                WITH recent AS (SELECT email FROM users)
+                SELECT 'select a, b from' AS source, "email" FROM recent
+                UNION SELECT 'all', email FROM users
+              SQL
+            }
+            "#,
+        );
+        let artifact = crate::backends::get_backend("prisma")
+            .unwrap()
+            .generate_artifact(&cfg, false)
+            .unwrap();
+        let schema = &artifact.statements[0].sql;
+        assert!(
+            schema
+                .contains("view Emails {\n  source Unsupported(\"unknown\")?\n  email String?\n}"),
+            "{schema}"
+        );
+    }
+
+    #[test]
+    fn prisma_starts_with_datasource_and_generator() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "id" { type = "int" }
+            }
+            "#,
+        );
+        let prisma = crate::generate_with_backend("prisma", &cfg, false).unwrap();
+        let body = &prisma[prisma.find("datasource").unwrap()..];
+        assert!(
+            body.starts_with(
+                "datasource db {\n  provider = \"postgresql\"\n  url = env(\"DATABASE_URL\")\n}\n\ngenerator client {\n  provider = \"prisma-client-js\"\n}\n\nmodel Users {"
+            ),
+            "{prisma}"
+        );
+        assert!(
+            prisma[..prisma.find("datasource").unwrap()]
+                .lines()
+                .all(|l| l.starts_with("//") || l.is_empty()),
+            "{prisma}"
+        );
+
+        let backend = crate::backends::prisma::PrismaBackend {
+            provider: "cockroachdb".into(),
+            url_env: "APP_DB".into(),
+            generator_provider: "prisma-client-py".into(),
+            ..Default::default()
+        };
+        let prisma = crate::backends::Backend::generate(&backend, &cfg, false).unwrap();
+        assert!(
+            prisma.contains("provider = \"cockroachdb\"\n  url = env(\"APP_DB\")"),
+            "{prisma}"
+        );
+        assert!(
+            prisma.contains("generator client {\n  provider = \"prisma-client-py\"\n}"),
+            "{prisma}"
+        );
+    }
+}
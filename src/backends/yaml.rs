@@ -0,0 +1,65 @@
+use anyhow::Result;
+
+use super::Backend;
+use super::json::document;
+use crate::ir::Config;
+
+/// Serializes the same document as the JSON backend, as YAML.
+pub struct YamlBackend;
+
+impl Backend for YamlBackend {
+    fn name(&self) -> &'static str {
+        "yaml"
+    }
+    fn file_extension(&self) -> &'static str {
+        "yaml"
+    }
+    fn content_type(&self) -> &'static str {
+        "application/yaml"
+    }
+    fn generate(&self, cfg: &Config, _strict: bool) -> Result<String> {
+        serde_norway::to_string(&document(self.name(), cfg)?).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backends::get_backend;
+    use crate::test_support::load_hcl;
+
+    #[test]
+    fn yaml_round_trips_to_the_json_document() {
+        let cfg = load_hcl(
+            r#"
+            enum "status" {
+              values = ["active", "inactive"]
+            }
+
+            table "users" {
+              column "id" {
+                type     = "serial"
+                nullable = false
+              }
+              column "status" {
+                type = "status"
+              }
+              primary_key {
+                columns = ["id"]
+              }
+            }
+            "#,
+        );
+
+        let yaml = get_backend("yaml").unwrap();
+        assert_eq!(yaml.file_extension(), "yaml");
+        let parsed: serde_json::Value =
+            serde_norway::from_str(&yaml.generate(&cfg, false).unwrap()).unwrap();
+
+        let json = get_backend("json").unwrap().generate(&cfg, false).unwrap();
+        let mut expected: serde_json::Value = serde_json::from_str(&json).unwrap();
+        expected["backend"] = "yaml".into();
+        assert_eq!(parsed, expected);
+        assert_eq!(parsed["config"]["tables"][0]["name"], "users");
+        assert_eq!(parsed["config"]["enums"][0]["values"][1], "inactive");
+    }
+}
@@ -0,0 +1,136 @@
+//! Synthetic inputs used by the `generate` benchmark.
+//!
+//! Builds a large, self-consistent HCL config in memory so the parse/generate
+//! pipeline can be measured without touching the filesystem.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::MapLoader;
+
+/// Root path the synthetic config is registered under in [`synthetic_loader`].
+pub const SYNTHETIC_ROOT: &str = "/bench/main.hcl";
+
+/// Render an HCL document with `tables` tables. Every table has a handful of
+/// columns, a primary key, an index, an `updated_at` trigger, and (except the
+/// first) a foreign key to the previous table.
+pub fn synthetic_hcl(tables: usize) -> String {
+    let mut out = String::new();
+    out.push_str(
+        r#"schema "app" {}
+
+enum "status" {
+  schema = "app"
+  values = ["active", "inactive", "deleted"]
+}
+
+function "set_updated_at" {
+  schema   = "app"
+  language = "plpgsql"
+  returns  = "trigger"
+  body     = "BEGIN NEW.updated_at = now(); RETURN NEW; END;"
+}
+"#,
+    );
+
+    for i in 0..tables {
+        out.push_str(&format!(
+            r#"
+table "t{i}" {{
+  schema = "app"
+
+  column "id" {{
+    type     = "bigint"
+    nullable = false
+  }}
+  column "name" {{
+    type     = "text"
+    nullable = false
+  }}
+  column "status" {{
+    type    = "app.status"
+    default = "'active'"
+  }}
+  column "payload" {{
+    type = "jsonb"
+  }}
+  column "updated_at" {{
+    type    = "timestamptz"
+    default = "now()"
+  }}
+"#
+        ));
+        if i > 0 {
+            out.push_str(&format!(
+                r#"  column "parent_id" {{
+    type = "bigint"
+  }}
+
+  foreign_key {{
+    columns = ["parent_id"]
+    ref {{
+      schema  = "app"
+      table   = "t{prev}"
+      columns = ["id"]
+    }}
+    on_delete = "CASCADE"
+  }}
+"#,
+                prev = i - 1
+            ));
+        }
+        out.push_str(&format!(
+            r#"
+  primary_key {{
+    columns = ["id"]
+  }}
+
+  index "t{i}_name_idx" {{
+    columns = ["name"]
+  }}
+}}
+
+trigger "t{i}_updated_at" {{
+  schema   = "app"
+  table    = "t{i}"
+  function = "set_updated_at"
+  events   = ["UPDATE"]
+}}
+"#
+        ));
+    }
+    out
+}
+
+/// A [`MapLoader`] containing [`synthetic_hcl`] at [`SYNTHETIC_ROOT`].
+pub fn synthetic_loader(tables: usize) -> MapLoader {
+    MapLoader {
+        files: HashMap::from([(PathBuf::from(SYNTHETIC_ROOT), synthetic_hcl(tables))]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::env::EnvVars;
+    use std::path::Path;
+
+    #[test]
+    fn synthetic_config_has_expected_table_count() {
+        let loader = synthetic_loader(250);
+        let cfg =
+            crate::load_config(Path::new(SYNTHETIC_ROOT), &loader, EnvVars::default()).unwrap();
+        assert_eq!(cfg.tables.len(), 250);
+        assert_eq!(cfg.triggers.len(), 250);
+        assert_eq!(
+            cfg.tables
+                .iter()
+                .map(|t| t.foreign_keys.len())
+                .sum::<usize>(),
+            249
+        );
+        crate::validate(&cfg, false).unwrap();
+        let sql = crate::generate_with_backend("postgres", &cfg, false).unwrap();
+        assert_eq!(sql.matches("CREATE TABLE IF NOT EXISTS").count(), 250);
+    }
+}
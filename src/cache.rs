@@ -0,0 +1,256 @@
+//! On-disk cache that lets `validate` skip projects that have not changed.
+//!
+//! A successful run records the content hash of every file the loader read.
+//! The next run with the same inputs re-reads only those files and, when none
+//! of them changed, replays the recorded result without parsing anything.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Loader;
+use crate::frontend::data_sources::content_hash;
+
+/// Wraps a [`Loader`] and remembers every file read through it.
+pub struct TrackingLoader<'a> {
+    inner: &'a dyn Loader,
+    files: RefCell<Vec<(PathBuf, Option<String>)>>,
+    volatile: Cell<bool>,
+}
+
+impl<'a> TrackingLoader<'a> {
+    pub fn new(inner: &'a dyn Loader) -> Self {
+        Self {
+            inner,
+            files: RefCell::new(Vec::new()),
+            volatile: Cell::new(false),
+        }
+    }
+
+    /// Files read so far with their content hash, `None` when reading failed.
+    pub fn files(&self) -> Vec<(PathBuf, Option<String>)> {
+        self.files.borrow().clone()
+    }
+
+    /// Whether an unpinned URL was fetched. Its body can change without any
+    /// file changing, so such runs cannot be cached.
+    pub fn is_volatile(&self) -> bool {
+        self.volatile.get()
+    }
+}
+
+impl Loader for TrackingLoader<'_> {
+    fn load(&self, path: &Path) -> Result<String> {
+        let result = self.inner.load(path);
+        let mut files = self.files.borrow_mut();
+        if !files.iter().any(|(p, _)| p == path) {
+            let hash = result.as_ref().ok().map(|body| content_hash(body));
+            files.push((path.to_path_buf(), hash));
+        }
+        result
+    }
+
+    fn fetch(&self, url: &str) -> Result<String> {
+        self.volatile.set(true);
+        self.inner.fetch(url)
+    }
+
+    // The pin lives in a tracked file, so the body is covered by its hash.
+    fn fetch_pinned(&self, url: &str, hash: &str) -> Result<String> {
+        self.inner.fetch_pinned(url, hash)
+    }
+}
+
+/// What a cached validation reports again on a hit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedValidation {
+    /// Summary line logged after validating.
+    pub summary: String,
+    /// Text printed to stdout, such as evaluated outputs.
+    pub stdout: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Hash of the fingerprint, which may contain variable values.
+    fingerprint: String,
+    files: Vec<(PathBuf, Option<String>)>,
+    result: CachedValidation,
+}
+
+/// Directory of validation results, one file per input fingerprint.
+///
+/// The fingerprint is chosen by the caller and must cover every input other
+/// than the loaded files: the root path, variables, flags and so on. Only its
+/// hash is written to disk.
+pub struct ValidationCache {
+    dir: PathBuf,
+}
+
+impl ValidationCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn entry_path(&self, fingerprint: &str) -> PathBuf {
+        let hash = content_hash(fingerprint);
+        let hex = hash.trim_start_matches("sha256:");
+        self.dir.join(format!("validate-{}.json", &hex[..16]))
+    }
+
+    /// The recorded result for `fingerprint`, if every file that run read
+    /// still has the same content when read through `loader`.
+    pub fn lookup(&self, fingerprint: &str, loader: &dyn Loader) -> Option<CachedValidation> {
+        let raw = fs::read_to_string(self.entry_path(fingerprint)).ok()?;
+        let entry: Entry = serde_json::from_str(&raw).ok()?;
+        if entry.fingerprint != content_hash(fingerprint) {
+            return None;
+        }
+        let unchanged = entry.files.iter().all(|(path, hash)| {
+            let current = loader.load(path).ok().map(|body| content_hash(&body));
+            current == *hash
+        });
+        unchanged.then_some(entry.result)
+    }
+
+    /// Record a successful validation of the files read through `loader`.
+    /// Runs that fetched unpinned URLs are skipped.
+    pub fn store(
+        &self,
+        fingerprint: &str,
+        loader: &TrackingLoader,
+        result: CachedValidation,
+    ) -> Result<()> {
+        if loader.is_volatile() {
+            return Ok(());
+        }
+        let entry = Entry {
+            fingerprint: content_hash(fingerprint),
+            files: loader.files(),
+            result,
+        };
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.entry_path(fingerprint), serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::env::EnvVars;
+    use crate::{MapLoader, load_config, validate};
+    use std::collections::HashMap;
+
+    #[test]
+    fn unchanged_inputs_hit_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ValidationCache::new(dir.path());
+        let root = PathBuf::from("/root/main.hcl");
+        let mut loader = MapLoader {
+            files: HashMap::from([
+                (
+                    root.clone(),
+                    r#"
+                    module "users" {
+                      source = "/root/users"
+                    }
+                    "#
+                    .to_string(),
+                ),
+                (
+                    PathBuf::from("/root/users/main.hcl"),
+                    r#"
+                    table "users" {
+                      column "id" {
+                        type = "integer"
+                      }
+                    }
+                    "#
+                    .to_string(),
+                ),
+            ]),
+        };
+
+        // Returns whether the run was served from the cache.
+        let run = |loader: &MapLoader, fingerprint: &str| {
+            if cache.lookup(fingerprint, loader).is_some() {
+                return true;
+            }
+            let tracking = TrackingLoader::new(loader);
+            let cfg = load_config(&root, &tracking, EnvVars::default()).unwrap();
+            validate(&cfg, false).unwrap();
+            let result = CachedValidation {
+                summary: format!("{} table(s)", cfg.tables.len()),
+                stdout: String::new(),
+            };
+            cache.store(fingerprint, &tracking, result).unwrap();
+            false
+        };
+
+        assert!(!run(&loader, "strict=false"));
+        assert!(run(&loader, "strict=false"));
+        assert_eq!(
+            cache.lookup("strict=false", &loader).unwrap().summary,
+            "1 table(s)"
+        );
+        assert!(!run(&loader, "strict=true"));
+
+        loader
+            .files
+            .get_mut(Path::new("/root/users/main.hcl"))
+            .unwrap()
+            .push_str("\ntable \"posts\" {}\n");
+        assert!(!run(&loader, "strict=false"));
+        assert!(run(&loader, "strict=false"));
+    }
+
+    #[test]
+    fn unpinned_fetches_are_not_cached() {
+        struct Http(MapLoader);
+        impl Loader for Http {
+            fn load(&self, path: &Path) -> Result<String> {
+                self.0.load(path)
+            }
+            fn fetch(&self, _url: &str) -> Result<String> {
+                Ok("{}".into())
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ValidationCache::new(dir.path());
+        let loader = Http(MapLoader {
+            files: HashMap::new(),
+        });
+        let tracking = TrackingLoader::new(&loader);
+        tracking.fetch("https://example.com/schema.json").unwrap();
+        let result = CachedValidation {
+            summary: String::new(),
+            stdout: String::new(),
+        };
+        cache.store("fp", &tracking, result).unwrap();
+        assert!(cache.lookup("fp", &loader).is_none());
+    }
+
+    #[test]
+    fn fingerprints_are_stored_hashed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ValidationCache::new(dir.path());
+        let loader = MapLoader {
+            files: HashMap::new(),
+        };
+        let tracking = TrackingLoader::new(&loader);
+        let result = CachedValidation {
+            summary: String::new(),
+            stdout: String::new(),
+        };
+        let fingerprint = r#"vars=[("db_password", "hunter2")]"#;
+        cache.store(fingerprint, &tracking, result).unwrap();
+        assert!(cache.lookup(fingerprint, &loader).is_some());
+        let entry = fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap();
+        let raw = fs::read_to_string(entry.path()).unwrap();
+        assert!(!raw.contains("hunter2"), "{raw}");
+    }
+}
@@ -1,5 +1,7 @@
+use crate::backends::{self, Backend, prisma::PrismaBackend};
 use crate::lint::LintSettings;
-use anyhow::{Result, anyhow};
+use crate::provider::postgres::version::PgVersion;
+use anyhow::{Result, anyhow, bail};
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -23,11 +25,81 @@ pub struct Settings {
     /// Default DSN for `dbschema test`
     #[serde(default)]
     pub test_dsn: Option<String>,
+    /// Connection fields used to assemble a DSN when none is given
+    #[serde(default)]
+    pub db: DbConnection,
     /// Lint configuration
     #[serde(default)]
     pub lint: LintSettings,
 }
 
+/// Discrete database connection fields, assembled into a Postgres URL by
+/// [`DbConnection::to_dsn`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct DbConnection {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub name: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+impl DbConnection {
+    /// True when no field is set.
+    pub fn is_empty(&self) -> bool {
+        *self == DbConnection::default()
+    }
+
+    /// Fields set in `other` take precedence over the ones in `self`.
+    pub fn merge(self, other: DbConnection) -> DbConnection {
+        DbConnection {
+            host: other.host.or(self.host),
+            port: other.port.or(self.port),
+            name: other.name.or(self.name),
+            user: other.user.or(self.user),
+            password: other.password.or(self.password),
+        }
+    }
+
+    /// Build a `postgres://` URL, percent-encoding the user, password and
+    /// database name. Returns `None` when no field is set, and an error for a
+    /// password without a user, which a URL cannot express.
+    pub fn to_dsn(&self) -> Result<Option<String>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let host = self.host.as_deref().unwrap_or("localhost");
+        let host = if host.contains(':') {
+            format!("[{host}]")
+        } else {
+            host.to_string()
+        };
+        let mut url = url::Url::parse(&format!("postgres://{host}"))
+            .map_err(|e| anyhow!("invalid database host '{host}': {e}"))?;
+        let invalid = |field: &str| anyhow!("cannot set database {field} for host '{host}'");
+        url.set_port(self.port).map_err(|_| invalid("port"))?;
+        // `Url` leaves `%` alone in user info, which would then be read as
+        // the start of an escape; encode it first.
+        if let Some(user) = &self.user {
+            url.set_username(&user.replace('%', "%25"))
+                .map_err(|_| invalid("user"))?;
+        }
+        match (&self.user, &self.password) {
+            (Some(_), Some(password)) => url
+                .set_password(Some(&password.replace('%', "%25")))
+                .map_err(|_| invalid("password"))?,
+            (None, Some(_)) => bail!("database password is set but user is not"),
+            _ => {}
+        }
+        if let Some(name) = &self.name {
+            url.path_segments_mut()
+                .map_err(|_| invalid("name"))?
+                .push(name);
+        }
+        Ok(Some(url.to_string()))
+    }
+}
+
 /// Configuration for a single target output
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TargetConfig {
@@ -253,6 +325,74 @@ impl TargetConfig {
     pub fn get_exclude_set(&self) -> Result<HashSet<ResourceKind>> {
         parse_resource_kinds(&self.exclude)
     }
+
+    /// Get the PostgreSQL version set by the `target_version` option
+    pub fn target_version(&self) -> Result<Option<PgVersion>> {
+        match self.options.get("target_version") {
+            None => Ok(None),
+            Some(toml::Value::Integer(v)) => u32::try_from(*v)
+                .map_err(|_| anyhow!("invalid PostgreSQL target version {v}"))
+                .and_then(PgVersion::try_from)
+                .map(Some),
+            Some(toml::Value::String(s)) => s.parse().map(Some),
+            Some(other) => Err(anyhow!(
+                "target '{}': target_version must be a number, got {other}",
+                self.name
+            )),
+        }
+    }
+
+    /// Get the Prisma settings from the `datasource_provider`,
+    /// `datasource_url_env`, `generator_provider` and `type_map` options
+    pub fn prisma_backend(&self) -> Result<PrismaBackend> {
+        let mut backend = PrismaBackend::default();
+        for (key, field) in [
+            ("datasource_provider", &mut backend.provider),
+            ("datasource_url_env", &mut backend.url_env),
+            ("generator_provider", &mut backend.generator_provider),
+        ] {
+            match self.options.get(key) {
+                None => {}
+                Some(toml::Value::String(s)) => *field = s.clone(),
+                Some(other) => bail!(
+                    "target '{}': {key} must be a string, got {other}",
+                    self.name
+                ),
+            }
+        }
+        match self.options.get("type_map") {
+            None => {}
+            Some(toml::Value::Table(map)) => {
+                for (pg_type, prisma_type) in map {
+                    let toml::Value::String(prisma_type) = prisma_type else {
+                        bail!(
+                            "target '{}': type_map.{pg_type} must be a string, got {prisma_type}",
+                            self.name
+                        );
+                    };
+                    backend
+                        .type_map
+                        .insert(pg_type.trim().to_lowercase(), prisma_type.clone());
+                }
+            }
+            Some(other) => bail!(
+                "target '{}': type_map must be a table, got {other}",
+                self.name
+            ),
+        }
+        Ok(backend)
+    }
+
+    /// Create the backend for this target with its backend-specific options
+    /// applied
+    pub fn create_backend(&self) -> Result<Box<dyn Backend>> {
+        if self.backend.eq_ignore_ascii_case("prisma") {
+            return Ok(Box::new(self.prisma_backend()?));
+        }
+        let version = self.target_version()?.unwrap_or_default();
+        backends::get_backend_for_version(&self.backend, version)
+            .ok_or_else(|| anyhow!("unknown backend '{}'", self.backend))
+    }
 }
 
 fn parse_resource_kinds(values: &[String]) -> Result<HashSet<ResourceKind>> {
@@ -289,6 +429,58 @@ mod tests {
         assert!("invalid".parse::<ResourceKind>().is_err());
     }
 
+    #[test]
+    fn test_db_connection_to_dsn_encodes_password() {
+        let db = DbConnection {
+            host: Some("db.example.com".into()),
+            port: Some(5433),
+            name: Some("app".into()),
+            user: Some("admin".into()),
+            password: Some("p@ss:w/rd%#?".into()),
+        };
+        let dsn = db.to_dsn().unwrap().unwrap();
+        let parsed = url::Url::parse(&dsn).unwrap();
+        assert_eq!(parsed.host_str(), Some("db.example.com"));
+        assert_eq!(parsed.port(), Some(5433));
+        assert_eq!(parsed.path(), "/app");
+        assert_eq!(parsed.username(), "admin");
+        let password = parsed.password().unwrap();
+        let decoded: Vec<u8> = url::form_urlencoded::parse(format!("p={password}").as_bytes())
+            .next()
+            .map(|(_, v)| v.into_owned().into_bytes())
+            .unwrap();
+        assert_eq!(decoded, b"p@ss:w/rd%#?");
+        assert_eq!(DbConnection::default().to_dsn().unwrap(), None);
+    }
+
+    #[test]
+    fn test_db_connection_rejects_password_without_user() {
+        let db = DbConnection {
+            password: Some("secret".into()),
+            ..Default::default()
+        };
+        let err = db.to_dsn().unwrap_err();
+        assert!(err.to_string().contains("password is set but user is not"));
+    }
+
+    #[test]
+    fn test_db_connection_merge_prefers_other() {
+        let settings = DbConnection {
+            host: Some("settings-host".into()),
+            name: Some("app".into()),
+            ..Default::default()
+        };
+        let cli = DbConnection {
+            host: Some("cli-host".into()),
+            user: Some("me".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.merge(cli).to_dsn().unwrap().as_deref(),
+            Some("postgres://me@cli-host/app")
+        );
+    }
+
     #[test]
     fn test_target_config_include_all() {
         let target = TargetConfig {
@@ -376,4 +568,38 @@ mod tests {
         let err = target.get_include_set().unwrap_err();
         assert!(err.to_string().contains("invalid resource kind"));
     }
+
+    #[test]
+    fn test_target_config_prisma_options() {
+        let config: Config = toml::from_str(
+            r#"
+[[targets]]
+name = "prisma"
+backend = "prisma"
+datasource_provider = "mysql"
+datasource_url_env = "APP_DATABASE_URL"
+type_map = { CITEXT = "String", ltree = "Unsupported(\"ltree\")" }
+
+[[targets]]
+name = "bad"
+backend = "prisma"
+generator_provider = 1
+"#,
+        )
+        .unwrap();
+
+        let backend = config.targets[0].prisma_backend().unwrap();
+        assert_eq!(backend.provider, "mysql");
+        assert_eq!(backend.url_env, "APP_DATABASE_URL");
+        assert_eq!(backend.generator_provider, "prisma-client-js");
+        assert_eq!(backend.type_map["citext"], "String");
+        assert_eq!(backend.type_map["ltree"], "Unsupported(\"ltree\")");
+        assert_eq!(config.targets[0].create_backend().unwrap().name(), "prisma");
+
+        let err = config.targets[1].prisma_backend().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("generator_provider must be a string")
+        );
+    }
 }
@@ -38,6 +38,48 @@ pub struct Config {
     pub tests: Vec<AstTest>,
     pub invariants: Vec<AstInvariant>,
     pub outputs: Vec<AstOutput>,
+    pub sensitive_values: Vec<String>,
+}
+
+impl Config {
+    /// Number of resources in the configuration (outputs excluded).
+    pub fn resource_count(&self) -> usize {
+        self.providers.len()
+            + self.functions.len()
+            + self.procedures.len()
+            + self.aggregates.len()
+            + self.operators.len()
+            + self.triggers.len()
+            + self.rules.len()
+            + self.event_triggers.len()
+            + self.extensions.len()
+            + self.collations.len()
+            + self.sequences.len()
+            + self.schemas.len()
+            + self.enums.len()
+            + self.domains.len()
+            + self.types.len()
+            + self.tables.len()
+            + self.indexes.len()
+            + self.statistics.len()
+            + self.views.len()
+            + self.materialized.len()
+            + self.policies.len()
+            + self.roles.len()
+            + self.tablespaces.len()
+            + self.grants.len()
+            + self.foreign_data_wrappers.len()
+            + self.foreign_servers.len()
+            + self.foreign_tables.len()
+            + self.text_search_dictionaries.len()
+            + self.text_search_configurations.len()
+            + self.text_search_templates.len()
+            + self.text_search_parsers.len()
+            + self.publications.len()
+            + self.subscriptions.len()
+            + self.tests.len()
+            + self.invariants.len()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -52,7 +94,7 @@ pub struct AstFunction {
     pub alt_name: Option<String>,
     pub schema: Option<String>,
     pub language: String,
-    pub parameters: Vec<String>,
+    pub parameters: Vec<AstFunctionParameter>,
     pub returns: String,
     pub replace: bool,
     pub volatility: Option<String>,
@@ -60,9 +102,18 @@ pub struct AstFunction {
     pub security: Option<String>,
     pub cost: Option<f64>,
     pub body: String,
+    pub lint_ignore: Vec<String>,
     pub comment: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct AstFunctionParameter {
+    pub mode: Option<String>,
+    pub name: Option<String>,
+    pub r#type: String,
+    pub default: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct AstProcedure {
     pub name: String,
@@ -113,10 +164,17 @@ pub struct AstTrigger {
     pub table: String,
     pub timing: String,
     pub events: Vec<String>,
+    pub update_columns: Vec<String>,
     pub level: String,
     pub function: String,
     pub function_schema: Option<String>,
     pub when: Option<String>,
+    pub referencing_old_table: Option<String>,
+    pub referencing_new_table: Option<String>,
+    pub constraint: bool,
+    pub deferrable: bool,
+    pub initially_deferred: bool,
+    pub create: crate::ir::TriggerCreate,
     pub comment: Option<String>,
 }
 
@@ -130,6 +188,7 @@ pub struct AstRule {
     pub r#where: Option<String>,
     pub instead: bool,
     pub command: String,
+    pub replace: bool,
     pub comment: Option<String>,
 }
 
@@ -202,6 +261,7 @@ pub struct AstEnum {
     pub alt_name: Option<String>,
     pub schema: Option<String>,
     pub values: Vec<String>,
+    pub evolve: bool,
     pub comment: Option<String>,
 }
 
@@ -239,7 +299,9 @@ pub struct AstView {
     pub alt_name: Option<String>,
     pub schema: Option<String>,
     pub replace: bool,
+    pub columns: Vec<String>,
     pub sql: String,
+    pub check_option: Option<String>,
     pub comment: Option<String>,
 }
 
@@ -250,6 +312,7 @@ pub struct AstMaterializedView {
     pub schema: Option<String>,
     pub with_data: bool,
     pub sql: String,
+    pub indexes: Vec<AstIndex>,
     pub comment: Option<String>,
 }
 
@@ -258,7 +321,7 @@ pub struct AstPolicy {
     pub name: String,
     pub alt_name: Option<String>,
     pub schema: Option<String>,
-    pub table: String,
+    pub target: AstPolicyTarget,
     pub command: String,
     pub r#as: Option<String>,
     pub roles: Vec<String>,
@@ -267,6 +330,21 @@ pub struct AstPolicy {
     pub comment: Option<String>,
 }
 
+/// The tables a policy applies to.
+#[derive(Debug, Clone)]
+pub enum AstPolicyTarget {
+    /// The single table named by `table`.
+    Table(String),
+    /// Every table matching the `for_tables` selector.
+    Tables(AstTableSelector),
+}
+
+/// Selects tables for a templated resource, e.g. `{ has_column = "tenant_id" }`.
+#[derive(Debug, Clone)]
+pub struct AstTableSelector {
+    pub has_column: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct AstRole {
     pub name: String,
@@ -277,7 +355,9 @@ pub struct AstRole {
     pub createrole: bool,
     pub replication: bool,
     pub password: Option<String>,
+    pub valid_until: Option<String>,
     pub in_role: Vec<String>,
+    pub config: hcl::Map<String, Value>,
     pub comment: Option<String>,
 }
 
@@ -299,9 +379,13 @@ pub struct AstGrant {
     pub privileges: Vec<String>,
     pub schema: Option<String>,
     pub table: Option<String>,
+    pub columns: Vec<String>,
     pub function: Option<String>,
     pub database: Option<String>,
     pub sequence: Option<String>,
+    pub all_in_schema: Option<String>,
+    pub with_grant_option: bool,
+    pub revoke: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -346,9 +430,14 @@ pub struct AstTable {
     pub primary_key: Option<AstPrimaryKey>,
     pub indexes: Vec<AstIndex>,
     pub checks: Vec<AstCheck>,
+    pub exclusions: Vec<AstExclusionConstraint>,
     pub foreign_keys: Vec<AstForeignKey>,
     pub partition_by: Option<AstPartitionBy>,
     pub partitions: Vec<AstPartition>,
+    pub storage_parameters: hcl::Map<String, Value>,
+    /// Enable row-level security, so only rows allowed by a `policy` are visible.
+    pub row_level_security: bool,
+    pub tablespace: Option<String>,
     pub back_references: Vec<AstBackReference>,
     pub lint_ignore: Vec<String>,
     pub comment: Option<String>,
@@ -381,6 +470,8 @@ pub struct AstColumn {
     pub nullable: bool,
     pub default: Option<String>,
     pub db_type: Option<String>,
+    pub collation: Option<String>,
+    pub updated_at: bool,
     pub lint_ignore: Vec<String>,
     pub comment: Option<String>,
     pub count: usize,
@@ -398,6 +489,21 @@ pub struct AstCheck {
     pub expression: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct AstExclusionConstraint {
+    pub name: Option<String>,
+    pub method: String,
+    pub elements: Vec<AstExclusionElement>,
+    pub r#where: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AstExclusionElement {
+    pub column: Option<String>,
+    pub expression: Option<String>,
+    pub operator: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct AstIndex {
     pub name: Option<String>,
@@ -407,6 +513,15 @@ pub struct AstIndex {
     pub orders: Vec<String>,
     pub operator_classes: Vec<String>,
     pub unique: bool,
+    /// Treat NULLs as equal in a unique index (`NULLS NOT DISTINCT`, PostgreSQL 15+).
+    pub nulls_not_distinct: bool,
+    /// Build with `CREATE INDEX CONCURRENTLY`, which cannot run in a transaction.
+    pub concurrently: bool,
+    /// Index access method, e.g. `gin` or `hnsw`; `None` means the default (btree).
+    pub method: Option<String>,
+    /// Rendered as `WITH (key = value, ...)`, e.g. `{ m = 16 }` for hnsw.
+    pub storage_parameters: hcl::Map<String, Value>,
+    pub tablespace: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -416,8 +531,11 @@ pub struct AstForeignKey {
     pub ref_schema: Option<String>,
     pub ref_table: String,
     pub ref_columns: Vec<String>,
+    pub r#match: Option<String>,
     pub on_delete: Option<String>,
     pub on_update: Option<String>,
+    pub deferrable: bool,
+    pub initially_deferred: bool,
     pub back_reference_name: Option<String>,
 }
 
@@ -432,6 +550,15 @@ pub struct AstStandaloneIndex {
     pub orders: Vec<String>,
     pub operator_classes: Vec<String>,
     pub unique: bool,
+    /// Treat NULLs as equal in a unique index (`NULLS NOT DISTINCT`, PostgreSQL 15+).
+    pub nulls_not_distinct: bool,
+    /// Build with `CREATE INDEX CONCURRENTLY`, which cannot run in a transaction.
+    pub concurrently: bool,
+    /// Index access method, e.g. `gin` or `hnsw`; `None` means the default (btree).
+    pub method: Option<String>,
+    /// Rendered as `WITH (key = value, ...)`, e.g. `{ m = 16 }` for hnsw.
+    pub storage_parameters: hcl::Map<String, Value>,
+    pub tablespace: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -546,6 +673,7 @@ pub struct SnapshotAssert {
 #[derive(Debug, Clone)]
 pub struct AstTest {
     pub name: String,
+    pub tags: Vec<String>,
     pub setup: Vec<String>,
     pub asserts: Vec<String>,
     pub assert_fail: Vec<String>,
@@ -129,6 +129,20 @@ pub fn create_index_func() -> FuncDef {
         })
 }
 
+/// Shallow-merge objects; keys from later objects win
+pub fn create_merge_func() -> FuncDef {
+    FuncDef::builder()
+        .variadic_param(ParamType::object_of(ParamType::Any))
+        .build(|args: FuncArgs| {
+            let mut result = hcl::Map::new();
+            for arg in args.iter() {
+                let obj = arg.as_object().unwrap();
+                result.extend(obj.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            Ok(Value::Object(result))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,6 +157,7 @@ mod tests {
         ctx.declare_func("sort", create_sort_func());
         ctx.declare_func("reverse", create_reverse_func());
         ctx.declare_func("index", create_index_func());
+        ctx.declare_func("merge", create_merge_func());
         ctx
     }
 
@@ -265,4 +280,20 @@ mod tests {
             .expr();
         assert!(expr.evaluate(&ctx).is_err());
     }
+
+    #[test]
+    fn test_merge_function() {
+        let ctx = create_test_context();
+        let expr_str = "merge({a=1}, {a=2, b=3})";
+        let body: hcl::Body = hcl::from_str(&format!("test = {}", expr_str)).unwrap();
+        let expr = body
+            .attributes()
+            .find(|a| a.key() == "test")
+            .unwrap()
+            .expr();
+        let mut expected = hcl::Map::new();
+        expected.insert("a".to_string(), Value::from(2));
+        expected.insert("b".to_string(), Value::from(3));
+        assert_eq!(expr.evaluate(&ctx).unwrap(), Value::Object(expected));
+    }
 }
@@ -0,0 +1,89 @@
+use hcl::Value;
+use hcl::eval::{FuncArgs, FuncDef, ParamType};
+
+/// JSON encoding/decoding functions
+pub fn create_jsonencode_func() -> FuncDef {
+    FuncDef::builder()
+        .param(ParamType::Any)
+        .build(|args: FuncArgs| {
+            serde_json::to_string(&args[0])
+                .map(Value::from)
+                .map_err(|e| format!("Cannot encode value as JSON: {e}"))
+        })
+}
+
+pub fn create_jsondecode_func() -> FuncDef {
+    FuncDef::builder()
+        .param(ParamType::String)
+        .build(|args: FuncArgs| {
+            let input = args[0].as_str().unwrap();
+            serde_json::from_str::<Value>(input).map_err(|e| format!("Invalid JSON string: {e}"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hcl::eval::{Context, Evaluate};
+
+    fn create_test_context() -> Context<'static> {
+        let mut ctx = Context::new();
+        ctx.declare_func("jsonencode", create_jsonencode_func());
+        ctx.declare_func("jsondecode", create_jsondecode_func());
+        ctx
+    }
+
+    fn eval(expr_str: &str) -> Result<Value, hcl::eval::Error> {
+        let ctx = create_test_context();
+        let body: hcl::Body = hcl::from_str(&format!("test = {}", expr_str)).unwrap();
+        let expr = body
+            .attributes()
+            .find(|a| a.key() == "test")
+            .unwrap()
+            .expr();
+        expr.evaluate(&ctx)
+    }
+
+    #[test]
+    fn test_jsonencode_function() {
+        let result = eval(r#"jsonencode({ enabled = true, tags = ["a", "b"], limit = 10 })"#);
+        assert_eq!(
+            result.unwrap(),
+            Value::from(r#"{"enabled":true,"tags":["a","b"],"limit":10}"#)
+        );
+        assert_eq!(eval(r#"jsonencode("x")"#).unwrap(), Value::from(r#""x""#));
+        assert_eq!(eval("jsonencode(null)").unwrap(), Value::from("null"));
+    }
+
+    #[test]
+    fn test_jsondecode_function() {
+        let result = eval(r#"jsondecode("{\"a\": [1, 2], \"b\": null}")"#).unwrap();
+        let mut expected = hcl::Map::new();
+        expected.insert("a".to_string(), Value::from(vec![1, 2]));
+        expected.insert("b".to_string(), Value::Null);
+        assert_eq!(result, Value::Object(expected));
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let result = eval(r#"jsondecode(jsonencode({ name = "x", n = 1.5 }))"#).unwrap();
+        let mut expected = hcl::Map::new();
+        expected.insert("name".to_string(), Value::from("x"));
+        expected.insert("n".to_string(), Value::from(1.5));
+        assert_eq!(result, Value::Object(expected));
+    }
+
+    #[test]
+    fn test_json_roundtrip_nested() {
+        let input = r#"{ index = { method = "gin", columns = ["a", "b"], opts = [{ fastupdate = false }] } }"#;
+        let original = eval(input).unwrap();
+        let result = eval(&format!("jsondecode(jsonencode({input}))")).unwrap();
+        assert_eq!(result, original);
+    }
+
+    #[test]
+    fn test_jsondecode_invalid() {
+        let err = eval(r#"jsondecode("{not json")"#).unwrap_err();
+        assert!(err.to_string().contains("Invalid JSON string"));
+    }
+}
@@ -5,6 +5,7 @@ pub mod collection;
 pub mod conversion;
 pub mod crypto;
 pub mod datetime;
+pub mod json;
 pub mod numeric;
 pub mod string;
 pub mod utility;
@@ -26,6 +27,7 @@ pub fn create_context() -> Context<'static> {
     ctx.declare_func("trim", string::create_trim_func());
     ctx.declare_func("trimspace", string::create_trimspace_func());
     ctx.declare_func("replace", string::create_replace_func());
+    ctx.declare_func("format", string::create_format_func());
 
     // Numeric functions
     ctx.declare_func("min", numeric::create_min_func());
@@ -40,6 +42,7 @@ pub fn create_context() -> Context<'static> {
     ctx.declare_func("sort", collection::create_sort_func());
     ctx.declare_func("reverse", collection::create_reverse_func());
     ctx.declare_func("index", collection::create_index_func());
+    ctx.declare_func("merge", collection::create_merge_func());
 
     // Utility functions
     ctx.declare_func("coalesce", utility::create_coalesce_func());
@@ -62,6 +65,10 @@ pub fn create_context() -> Context<'static> {
     ctx.declare_func("base64encode", base64::create_base64encode_func());
     ctx.declare_func("base64decode", base64::create_base64decode_func());
 
+    // JSON functions
+    ctx.declare_func("jsonencode", json::create_jsonencode_func());
+    ctx.declare_func("jsondecode", json::create_jsondecode_func());
+
     // Datetime functions
     ctx.declare_func("timestamp", datetime::create_timestamp_func());
     ctx.declare_func("formatdate", datetime::create_formatdate_func());
@@ -92,6 +99,7 @@ mod tests {
             "trim",
             "trimspace",
             "replace",
+            "format",
             "min",
             "max",
             "abs",
@@ -102,6 +110,7 @@ mod tests {
             "sort",
             "reverse",
             "index",
+            "merge",
             "coalesce",
             "join",
             "split",
@@ -115,6 +124,8 @@ mod tests {
             "sha512",
             "base64encode",
             "base64decode",
+            "jsonencode",
+            "jsondecode",
             "timestamp",
             "formatdate",
             "timeadd",
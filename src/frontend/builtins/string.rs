@@ -27,15 +27,13 @@ pub fn create_substr_func() -> FuncDef {
         .param(ParamType::Number)
         .build(|args: FuncArgs| {
             let s = args[0].as_str().unwrap();
-            let start = args[1].as_i64().unwrap() as usize;
-            let len = args[2].as_i64().unwrap() as usize;
-
-            if start >= s.len() {
-                Ok(Value::from(""))
-            } else {
-                let end = (start + len).min(s.len());
-                Ok(Value::from(&s[start..end]))
-            }
+            let (Some(start), Some(len)) = (args[1].as_u64(), args[2].as_u64()) else {
+                return Err("substr start and length must be non-negative integers".to_string());
+            };
+
+            // Count in characters so multi-byte input never splits a code point.
+            let out: String = s.chars().skip(start as usize).take(len as usize).collect();
+            Ok(Value::from(out))
         })
 }
 
@@ -97,6 +95,82 @@ pub fn create_replace_func() -> FuncDef {
         })
 }
 
+/// `format(spec, args...)` with the Terraform verbs `%s`, `%d`, `%f` (or
+/// `%.Nf`), `%t`, `%q`, `%v` and `%%`.
+pub fn create_format_func() -> FuncDef {
+    FuncDef::builder()
+        .param(ParamType::String)
+        .variadic_param(ParamType::Any)
+        .build(|args: FuncArgs| {
+            let spec = args[0].as_str().unwrap();
+            let values: Vec<&Value> = args.iter().skip(1).collect();
+            let mut out = String::new();
+            let mut next = 0;
+            let mut chars = spec.chars().peekable();
+            while let Some(c) = chars.next() {
+                if c != '%' {
+                    out.push(c);
+                    continue;
+                }
+                let mut precision = None;
+                if chars.peek() == Some(&'.') {
+                    chars.next();
+                    let mut digits = String::new();
+                    while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                        digits.push(*d);
+                        chars.next();
+                    }
+                    precision = Some(digits.parse::<usize>().unwrap_or(0));
+                }
+                let verb = chars
+                    .next()
+                    .ok_or_else(|| "format: unterminated verb at end of string".to_string())?;
+                if verb == '%' {
+                    out.push('%');
+                    continue;
+                }
+                let value = values
+                    .get(next)
+                    .ok_or_else(|| format!("format: not enough arguments for %{verb}"))?;
+                next += 1;
+                match (verb, value) {
+                    ('s', Value::String(s)) => out.push_str(s),
+                    ('s' | 'v', Value::Number(n)) => out.push_str(&n.to_string()),
+                    ('s' | 'v', Value::Bool(b)) => out.push_str(&b.to_string()),
+                    ('v', Value::String(s)) => out.push_str(s),
+                    ('d', Value::Number(n)) if n.is_i64() || n.is_u64() => {
+                        out.push_str(&n.to_string())
+                    }
+                    ('d', Value::Number(n)) => {
+                        let f = n.as_f64().unwrap();
+                        if f.fract() != 0.0 {
+                            return Err(format!("format: %d requires a whole number, got {f}"));
+                        }
+                        out.push_str(&(f as i64).to_string());
+                    }
+                    ('f', Value::Number(n)) => {
+                        let f = n.as_f64().unwrap();
+                        out.push_str(&format!("{f:.*}", precision.unwrap_or(6)));
+                    }
+                    ('t', Value::Bool(b)) => out.push_str(&b.to_string()),
+                    ('q', Value::String(s)) => out.push_str(&format!("{s:?}")),
+                    ('v', other) => out.push_str(&other.to_string()),
+                    ('s' | 'd' | 'f' | 't' | 'q', other) => {
+                        return Err(format!("format: invalid value {other} for %{verb}"));
+                    }
+                    _ => return Err(format!("format: unsupported verb %{verb}")),
+                }
+            }
+            if next < values.len() {
+                return Err(format!(
+                    "format: too many arguments; {} given, {next} used",
+                    values.len()
+                ));
+            }
+            Ok(Value::from(out))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,6 +188,7 @@ mod tests {
         ctx.declare_func("trim", create_trim_func());
         ctx.declare_func("trimspace", create_trimspace_func());
         ctx.declare_func("replace", create_replace_func());
+        ctx.declare_func("format", create_format_func());
         ctx
     }
 
@@ -182,6 +257,28 @@ mod tests {
         assert_eq!(expr.evaluate(&ctx).unwrap(), Value::from("world"));
     }
 
+    #[test]
+    fn test_substr_multibyte_and_out_of_range() {
+        let ctx = create_test_context();
+        for (expr_str, expected) in [
+            ("substr(\"héllo\", 1, 3)", Some("éll")),
+            ("substr(\"abc\", 2, 100)", Some("c")),
+            ("substr(\"abc\", 10, 1)", Some("")),
+            ("substr(\"abc\", -1, 1)", None),
+        ] {
+            let body: hcl::Body = hcl::from_str(&format!("test = {}", expr_str)).unwrap();
+            let expr = body
+                .attributes()
+                .find(|a| a.key() == "test")
+                .unwrap()
+                .expr();
+            match expected {
+                Some(v) => assert_eq!(expr.evaluate(&ctx).unwrap(), Value::from(v)),
+                None => assert!(expr.evaluate(&ctx).is_err()),
+            }
+        }
+    }
+
     #[test]
     fn test_contains_function() {
         let ctx = create_test_context();
@@ -246,4 +343,29 @@ mod tests {
             .expr();
         assert_eq!(expr.evaluate(&ctx).unwrap(), Value::from("hello universe"));
     }
+
+    #[test]
+    fn test_format_function() {
+        let ctx = create_test_context();
+        for (expr_str, expected) in [
+            ("format(\"%s_%d\", \"user\", 3)", Some("user_3")),
+            ("format(\"%.2f%%\", 12.5)", Some("12.50%")),
+            ("format(\"%q is %t\", \"x\", true)", Some("\"x\" is true")),
+            ("format(\"%v-%v\", \"a\", 1)", Some("a-1")),
+            ("format(\"%s_%s\", \"only\")", None),
+            ("format(\"%s\", \"a\", \"b\")", None),
+            ("format(\"%d\", \"x\")", None),
+        ] {
+            let body: hcl::Body = hcl::from_str(&format!("test = {}", expr_str)).unwrap();
+            let expr = body
+                .attributes()
+                .find(|a| a.key() == "test")
+                .unwrap()
+                .expr();
+            match expected {
+                Some(v) => assert_eq!(expr.evaluate(&ctx).unwrap(), Value::from(v)),
+                None => assert!(expr.evaluate(&ctx).is_err(), "{expr_str}"),
+            }
+        }
+    }
 }
@@ -1,13 +1,15 @@
 use anyhow::{Context, Result, bail};
 use hcl::value::Map;
 use hcl::eval::{Context as HclContext, Evaluate};
-use hcl::template::{Element as TplElement, Template};
+use hcl::template::{Directive, Element as TplElement, Template};
 use hcl::{
     Attribute, Block, Body, Number, Structure, Traversal, TraversalOperator, Value,
     expr::{BinaryOperator, TemplateExpr, UnaryOperator},
 };
 use path_absolutize::Absolutize;
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 
 use crate::Loader;
@@ -16,7 +18,7 @@ use crate::frontend::ast::VarValidation;
 use crate::frontend::builtins;
 use crate::frontend::data_sources;
 use crate::frontend::env::{EnvVars, VarSpec, VarType};
-use crate::frontend::for_each::execute_for_each;
+use crate::frontend::for_each::{check_resource_limit, execute_for_each};
 use crate::frontend::lower;
 use crate::ir;
 
@@ -50,18 +52,183 @@ pub fn expr_to_string(expr: &hcl::Expression, env: &EnvVars) -> Result<String> {
         }
         hcl::Expression::Number(n) => Ok(n.to_string()),
         hcl::Expression::Bool(b) => Ok(b.to_string()),
-        _ => bail!("unsupported expression kind for string value: {expr:?}"),
+        // Conditionals, operations, parentheses, ...
+        _ => value_to_string(&expr_to_value(expr, env)?),
     }
 }
 
 pub fn expr_to_string_vec(expr: &hcl::Expression, env: &EnvVars) -> Result<Vec<String>> {
     match expr {
         hcl::Expression::Array(a) => a.iter().map(|e| expr_to_string(e, env)).collect(),
-        _ => bail!("expected array expression"),
+        // Computed lists: traversals, for expressions, function calls, ...
+        _ => match expr_to_value(expr, env)? {
+            Value::Array(items) => items.iter().map(value_to_string).collect(),
+            other => bail!("expected array expression, got {}", value_kind(&other)),
+        },
+    }
+}
+
+thread_local! {
+    static EXPR_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static FILE_READER: Cell<Option<FileReader<'static>>> = const { Cell::new(None) };
+}
+
+/// Tracks how deeply [`expr_to_value`] is nested on the current thread. Every
+/// recursive path through the evaluator re-enters `expr_to_value`, so counting
+/// there bounds the whole evaluator.
+struct DepthGuard;
+
+impl DepthGuard {
+    fn enter(limit: usize) -> Result<Self> {
+        let depth = EXPR_DEPTH.with(|d| {
+            d.set(d.get() + 1);
+            d.get()
+        });
+        let guard = DepthGuard;
+        if depth > limit {
+            bail!("expression nesting exceeds maximum depth of {limit}");
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        EXPR_DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+/// Reads files for `file(path)` through a [`Loader`], resolving relative
+/// paths against `base`.
+#[derive(Clone, Copy)]
+struct FileReader<'a> {
+    loader: &'a dyn Loader,
+    base: &'a Path,
+}
+
+/// Makes a [`FileReader`] the one `file(path)` uses on the current thread
+/// while an HCL file is evaluated. Dropping it restores the reader of the
+/// enclosing file, so module files resolve paths against their own directory.
+struct FileReaderGuard<'a> {
+    previous: Option<FileReader<'static>>,
+    _reader: PhantomData<FileReader<'a>>,
+}
+
+impl<'a> FileReaderGuard<'a> {
+    fn enter(reader: FileReader<'a>) -> Self {
+        // SAFETY: the erased lifetime never escapes the guard: `evaluate_file`
+        // only uses the reader within a single call, and `Drop` swaps it out
+        // again before `'a` ends.
+        let reader = unsafe { std::mem::transmute::<FileReader<'a>, FileReader<'static>>(reader) };
+        FileReaderGuard {
+            previous: FILE_READER.with(|r| r.replace(Some(reader))),
+            _reader: PhantomData,
+        }
+    }
+}
+
+impl Drop for FileReaderGuard<'_> {
+    fn drop(&mut self) {
+        FILE_READER.with(|r| r.set(self.previous));
+    }
+}
+
+/// Where [`check_nesting_depth`] is while scanning a source file.
+#[derive(Clone)]
+enum ScanMode {
+    Code,
+    Str,
+    /// Inside a heredoc ending at a line holding only this marker.
+    Heredoc(String),
+}
+
+/// Reject sources whose bracket nesting exceeds `limit` before handing them to
+/// the HCL parser, which recurses once per level and would otherwise overflow
+/// the stack on pathological input. Brackets inside string literals, heredocs
+/// and comments are text and don't count; `${ }` and `%{ }` interpolations do.
+fn check_nesting_depth(src: &str, limit: usize) -> Result<()> {
+    // Each open bracket remembers the mode its closing `}` returns to:
+    // `Code` for plain brackets, the enclosing string for interpolations.
+    let mut stack: Vec<ScanMode> = Vec::new();
+    let mut mode = ScanMode::Code;
+    let open = |stack: &mut Vec<ScanMode>, resume: ScanMode| -> Result<()> {
+        stack.push(resume);
+        if stack.len() > limit {
+            bail!("expression nesting exceeds maximum depth of {limit}");
+        }
+        Ok(())
+    };
+    let b = src.as_bytes();
+    let mut i = 0;
+    while i < b.len() {
+        let next = b.get(i + 1).copied();
+        match &mode {
+            ScanMode::Code => match b[i] {
+                b'#' => i = line_end(b, i),
+                b'/' if next == Some(b'/') => i = line_end(b, i),
+                b'/' if next == Some(b'*') => {
+                    i = src[i + 2..].find("*/").map_or(b.len(), |e| i + 2 + e + 1);
+                }
+                b'"' => mode = ScanMode::Str,
+                b'<' if next == Some(b'<') => {
+                    let rest = src[i + 2..].strip_prefix('-').unwrap_or(&src[i + 2..]);
+                    let marker: String = rest
+                        .chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    if !marker.is_empty() {
+                        mode = ScanMode::Heredoc(marker);
+                        // Stop on the newline so the first line is checked
+                        // for the closing marker too.
+                        i = line_end(b, i) - 1;
+                    }
+                }
+                b'(' | b'[' | b'{' => open(&mut stack, ScanMode::Code)?,
+                b')' | b']' => {
+                    stack.pop();
+                }
+                b'}' => mode = stack.pop().unwrap_or(ScanMode::Code),
+                _ => {}
+            },
+            ScanMode::Str | ScanMode::Heredoc(_) => {
+                let in_str = matches!(mode, ScanMode::Str);
+                match b[i] {
+                    b'\\' if in_str => i += 1,
+                    b'"' if in_str => mode = ScanMode::Code,
+                    b'$' | b'%' if next == Some(b[i]) => i += 1,
+                    b'$' | b'%' if next == Some(b'{') => {
+                        open(&mut stack, mode.clone())?;
+                        mode = ScanMode::Code;
+                        i += 1;
+                    }
+                    b'\n' => {
+                        if let ScanMode::Heredoc(marker) = &mode {
+                            let end = line_end(b, i + 1);
+                            if src[i + 1..end].trim() == marker {
+                                mode = ScanMode::Code;
+                                i = end;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        i += 1;
     }
+    Ok(())
+}
+
+/// Index of the newline ending the line that contains `i`, or the end of `b`.
+fn line_end(b: &[u8], i: usize) -> usize {
+    b[i..]
+        .iter()
+        .position(|&c| c == b'\n')
+        .map_or(b.len(), |p| i + p)
 }
 
 pub fn expr_to_value(expr: &hcl::Expression, env: &EnvVars) -> Result<Value> {
+    let _depth = DepthGuard::enter(env.limits.max_expr_depth)?;
     match expr {
         hcl::Expression::String(s) => Ok(Value::String(s.clone())),
         hcl::Expression::Number(n) => Ok(Value::Number(n.clone())),
@@ -195,9 +362,15 @@ pub fn expr_to_value(expr: &hcl::Expression, env: &EnvVars) -> Result<Value> {
                         _ => bail!("unsupported operand type for !: {}", value_kind(&v)),
                     },
                     UnaryOperator::Neg => match v {
-                        Value::Number(n) => Ok(Value::Number(
-                            Number::from_f64(-n.as_f64().unwrap_or(0.0)).unwrap(),
-                        )),
+                        Value::Number(n) => {
+                            let negated = match n.as_i64().and_then(i64::checked_neg) {
+                                Some(i) => Some(Number::from(i)),
+                                None => Number::from_f64(-n.as_f64().unwrap_or(0.0)),
+                            };
+                            negated
+                                .map(Value::Number)
+                                .with_context(|| format!("cannot negate number {n}"))
+                        }
                         _ => bail!("unsupported operand type for -: {}", value_kind(&v)),
                     },
                 }
@@ -251,7 +424,11 @@ pub fn expr_to_value(expr: &hcl::Expression, env: &EnvVars) -> Result<Value> {
                         };
                         Ok(Value::Bool(res))
                     }
-                    _ => bail!("unsupported binary operator: {:?}", b.operator),
+                    BinaryOperator::Plus
+                    | BinaryOperator::Minus
+                    | BinaryOperator::Mul
+                    | BinaryOperator::Div
+                    | BinaryOperator::Mod => arithmetic(b.operator, &lhs, &rhs),
                 }
             }
         },
@@ -259,6 +436,64 @@ pub fn expr_to_value(expr: &hcl::Expression, env: &EnvVars) -> Result<Value> {
     }
 }
 
+/// `lhs <op> rhs` for numbers. Integer operands stay integers unless the
+/// result is fractional or overflows.
+fn arithmetic(op: BinaryOperator, lhs: &Value, rhs: &Value) -> Result<Value> {
+    let (Value::Number(l), Value::Number(r)) = (lhs, rhs) else {
+        bail!(
+            "unsupported operands for {}: {} and {}",
+            op.as_str(),
+            value_kind(lhs),
+            value_kind(rhs)
+        );
+    };
+    if matches!(op, BinaryOperator::Div | BinaryOperator::Mod) && r.as_f64() == Some(0.0) {
+        let what = if op == BinaryOperator::Div {
+            "division"
+        } else {
+            "modulo"
+        };
+        bail!("{what} by zero");
+    }
+    if let (Some(a), Some(b)) = (l.as_i64(), r.as_i64()) {
+        let exact = match op {
+            BinaryOperator::Plus => a.checked_add(b),
+            BinaryOperator::Minus => a.checked_sub(b),
+            BinaryOperator::Mul => a.checked_mul(b),
+            BinaryOperator::Div => a.checked_rem(b).filter(|&m| m == 0).and(a.checked_div(b)),
+            BinaryOperator::Mod => a.checked_rem(b),
+            _ => None,
+        };
+        if let Some(n) = exact {
+            return Ok(Value::from(n));
+        }
+    }
+    let (a, b) = (l.as_f64().unwrap_or(0.0), r.as_f64().unwrap_or(0.0));
+    let result = match op {
+        BinaryOperator::Plus => a + b,
+        BinaryOperator::Minus => a - b,
+        BinaryOperator::Mul => a * b,
+        BinaryOperator::Div => a / b,
+        BinaryOperator::Mod => a % b,
+        _ => unreachable!(),
+    };
+    Number::from_f64(result)
+        .map(Value::Number)
+        .with_context(|| format!("{a} {} {b} is not a finite number", op.as_str()))
+}
+
+/// Record every non-empty string leaf of `v` so backends can redact it.
+/// Numbers and bools are left out: redaction matches by value, and e.g. a
+/// sensitive `true` would hide every unrelated `true` in the output.
+fn collect_sensitive_values(v: &Value, out: &mut Vec<String>) {
+    match v {
+        Value::String(s) if !s.is_empty() => out.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|i| collect_sensitive_values(i, out)),
+        Value::Object(map) => map.values().for_each(|i| collect_sensitive_values(i, out)),
+        _ => {}
+    }
+}
+
 pub fn value_to_string(v: &Value) -> Result<String> {
     match v {
         Value::String(s) => Ok(s.clone()),
@@ -364,6 +599,15 @@ fn resolve_traversal_value(tr: &Traversal, env: &EnvVars) -> Result<Value> {
             // Check if the root is a variable in the environment
             if let Some(current) = env.vars.get(root).cloned() {
                 apply_traversal(current, &mut it, env)
+            } else if let Some(resources) = env.resources.get(root) {
+                let Some(TraversalOperator::GetAttr(name)) = it.next() else {
+                    bail!("expected {root}.<name>");
+                };
+                let current = resources
+                    .get(name.as_str())
+                    .cloned()
+                    .with_context(|| format!("undefined {root} '{name}'"))?;
+                apply_traversal(current, &mut it, env)
             } else {
                 bail!(
                     "unsupported traversal root '{}': expected var.*, local.*, module.*, each.*, count.*, or a variable name",
@@ -548,7 +792,7 @@ pub fn get_attr_bool(body: &hcl::Body, name: &str, env: &EnvVars) -> Result<Opti
 }
 
 /// Create an HCL evaluation context with built-in functions and custom variable resolvers
-pub fn create_eval_context(env: &EnvVars) -> HclContext<'_> {
+pub fn create_eval_context(env: &EnvVars) -> HclContext<'static> {
     let mut ctx = builtins::create_context();
 
     // Add custom variable resolvers for our special variables (var, local, each)
@@ -600,6 +844,14 @@ pub fn create_eval_context(env: &EnvVars) -> HclContext<'_> {
         ctx.declare_var("data", Value::Object(data_object));
     }
 
+    for (kind, resources) in &env.resources {
+        let object: Map<String, Value> = resources
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        ctx.declare_var(kind.clone(), Value::Object(object));
+    }
+
     if let Some((key, value)) = &env.each {
         let mut each_object = Map::new();
         each_object.insert("key".to_string(), key.clone());
@@ -626,7 +878,11 @@ pub fn create_eval_context(env: &EnvVars) -> HclContext<'_> {
 pub fn evaluate_expr(expr: &hcl::Expression, env: &EnvVars) -> Result<Value> {
     // Try to use HCL's built-in evaluation for expressions that support functions
     match expr {
-        hcl::Expression::FuncCall(_) => {
+        hcl::Expression::FuncCall(call) => {
+            if let Some(value) = evaluate_special_call(call, env) {
+                return value;
+            }
+            let expr = &inline_special_calls(expr, env)?;
             // Function calls should be evaluated by HCL's context
             let ctx = create_eval_context(env);
             // Try to evaluate the expression directly using HCL's evaluation
@@ -638,7 +894,10 @@ pub fn evaluate_expr(expr: &hcl::Expression, env: &EnvVars) -> Result<Value> {
                     let expr_str = format!("{}", expr);
                     let body: hcl::Body = hcl::from_str(&format!("temp = {}", expr_str))
                         .map_err(|e| anyhow::anyhow!("Failed to parse expression: {}", e))?;
-                    let temp_attr = body.attributes().find(|a| a.key() == "temp").unwrap();
+                    let temp_attr = body
+                        .attributes()
+                        .find(|a| a.key() == "temp")
+                        .context("Failed to parse expression")?;
                     let temp_expr = temp_attr.expr();
                     temp_expr
                         .evaluate(&ctx)
@@ -657,6 +916,212 @@ pub fn evaluate_expr(expr: &hcl::Expression, env: &EnvVars) -> Result<Value> {
     }
 }
 
+/// `try(a, b, ...)` returns the first argument that evaluates without error
+/// and `can(a)` whether `a` does. HCL's evaluator fails on the first bad
+/// argument, so these two are evaluated here instead, as is `file(path)`,
+/// which reads through the current [`FileReader`]. Returns `None` for any other
+/// function.
+fn evaluate_special_call(call: &hcl::expr::FuncCall, env: &EnvVars) -> Option<Result<Value>> {
+    if call.name.is_namespaced() {
+        return None;
+    }
+    match call.name.name.as_str() {
+        "try" => Some(evaluate_try(&call.args, env)),
+        "can" => Some(match call.args.as_slice() {
+            [arg] => Ok(Value::Bool(expr_to_value(arg, env).is_ok())),
+            _ => Err(anyhow::anyhow!("can expects exactly one argument")),
+        }),
+        "file" => Some(evaluate_file(&call.args, env)),
+        _ => None,
+    }
+}
+
+fn evaluate_file(args: &[hcl::Expression], env: &EnvVars) -> Result<Value> {
+    let [arg] = args else {
+        bail!("file expects exactly one argument");
+    };
+    let path = expr_to_string(arg, env)?;
+    let Some(files) = FILE_READER.with(Cell::get) else {
+        bail!("file: not available outside of an HCL file");
+    };
+    let full = resolve_module_path(files.base, &path)?;
+    let content = files
+        .loader
+        .load(&full)
+        .with_context(|| format!("file: reading {}", full.display()))?;
+    Ok(Value::String(content))
+}
+
+fn evaluate_try(args: &[hcl::Expression], env: &EnvVars) -> Result<Value> {
+    let mut last_err = None;
+    for arg in args {
+        match expr_to_value(arg, env) {
+            Ok(value) => return Ok(value),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    match last_err {
+        Some(e) => Err(e.context("try: no argument evaluated without error")),
+        None => bail!("try expects at least one argument"),
+    }
+}
+
+/// Replace `try`/`can`/`file` calls nested in the arguments of `expr` with
+/// their values, so the rest of the call can go to HCL's evaluator.
+/// Conditionals, for expressions and templates that contain such a call are
+/// evaluated whole, so untaken branches stay unevaluated and loop variables
+/// stay in scope.
+fn inline_special_calls(expr: &hcl::Expression, env: &EnvVars) -> Result<hcl::Expression> {
+    use hcl::Expression as E;
+    use hcl::expr::Operation;
+
+    if !contains_special_call(expr) {
+        return Ok(expr.clone());
+    }
+    let inline = |e: &E| inline_special_calls(e, env);
+    Ok(match expr {
+        E::FuncCall(call) => match evaluate_special_call(call, env) {
+            Some(value) => value?.into(),
+            None => {
+                let mut call = call.clone();
+                for arg in &mut call.args {
+                    *arg = inline(arg)?;
+                }
+                E::FuncCall(call)
+            }
+        },
+        E::Array(items) => E::Array(items.iter().map(inline).collect::<Result<_>>()?),
+        E::Object(obj) => {
+            let mut out = hcl::Object::new();
+            for (key, value) in obj {
+                let key = match key {
+                    hcl::ObjectKey::Expression(k) => hcl::ObjectKey::Expression(inline(k)?),
+                    ident => ident.clone(),
+                };
+                out.insert(key, inline(value)?);
+            }
+            E::Object(out)
+        }
+        E::Parenthesis(inner) => E::Parenthesis(Box::new(inline(inner)?)),
+        E::Operation(op) => E::Operation(Box::new(match &**op {
+            Operation::Unary(u) => {
+                let mut u = u.clone();
+                u.expr = inline(&u.expr)?;
+                Operation::Unary(u)
+            }
+            Operation::Binary(b) => {
+                let mut b = b.clone();
+                b.lhs_expr = inline(&b.lhs_expr)?;
+                b.rhs_expr = inline(&b.rhs_expr)?;
+                Operation::Binary(b)
+            }
+        })),
+        E::Traversal(tr) => {
+            let mut tr = tr.clone();
+            tr.expr = inline(&tr.expr)?;
+            for op in &mut tr.operators {
+                if let TraversalOperator::Index(index) = op {
+                    *index = inline(index)?;
+                }
+            }
+            E::Traversal(tr)
+        }
+        _ => expr_to_value(expr, env)?.into(),
+    })
+}
+
+/// Whether `expr` calls `try`, `can` or `file` anywhere.
+fn contains_special_call(expr: &hcl::Expression) -> bool {
+    let mut found = false;
+    walk_expr(expr, &mut |e| {
+        if let hcl::Expression::FuncCall(call) = e {
+            found |= !call.name.is_namespaced()
+                && matches!(call.name.name.as_str(), "try" | "can" | "file");
+        }
+    });
+    found
+}
+
+/// Call `f` on `expr` and on every expression nested in it, including the
+/// interpolations and directives of templates.
+fn walk_expr(expr: &hcl::Expression, f: &mut impl FnMut(&hcl::Expression)) {
+    use hcl::Expression as E;
+    use hcl::expr::Operation;
+
+    f(expr);
+    match expr {
+        E::Array(items) => items.iter().for_each(|e| walk_expr(e, f)),
+        E::Object(obj) => {
+            for (key, value) in obj {
+                if let hcl::ObjectKey::Expression(key) = key {
+                    walk_expr(key, f);
+                }
+                walk_expr(value, f);
+            }
+        }
+        E::TemplateExpr(t) => {
+            if let Ok(tpl) = Template::from_expr(t) {
+                walk_template(&tpl, f);
+            }
+        }
+        E::Traversal(tr) => {
+            walk_expr(&tr.expr, f);
+            for op in &tr.operators {
+                if let TraversalOperator::Index(index) = op {
+                    walk_expr(index, f);
+                }
+            }
+        }
+        E::FuncCall(call) => call.args.iter().for_each(|e| walk_expr(e, f)),
+        E::Parenthesis(inner) => walk_expr(inner, f),
+        E::Conditional(c) => {
+            walk_expr(&c.cond_expr, f);
+            walk_expr(&c.true_expr, f);
+            walk_expr(&c.false_expr, f);
+        }
+        E::Operation(op) => match &**op {
+            Operation::Unary(u) => walk_expr(&u.expr, f),
+            Operation::Binary(b) => {
+                walk_expr(&b.lhs_expr, f);
+                walk_expr(&b.rhs_expr, f);
+            }
+        },
+        E::ForExpr(fe) => {
+            walk_expr(&fe.collection_expr, f);
+            if let Some(key) = &fe.key_expr {
+                walk_expr(key, f);
+            }
+            walk_expr(&fe.value_expr, f);
+            if let Some(cond) = &fe.cond_expr {
+                walk_expr(cond, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn walk_template(tpl: &Template, f: &mut impl FnMut(&hcl::Expression)) {
+    for el in tpl.elements() {
+        match el {
+            TplElement::Literal(_) => {}
+            TplElement::Interpolation(ip) => walk_expr(&ip.expr, f),
+            TplElement::Directive(directive) => match &**directive {
+                Directive::If(d) => {
+                    walk_expr(&d.cond_expr, f);
+                    walk_template(&d.true_template, f);
+                    if let Some(t) = &d.false_template {
+                        walk_template(t, f);
+                    }
+                }
+                Directive::For(d) => {
+                    walk_expr(&d.collection_expr, f);
+                    walk_template(&d.template, f);
+                }
+            },
+        }
+    }
+}
+
 pub fn resolve_module_path(base: &Path, source: &str) -> Result<PathBuf> {
     let p = Path::new(source);
     let path = if p.is_absolute() {
@@ -672,6 +1137,27 @@ pub fn load_root_with_loader(
     loader: &dyn Loader,
     root_env: EnvVars,
 ) -> Result<ir::Config> {
+    Ok(load_root(path, loader, root_env, None)?.0)
+}
+
+/// Load the configuration at `path` like [`load_root_with_loader`] and
+/// evaluate `expr` in the scope the root file's outputs see.
+pub fn evaluate_in_root(
+    path: &Path,
+    loader: &dyn Loader,
+    root_env: EnvVars,
+    expr: &hcl::Expression,
+) -> Result<(Value, ir::Config)> {
+    let (cfg, value) = load_root(path, loader, root_env, Some(expr))?;
+    Ok((value.context("expression was not evaluated")?, cfg))
+}
+
+fn load_root(
+    path: &Path,
+    loader: &dyn Loader,
+    root_env: EnvVars,
+    eval: Option<&hcl::Expression>,
+) -> Result<(ir::Config, Option<Value>)> {
     let path = if path.is_dir() {
         path.join("main.hcl")
     } else {
@@ -681,11 +1167,85 @@ pub fn load_root_with_loader(
         .parent()
         .map(|p| p.to_path_buf())
         .unwrap_or_else(|| PathBuf::from("."));
+    let start = std::time::Instant::now();
+    let parse_before = crate::profile::elapsed("parse");
     let mut visited = Vec::new();
-    let ast_cfg = load_file(loader, &path, &base, &root_env, &mut visited)?;
+    let (ast_cfg, value) = load_file(loader, &path, &base, &root_env, &mut visited, eval)?;
     let mut cfg = lower::lower_config(ast_cfg);
     populate_back_references(&mut cfg)?;
-    Ok(cfg)
+    // Evaluation is everything but the file parsing interleaved with it.
+    let parse = crate::profile::elapsed("parse") - parse_before;
+    crate::profile::record("evaluate", start.elapsed().saturating_sub(parse));
+    Ok((cfg, value))
+}
+
+/// Indexes into `locals` in an order where every local comes after the
+/// locals it references. Fails on duplicate names and reference cycles.
+fn local_order(locals: &[(&str, &hcl::Expression)]) -> Result<Vec<usize>> {
+    let mut index = HashMap::new();
+    for (i, (name, _)) in locals.iter().enumerate() {
+        if index.insert(*name, i).is_some() {
+            bail!("local '{name}' is defined more than once");
+        }
+    }
+    let deps: Vec<Vec<usize>> = locals
+        .iter()
+        .map(|(_, expr)| {
+            let mut deps = Vec::new();
+            walk_expr(expr, &mut |e| {
+                if let hcl::Expression::Traversal(tr) = e
+                    && matches!(&tr.expr, hcl::Expression::Variable(v) if v.as_str() == "local")
+                    && let Some(TraversalOperator::GetAttr(name)) = tr.operators.first()
+                    && let Some(&i) = index.get(name.as_str())
+                {
+                    deps.push(i);
+                }
+            });
+            deps
+        })
+        .collect();
+
+    fn visit(
+        i: usize,
+        locals: &[(&str, &hcl::Expression)],
+        deps: &[Vec<usize>],
+        state: &mut [u8],
+        path: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<()> {
+        match state[i] {
+            2 => return Ok(()),
+            1 => {
+                let start = path.iter().position(|&p| p == i).unwrap();
+                let cycle: Vec<&str> = path[start..]
+                    .iter()
+                    .chain([&i])
+                    .map(|&p| locals[p].0)
+                    .collect();
+                bail!(
+                    "locals reference each other in a cycle: {}",
+                    cycle.join(" -> ")
+                );
+            }
+            _ => {}
+        }
+        state[i] = 1;
+        path.push(i);
+        for &d in &deps[i] {
+            visit(d, locals, deps, state, path, order)?;
+        }
+        path.pop();
+        state[i] = 2;
+        order.push(i);
+        Ok(())
+    }
+
+    let mut state = vec![0u8; locals.len()];
+    let mut order = Vec::with_capacity(locals.len());
+    for i in 0..locals.len() {
+        visit(i, locals, &deps, &mut state, &mut Vec::new(), &mut order)?;
+    }
+    Ok(order)
 }
 
 fn populate_back_references(cfg: &mut ir::Config) -> Result<()> {
@@ -701,8 +1261,8 @@ fn populate_back_references(cfg: &mut ir::Config) -> Result<()> {
                     .map(|an| fk.ref_table == *an)
                     .unwrap_or(false);
                 if matches_name || matches_alt {
-                    let name = fk
-                        .back_reference_name
+                    let relation_name = other_table.relation_name(fk);
+                    let name = relation_name
                         .clone()
                         .unwrap_or_else(|| other_table.name.clone().to_lowercase() + "s");
                     // Prefer the concrete table name when present so downstream backends
@@ -714,7 +1274,7 @@ fn populate_back_references(cfg: &mut ir::Config) -> Result<()> {
                     table.back_references.push(crate::ir::BackReferenceSpec {
                         name,
                         table: target_table,
-                        relation_name: fk.back_reference_name.clone(),
+                        relation_name,
                     });
                 }
             }
@@ -824,7 +1384,8 @@ fn load_file(
     base: &Path,
     parent_env: &EnvVars,
     visited: &mut Vec<PathBuf>,
-) -> Result<ast::Config> {
+    eval: Option<&hcl::Expression>,
+) -> Result<(ast::Config, Option<Value>)> {
     let abspath = path
         .absolutize()
         .map_err(|e| anyhow::anyhow!("absolutize error: {e}"))?
@@ -832,13 +1393,24 @@ fn load_file(
     if visited.contains(&abspath) {
         bail!("module cycle detected at {}", abspath.display());
     }
+    // The root file is depth 0; every nested module adds one.
+    let max_depth = parent_env.limits.max_module_depth;
+    if visited.len() > max_depth {
+        bail!(
+            "module nesting exceeds maximum depth of {max_depth} at {}",
+            abspath.display()
+        );
+    }
     visited.push(abspath.clone());
 
-    let content = loader
-        .load(path)
-        .with_context(|| format!("reading HCL file {}", path.display()))?;
-    let mut body: hcl::Body =
-        hcl::from_str(&content).with_context(|| format!("parsing HCL in {}", path.display()))?;
+    let mut body: hcl::Body = crate::profile::time("parse", || -> Result<hcl::Body> {
+        let content = loader
+            .load(path)
+            .with_context(|| format!("reading HCL file {}", path.display()))?;
+        check_nesting_depth(&content, parent_env.limits.max_expr_depth)
+            .with_context(|| format!("parsing HCL in {}", path.display()))?;
+        hcl::from_str(&content).with_context(|| format!("parsing HCL in {}", path.display()))
+    })?;
 
     // 1) Collect variable specs (default/type/validation)
     let mut var_specs: HashMap<String, VarSpec> = HashMap::new();
@@ -863,6 +1435,16 @@ fn load_file(
                     .with_context(|| format!("parsing type for variable '{name}'"))?,
             );
         }
+        if let Some(attr) = find_attr(blk.body(), "sensitive") {
+            spec.sensitive = match expr_to_value(attr.expr(), parent_env)? {
+                Value::Bool(b) => b,
+                other => bail!(
+                    "variable '{}' sensitive must be a bool, got {}",
+                    name,
+                    value_kind(&other)
+                ),
+            };
+        }
         if let Some(vblk) = blk.body().blocks().find(|b| b.identifier() == "validation") {
             let cond_attr = find_attr(vblk.body(), "condition")
                 .ok_or_else(|| anyhow::anyhow!("validation block missing 'condition'"))?;
@@ -877,7 +1459,10 @@ fn load_file(
     }
 
     // Merge env: defaults overridden by parent vars (root) for root file; for modules we override via module call
-    let mut env = EnvVars::default();
+    let mut env = EnvVars {
+        limits: parent_env.limits,
+        ..EnvVars::default()
+    };
     for (name, spec) in &var_specs {
         if let Some(v) = &spec.default {
             env.vars.insert(name.clone(), v.clone());
@@ -885,19 +1470,25 @@ fn load_file(
     }
     env.vars.extend(parent_env.vars.clone());
     env.data = parent_env.data.clone();
+    let _files = FileReaderGuard::enter(FileReader { loader, base });
 
-    // 2) Compute locals (can reference vars)
-    for blk in body.blocks().filter(|b| b.identifier() == "locals") {
-        for attr in blk.body().attributes() {
-            let key = attr.key();
-            let v = expr_to_value(attr.expr(), &env)
-                .with_context(|| format!("evaluating local '{}')", key))?;
-            env.locals.insert(key.to_string(), v);
-        }
+    // 2) Compute locals (can reference vars and each other, in any order)
+    let locals: Vec<(&str, &hcl::Expression)> = body
+        .blocks()
+        .filter(|b| b.identifier() == "locals")
+        .flat_map(|b| b.body().attributes())
+        .map(|attr| (attr.key(), attr.expr()))
+        .collect();
+    for i in local_order(&locals)? {
+        let (key, expr) = locals[i];
+        let v =
+            expr_to_value(expr, &env).with_context(|| format!("evaluating local '{}')", key))?;
+        env.locals.insert(key.to_string(), v);
     }
 
     // 2.5) Load data sources before modules so their values are available for module arguments
-    data_sources::load_data_sources(loader, base, &body, &mut env)?;
+    let mut cfg = ast::Config::default();
+    data_sources::load_data_sources(loader, base, &body, &mut env, &mut cfg)?;
 
     // Enforce variable types and run validations
     for (name, spec) in &var_specs {
@@ -934,7 +1525,11 @@ fn load_file(
     body = expand_dynamic_blocks(&body, &env)?;
 
     // 3) Load modules first so their outputs are available
-    let mut cfg = ast::Config::default();
+    for (name, _) in var_specs.iter().filter(|(_, spec)| spec.sensitive) {
+        if let Some(value) = env.vars.get(name) {
+            collect_sensitive_values(value, &mut cfg.sensitive_values);
+        }
+    }
 
     // Load provider registry to validate provider blocks
     let provider_registry = crate::provider::get_default_provider_registry();
@@ -1001,12 +1596,14 @@ fn load_file(
                 mod_env.vars = mod_vars;
                 mod_env.data = env.data.clone();
                 mod_env.modules = env.modules.clone();
-                let sub = load_file(
+                mod_env.limits = env.limits;
+                let (sub, _) = load_file(
                     loader,
                     &module_path.join("main.hcl"),
                     &module_path,
                     &mod_env,
                     visited,
+                    None,
                 )
                 .with_context(|| {
                     format!(
@@ -1026,6 +1623,8 @@ fn load_file(
                 cfg.materialized.extend(sub.materialized);
                 cfg.policies.extend(sub.policies);
                 cfg.providers.extend(sub.providers);
+                cfg.sensitive_values.extend(sub.sensitive_values);
+                check_resource_limit(&cfg, 0, &env)?;
                 // Outputs from for_each modules aren't accessible via module.*
                 Ok(())
             })?;
@@ -1056,12 +1655,14 @@ fn load_file(
                 mod_env.vars = mod_vars;
                 mod_env.data = env.data.clone();
                 mod_env.modules = env.modules.clone();
-                let sub = load_file(
+                mod_env.limits = env.limits;
+                let (sub, _) = load_file(
                     loader,
                     &module_path.join("main.hcl"),
                     &module_path,
                     &mod_env,
                     visited,
+                    None,
                 )
                 .with_context(|| {
                     format!(
@@ -1080,6 +1681,8 @@ fn load_file(
                 cfg.materialized.extend(sub.materialized);
                 cfg.policies.extend(sub.policies);
                 cfg.providers.extend(sub.providers);
+                cfg.sensitive_values.extend(sub.sensitive_values);
+                check_resource_limit(&cfg, 0, &env)?;
             }
         } else {
             // Prepare vars for module: start empty, collect its own defaults while loading; pass overrides from attrs (excluding 'source'/'for_each'/'count')
@@ -1097,12 +1700,14 @@ fn load_file(
             mod_env.vars = mod_vars;
             mod_env.data = env.data.clone();
             mod_env.modules = env.modules.clone();
-            let sub = load_file(
+            mod_env.limits = env.limits;
+            let (sub, _) = load_file(
                 loader,
                 &module_path.join("main.hcl"),
                 &module_path,
                 &mod_env,
                 visited,
+                None,
             )
             .with_context(|| {
                 format!(
@@ -1133,6 +1738,8 @@ fn load_file(
             cfg.roles.extend(sub.roles);
             cfg.grants.extend(sub.grants);
             cfg.providers.extend(sub.providers);
+            cfg.sensitive_values.extend(sub.sensitive_values);
+            check_resource_limit(&cfg, 0, &env)?;
         }
     }
 
@@ -1699,7 +2306,8 @@ fn load_file(
         )?;
     }
 
-    // Handle output blocks
+    // Handle output blocks, which may also reference the resources above
+    env.resources = resource_scope(&cfg);
     for blk in body.blocks().filter(|b| b.identifier() == "output") {
         let label = blk
             .labels()
@@ -1714,15 +2322,90 @@ fn load_file(
             .with_context(|| format!("evaluating output '{}'", label))?;
         cfg.outputs.push(ast::AstOutput { name: label, value });
     }
+    let value = match eval {
+        Some(expr) => Some(expr_to_value(expr, &env).context("evaluating expression")?),
+        None => None,
+    };
 
     visited.pop();
-    Ok(cfg)
+    Ok((cfg, value))
+}
+
+/// The `<type>.<name>` objects outputs can reference. `name` and `schema`
+/// are the resolved SQL names (schemas only have `name`); tables and views
+/// also list their columns and enums their values.
+fn resource_scope(cfg: &ast::Config) -> HashMap<String, HashMap<String, Value>> {
+    fn object(
+        name: &str,
+        alt_name: &Option<String>,
+        schema: &Option<String>,
+        extra: Vec<(&str, Value)>,
+    ) -> Value {
+        let mut map = Map::new();
+        map.insert(
+            "name".to_string(),
+            Value::from(alt_name.as_deref().unwrap_or(name)),
+        );
+        map.insert(
+            "schema".to_string(),
+            Value::from(schema.as_deref().unwrap_or("public")),
+        );
+        for (key, value) in extra {
+            map.insert(key.to_string(), value);
+        }
+        Value::Object(map)
+    }
+    let strings = |items: Vec<&String>| {
+        Value::Array(items.into_iter().map(|s| Value::from(s.as_str())).collect())
+    };
+
+    let mut scope: HashMap<String, HashMap<String, Value>> = HashMap::new();
+    let mut add = |kind: &str, name: &str, value: Value| {
+        scope
+            .entry(kind.to_string())
+            .or_default()
+            .insert(name.to_string(), value);
+    };
+    for s in &cfg.schemas {
+        let name = Value::from(s.alt_name.as_deref().unwrap_or(&s.name));
+        let value = Value::Object(Map::from([("name".to_string(), name)]));
+        add("schema", &s.name, value);
+    }
+    for t in &cfg.tables {
+        let columns = strings(t.columns.iter().map(|c| &c.name).collect());
+        let value = object(&t.name, &t.alt_name, &t.schema, vec![("columns", columns)]);
+        add("table", &t.name, value);
+    }
+    for v in &cfg.views {
+        let columns = strings(v.columns.iter().collect());
+        let value = object(&v.name, &v.alt_name, &v.schema, vec![("columns", columns)]);
+        add("view", &v.name, value);
+    }
+    for m in &cfg.materialized {
+        let value = object(&m.name, &m.alt_name, &m.schema, vec![]);
+        add("materialized", &m.name, value);
+    }
+    for e in &cfg.enums {
+        let values = strings(e.values.iter().collect());
+        let value = object(&e.name, &e.alt_name, &e.schema, vec![("values", values)]);
+        add("enum", &e.name, value);
+    }
+    for f in &cfg.functions {
+        let value = object(&f.name, &f.alt_name, &f.schema, vec![]);
+        add("function", &f.name, value);
+    }
+    for s in &cfg.sequences {
+        let value = object(&s.name, &s.alt_name, &s.schema, vec![]);
+        add("sequence", &s.name, value);
+    }
+    scope
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::frontend::env::EnvVars;
+    use crate::test_support::{load_hcl, try_load_hcl};
     use hcl::value::Map;
 
     #[test]
@@ -1733,6 +2416,216 @@ mod tests {
         assert_eq!(v, Value::from(1));
     }
 
+    #[test]
+    fn special_calls_nested_in_function_arguments() {
+        let env = EnvVars {
+            locals: HashMap::from([("names".into(), Value::from(vec!["a", "b"]))]),
+            ..EnvVars::default()
+        };
+        let eval = |src: &str| {
+            let expr: hcl::Expression = src.parse().unwrap();
+            expr_to_value(&expr, &env).unwrap()
+        };
+        assert_eq!(
+            eval(r#"upper(true ? try(var.missing, "yes") : file("missing.txt"))"#),
+            Value::from("YES")
+        );
+        assert_eq!(
+            eval(r#"jsonencode({ v = try(var.missing, 1) })"#),
+            Value::from(r#"{"v":1}"#)
+        );
+        assert_eq!(
+            eval(r#"lower("X${try(var.missing, "Y")}")"#),
+            Value::from("xy")
+        );
+        assert_eq!(
+            eval(r#"join(",", [for n in local.names : try(var.missing[n], n)])"#),
+            Value::from("a,b")
+        );
+        assert_eq!(eval("max(try(var.missing, 1) + 1, 0)"), Value::from(2));
+    }
+
+    #[test]
+    fn negating_numbers_does_not_panic() {
+        let env = EnvVars::default();
+        let expr: hcl::Expression = "-5".parse().unwrap();
+        assert_eq!(expr_to_value(&expr, &env).unwrap(), Value::from(-5));
+        let expr = hcl::Expression::Operation(Box::new(hcl::expr::Operation::Unary(
+            hcl::expr::UnaryOp::new(
+                UnaryOperator::Neg,
+                hcl::Expression::Number(Number::from(i64::MIN)),
+            ),
+        )));
+        assert!(expr_to_value(&expr, &env).is_ok());
+    }
+
+    #[test]
+    fn malformed_config_returns_error() {
+        // Regression inputs from the load_config fuzz target.
+        for src in [
+            "table \"t\" { column \"c\" { type = -true } }",
+            "locals { x = substr(\"héllo\", 1, 2) }\ntable \"t\" { schema = local.y }",
+            "schema \"s\" { name = upper(",
+        ] {
+            let loader = crate::MapLoader {
+                files: HashMap::from([(PathBuf::from("/fuzz/main.hcl"), src.to_string())]),
+            };
+            let res = crate::load_config(Path::new("/fuzz/main.hcl"), &loader, EnvVars::default());
+            assert!(res.is_err(), "expected error for {src:?}");
+        }
+    }
+
+    #[test]
+    fn evaluates_arithmetic() {
+        let env = EnvVars::default();
+        let eval = |src: &str| expr_to_value(&src.parse::<hcl::Expression>().unwrap(), &env);
+        assert_eq!(eval("1 + 2 * 3").unwrap(), Value::from(7));
+        assert_eq!(eval("6 / 2 - 1").unwrap(), Value::from(2));
+        assert_eq!(eval("7 / 2").unwrap(), Value::from(3.5));
+        assert_eq!(eval("7 % 4").unwrap(), Value::from(3));
+        assert!(
+            eval("1 / 0")
+                .unwrap_err()
+                .to_string()
+                .contains("division by zero")
+        );
+        assert!(eval("\"a\" + 1").is_err());
+    }
+
+    #[test]
+    fn locals_resolve_regardless_of_order() {
+        let load = |src: &str| {
+            let loader = crate::MapLoader {
+                files: HashMap::from([(PathBuf::from("/root/main.hcl"), src.to_string())]),
+            };
+            crate::load_config(Path::new("/root/main.hcl"), &loader, EnvVars::default())
+        };
+
+        let cfg = load(
+            r#"
+            locals {
+              table = "${local.prefix}_users"
+              width = local.base + 1
+            }
+            locals {
+              prefix = upper(local.env)
+              base   = 41
+              env    = "app"
+            }
+            table "t" {
+              table_name = local.table
+              comment    = "${local.width}"
+              column "id" { type = "int" }
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.tables[0].alt_name.as_deref(), Some("APP_users"));
+        assert_eq!(cfg.tables[0].comment.as_deref(), Some("42"));
+
+        let err =
+            load("locals {\n  a = local.b + 1\n  b = local.c\n  c = local.a\n}\n").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("locals reference each other in a cycle: a -> b -> c -> a"),
+            "{err:#}"
+        );
+
+        let err = load("locals {\n  a = 1\n}\nlocals {\n  a = 2\n}\n").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("local 'a' is defined more than once"),
+            "{err:#}"
+        );
+
+        // Only real `local.<name>` references count, not text in strings.
+        let cfg = load(
+            r#"
+            locals {
+              a = "see local.b"
+              b = <<-EOT
+                ${local.c} and local.a
+              EOT
+              c = "x"
+            }
+            table "t" {
+              comment = local.b
+              column "id" { type = "int" }
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.tables[0].comment.as_deref(), Some("x and local.a\n"));
+    }
+
+    #[test]
+    fn deeply_nested_expression_errors_instead_of_overflowing() {
+        let env = EnvVars::default();
+        let mut expr = hcl::Expression::Number(Number::from(1));
+        for _ in 0..1_000 {
+            expr = hcl::Expression::Conditional(Box::new(hcl::expr::Conditional::new(
+                hcl::Expression::Bool(true),
+                expr,
+                hcl::Expression::Number(Number::from(0)),
+            )));
+        }
+        let err = expr_to_value(&expr, &env).unwrap_err();
+        assert!(err.to_string().contains("maximum depth of 64"));
+
+        // The depth counter unwinds after an error and the limit is configurable.
+        let mut expr = hcl::Expression::Number(Number::from(1));
+        for _ in 0..50 {
+            expr = hcl::Expression::Array(vec![expr]);
+        }
+        assert!(expr_to_value(&expr, &env).is_ok());
+        let env = EnvVars {
+            limits: crate::frontend::env::Limits {
+                max_expr_depth: 20,
+                ..Default::default()
+            },
+            ..EnvVars::default()
+        };
+        let err = expr_to_value(&expr, &env).unwrap_err();
+        assert!(err.to_string().contains("maximum depth of 20"));
+    }
+
+    #[test]
+    fn deeply_nested_hcl_object_returns_error() {
+        let load = |depth: usize| {
+            let src = format!(
+                "locals {{\n  x = {}1{}\n}}\n",
+                "{ a = ".repeat(depth),
+                " }".repeat(depth)
+            );
+            let loader = crate::MapLoader {
+                files: HashMap::from([(PathBuf::from("/deep/main.hcl"), src)]),
+            };
+            crate::load_config(Path::new("/deep/main.hcl"), &loader, EnvVars::default())
+        };
+        load(30).unwrap();
+        let err = load(10_000).unwrap_err();
+        assert!(format!("{err:#}").contains("expression nesting exceeds maximum depth"));
+    }
+
+    #[test]
+    fn nesting_depth_ignores_brackets_in_literals_and_comments() {
+        let parens = "(".repeat(100);
+        check_nesting_depth(&format!("a = \"{parens}\"\n"), 10).unwrap();
+        check_nesting_depth(&format!("# {parens}\n// {parens}\n/* {parens} */\n"), 10).unwrap();
+        check_nesting_depth(&format!("a = <<-SQL\n  {parens}\n  SQL\nb = [1]\n"), 10).unwrap();
+        check_nesting_depth(&format!("a = <<EOF\nEOF\nb = \"{parens}\"\n"), 10).unwrap();
+
+        // Interpolations are expressions, so their brackets still count.
+        let deep = format!("a = \"${{{}1{}}}\"\n", "[".repeat(20), "]".repeat(20));
+        assert!(check_nesting_depth(&deep, 10).is_err());
+        let deep = format!(
+            "a = <<EOF\n${{{}1{}}}\nEOF\n",
+            "(".repeat(20),
+            ")".repeat(20)
+        );
+        assert!(check_nesting_depth(&deep, 10).is_err());
+        // Code after a closed interpolation is scanned as a string again.
+        check_nesting_depth(&format!("a = \"${{x}} {parens}\"\n"), 10).unwrap();
+    }
+
     #[test]
     fn evaluates_for_expression() {
         let expr: hcl::Expression = "[for x in [1,2,3] : x]".parse().unwrap();
@@ -1742,6 +2635,35 @@ mod tests {
         assert_eq!(v, expected);
     }
 
+    #[test]
+    fn evaluates_filtered_list_and_map_comprehensions() {
+        let mut env = EnvVars::default();
+        env.vars
+            .insert("tables".into(), Value::from(vec!["users", "", "orders"]));
+        let mut columns: Map<String, Value> = Map::new();
+        for (name, ty) in [("id", "uuid"), ("email", "citext"), ("note", "")] {
+            let mut col: Map<String, Value> = Map::new();
+            col.insert("type".into(), Value::from(ty));
+            columns.insert(name.into(), Value::Object(col));
+        }
+        env.vars.insert("m".into(), Value::Object(columns));
+
+        let expr: hcl::Expression =
+            r#"[for t in var.tables : upper(t) if t != ""]"#.parse().unwrap();
+        assert_eq!(
+            expr_to_value(&expr, &env).unwrap(),
+            Value::from(vec!["USERS", "ORDERS"])
+        );
+
+        let expr: hcl::Expression = r#"{for k, v in var.m : k => v.type if v.type != ""}"#
+            .parse()
+            .unwrap();
+        let mut expected: Map<String, Value> = Map::new();
+        expected.insert("email".into(), Value::from("citext"));
+        expected.insert("id".into(), Value::from("uuid"));
+        assert_eq!(expr_to_value(&expr, &env).unwrap(), Value::Object(expected));
+    }
+
     #[test]
     fn check_var_type_supports_object_schema() {
         let ty: VarType = "list(object({ name = string, type = string, nullable = bool }))"
@@ -1830,4 +2752,269 @@ mod tests {
         let err_msg = err.to_string();
         assert!(err_msg.contains("variable 'values.c[1]' expected type number"));
     }
+
+    #[test]
+    fn module_dynamic_blocks_iterate_module_inputs() {
+        let mut files = HashMap::new();
+        files.insert(
+            PathBuf::from("/root/main.hcl"),
+            r#"
+            module "users" {
+              source  = "/root/table"
+              name    = "users"
+              columns = [
+                { name = "id", type = "integer", nullable = false },
+                { name = "email", type = "text", nullable = true },
+              ]
+            }
+
+            module "tags" {
+              source  = "/root/table"
+              name    = "tags"
+              columns = [{ name = "label", type = "text", nullable = false }]
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            PathBuf::from("/root/table/main.hcl"),
+            r#"
+            variable "name" {}
+            variable "columns" {}
+
+            table "t" {
+              table_name = var.name
+
+              dynamic "column" {
+                for_each = var.columns
+                labels   = [each.value.name]
+                content {
+                  type     = each.value.type
+                  nullable = each.value.nullable
+                  comment  = "${var.name} column ${each.key}"
+                }
+              }
+            }
+            "#
+            .to_string(),
+        );
+
+        let loader = crate::MapLoader { files };
+        let cfg =
+            crate::load_config(Path::new("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let columns: Vec<Vec<(&str, &str, bool, &str)>> = cfg
+            .tables
+            .iter()
+            .map(|t| {
+                t.columns
+                    .iter()
+                    .map(|c| {
+                        let comment = c.comment.as_deref().unwrap();
+                        (c.name.as_str(), c.r#type.as_str(), c.nullable, comment)
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                vec![
+                    ("id", "integer", false, "users column 0"),
+                    ("email", "text", true, "users column 1"),
+                ],
+                vec![("label", "text", false, "tags column 0")],
+            ]
+        );
+    }
+
+    #[test]
+    fn for_tables_policy_applies_to_matching_tables() {
+        let cfg = load_hcl(
+            r#"
+            table "projects" {
+              column "id" { type = "uuid" }
+              column "tenant_id" { type = "uuid" }
+            }
+            table "tasks" {
+              table_name = "project_tasks"
+              schema     = "app"
+              column "id" { type = "uuid" }
+              column "tenant_id" { type = "uuid" }
+            }
+            table "countries" {
+              column "code" { type = "text" }
+            }
+
+            policy "tenant_isolation" {
+              for_tables = { has_column = "tenant_id" }
+              using      = "tenant_id = current_setting('app.tenant_id')::uuid"
+            }
+            "#,
+        );
+        let targets: Vec<_> = cfg
+            .policies
+            .iter()
+            .map(|p| (p.schema.as_deref(), p.table.as_str()))
+            .collect();
+        assert_eq!(
+            targets,
+            [(None, "projects"), (Some("app"), "project_tasks")]
+        );
+        let sql = crate::generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(
+            sql.contains("CREATE POLICY \"tenant_isolation\" ON \"public\".\"projects\""),
+            "{sql}"
+        );
+        assert!(
+            sql.contains("CREATE POLICY \"tenant_isolation\" ON \"app\".\"project_tasks\""),
+            "{sql}"
+        );
+        assert!(!sql.contains("\"countries\"\n    USING"), "{sql}");
+        assert_eq!(sql.matches("CREATE POLICY").count(), 2, "{sql}");
+
+        let err = try_load_hcl(
+            r#"
+            policy "p" {
+              table      = "projects"
+              for_tables = { has_column = "tenant_id" }
+            }
+            "#,
+        )
+        .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("policy 'p' sets both 'table' and 'for_tables'"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn file_function_reads_sql_relative_to_including_file() {
+        let mut files = HashMap::new();
+        files.insert(
+            PathBuf::from("/root/main.hcl"),
+            r#"
+            module "views" { source = "./views" }
+
+            function "touch" {
+              language = "plpgsql"
+              returns  = "trigger"
+              body     = file("./fn.sql")
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            PathBuf::from("/root/fn.sql"),
+            "BEGIN NEW.updated_at = now(); RETURN NEW; END;".to_string(),
+        );
+        files.insert(
+            PathBuf::from("/root/views/main.hcl"),
+            r#"
+            locals { dir = "sql" }
+            variable "name" { default = "active_users.sql" }
+
+            view "active_users" {
+              sql = trimspace(file("sql/active_users.sql"))
+            }
+
+            view "templated" {
+              sql     = trimspace(file("${local.dir}/${var.name}"))
+              comment = true ? "Reads ${trimspace(file("sql/comment.txt"))}" : file("sql/nope.sql")
+            }
+
+            view "documented" {
+              sql = <<-EOT
+                SELECT 'file("sql/nope.sql")' AS note
+              EOT
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            PathBuf::from("/root/views/sql/active_users.sql"),
+            "SELECT * FROM users WHERE active\n".to_string(),
+        );
+        files.insert(
+            PathBuf::from("/root/views/sql/comment.txt"),
+            "the active users\n".to_string(),
+        );
+        let loader = crate::MapLoader { files };
+        let cfg =
+            crate::load_config(Path::new("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        assert_eq!(
+            cfg.functions[0].body,
+            "BEGIN NEW.updated_at = now(); RETURN NEW; END;"
+        );
+        assert_eq!(cfg.views[0].sql, "SELECT * FROM users WHERE active");
+        assert_eq!(cfg.views[1].sql, "SELECT * FROM users WHERE active");
+        assert_eq!(
+            cfg.views[1].comment.as_deref(),
+            Some("Reads the active users")
+        );
+        assert!(cfg.views[2].sql.contains("file(\"sql/nope.sql\")"));
+
+        let err = try_load_hcl(r#"function "f" { body = file("./missing.sql") }"#).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("file: reading /root/./missing.sql"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn string_functions_build_names_and_comments() {
+        let cfg = load_hcl(
+            r#"
+            variable "env" { default = "prod" }
+            variable "tags" { default = ["billing", "audit"] }
+
+            table "users" {
+              table_name = format("%s_users_%d", lower(var.env), 2)
+              comment    = join(",", var.tags)
+              column "id" {
+                type    = "text"
+                comment = upper(var.env)
+              }
+            }
+            "#,
+        );
+        let table = &cfg.tables[0];
+        assert_eq!(table.alt_name.as_deref(), Some("prod_users_2"));
+        assert_eq!(table.comment.as_deref(), Some("billing,audit"));
+        assert_eq!(table.columns[0].comment.as_deref(), Some("PROD"));
+    }
+
+    #[test]
+    fn merge_layers_column_overrides() {
+        let cfg = load_hcl(
+            r#"
+            locals {
+              base      = { id = "bigint", created_at = "timestamptz" }
+              overrides = { id = "uuid", email = "text" }
+            }
+
+            table "users" {
+              dynamic "column" {
+                for_each = merge(local.base, local.overrides)
+                labels   = [each.key]
+                content {
+                  type = each.value
+                }
+              }
+            }
+            "#,
+        );
+        let columns: Vec<_> = cfg.tables[0]
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.r#type.as_str()))
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                ("id", "uuid"),
+                ("created_at", "timestamptz"),
+                ("email", "text")
+            ]
+        );
+    }
 }
@@ -4,20 +4,25 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result, bail};
 use hcl::Body;
 use hcl::value::Map;
+use sha2::{Digest, Sha256};
 
 use crate::Loader;
-use crate::frontend::core::get_attr_string;
+use crate::frontend::ast;
+use crate::frontend::core::{get_attr_bool, get_attr_string};
 use crate::frontend::env::EnvVars;
+use crate::ir::TriggerCreate;
 use crate::prisma::{
     self, BlockAttribute, ConfigBlock, DefaultValue, FieldAttribute, Model, Schema, View,
 };
 
 /// Load all `data` blocks in the current body and populate the evaluation environment.
+/// Resources synthesized by a data source are added to `cfg`.
 pub fn load_data_sources(
     loader: &dyn Loader,
     base: &Path,
     body: &Body,
     env: &mut EnvVars,
+    cfg: &mut ast::Config,
 ) -> Result<()> {
     for blk in body.blocks().filter(|b| b.identifier() == "data") {
         let dtype = blk
@@ -34,7 +39,12 @@ pub fn load_data_sources(
             .to_string();
 
         let value = match dtype.as_str() {
-            "prisma_schema" => load_prisma_schema(loader, base, blk.body(), env)?,
+            "prisma_schema" => load_prisma_schema(loader, base, blk.body(), env, cfg)?,
+            "csv" => load_csv(loader, base, blk.body(), env)?,
+            "json_file" | "yaml_file" => {
+                load_structured_file(&dtype, loader, base, blk.body(), env)?
+            }
+            "http" => load_http(loader, blk.body(), env)?,
             other => bail!("unsupported data source type '{other}'"),
         };
 
@@ -51,6 +61,7 @@ fn load_prisma_schema(
     base: &Path,
     body: &Body,
     env: &EnvVars,
+    cfg: &mut ast::Config,
 ) -> Result<hcl::Value> {
     let file = get_attr_string(body, "file", env)?
         .context("prisma_schema data source requires 'file' attribute")?;
@@ -59,9 +70,312 @@ fn load_prisma_schema(
         .load(&path)
         .with_context(|| format!("reading Prisma schema from {}", path.display()))?;
     let schema = prisma::parse_schema_str(&contents)?;
+    if get_attr_bool(body, "updated_at_triggers", env)?.unwrap_or(false) {
+        add_updated_at_triggers(&schema, cfg);
+    }
     Ok(schema_to_value(schema))
 }
 
+/// With `updated_at_triggers = true`, every model with `@updatedAt` fields
+/// gets a `<table>_set_updated_at` function and a `BEFORE UPDATE` trigger
+/// stamping those columns with `now()`, since Postgres has no equivalent of
+/// Prisma's client-side behaviour.
+fn add_updated_at_triggers(schema: &Schema, cfg: &mut ast::Config) {
+    for model in &schema.models {
+        let columns: Vec<String> = model
+            .fields
+            .iter()
+            .filter(|f| f.attributes.iter().any(is_updated_at))
+            .map(|f| {
+                f.attributes
+                    .iter()
+                    .find_map(|a| match a {
+                        FieldAttribute::Map(m) => Some(m.clone()),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| f.name.to_string())
+            })
+            .collect();
+        if columns.is_empty() {
+            continue;
+        }
+        let table = model
+            .attributes
+            .iter()
+            .find_map(|a| match a {
+                BlockAttribute::Map(m) => Some(m.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| model.name.to_string());
+        let table_schema = model.attributes.iter().find_map(|a| match a {
+            BlockAttribute::Schema(s) => Some(s.clone()),
+            _ => None,
+        });
+        let name = format!("{table}_set_updated_at");
+        let mut body = String::from("BEGIN\n");
+        for column in &columns {
+            body.push_str(&format!(
+                "  NEW.\"{}\" = now();\n",
+                column.replace('"', "\"\"")
+            ));
+        }
+        body.push_str("  RETURN NEW;\nEND;");
+
+        cfg.functions.push(ast::AstFunction {
+            name: name.clone(),
+            alt_name: None,
+            schema: table_schema.clone(),
+            language: "plpgsql".into(),
+            parameters: Vec::new(),
+            returns: "trigger".into(),
+            replace: true,
+            volatility: None,
+            strict: false,
+            security: None,
+            cost: None,
+            body,
+            lint_ignore: Vec::new(),
+            comment: None,
+        });
+        cfg.triggers.push(ast::AstTrigger {
+            name: name.clone(),
+            alt_name: None,
+            schema: table_schema.clone(),
+            table,
+            timing: "BEFORE".into(),
+            events: vec!["UPDATE".into()],
+            update_columns: Vec::new(),
+            level: "ROW".into(),
+            function: name,
+            function_schema: table_schema,
+            when: None,
+            referencing_old_table: None,
+            referencing_new_table: None,
+            constraint: false,
+            deferrable: false,
+            initially_deferred: false,
+            create: TriggerCreate::Create,
+            comment: None,
+        });
+    }
+}
+
+fn is_updated_at(attr: &FieldAttribute) -> bool {
+    matches!(attr, FieldAttribute::Raw(raw) if raw_field_attribute_name(raw) == Some("updatedAt"))
+}
+
+/// `data "csv" "x" { file = "..." }`. With `header = true` (the default) the
+/// first record names the columns and `rows` holds one object per following
+/// record; with `header = false` each row is a list of fields. `infer_types`
+/// turns numeric and `true`/`false` fields into numbers and bools.
+fn load_csv(loader: &dyn Loader, base: &Path, body: &Body, env: &EnvVars) -> Result<hcl::Value> {
+    let file =
+        get_attr_string(body, "file", env)?.context("csv data source requires 'file' attribute")?;
+    let path = resolve_relative(base, &file);
+    let contents = loader
+        .load(&path)
+        .with_context(|| format!("reading CSV from {}", path.display()))?;
+    let header_row = get_attr_bool(body, "header", env)?.unwrap_or(true);
+    let infer_types = get_attr_bool(body, "infer_types", env)?.unwrap_or(false);
+    let field = |s: String| {
+        if infer_types {
+            infer_csv_value(s)
+        } else {
+            hcl::Value::String(s)
+        }
+    };
+    let mut records = parse_csv(&contents)
+        .with_context(|| format!("parsing CSV {}", path.display()))?
+        .into_iter();
+    let mut root = Map::<String, hcl::Value>::new();
+    if !header_row {
+        let rows = records
+            .map(|record| hcl::Value::Array(record.into_iter().map(field).collect()))
+            .collect();
+        root.insert("rows".to_string(), hcl::Value::Array(rows));
+        return Ok(hcl::Value::Object(root));
+    }
+    let header = records.next().unwrap_or_default();
+    let mut rows = Vec::new();
+    for (i, record) in records.enumerate() {
+        if record.len() != header.len() {
+            bail!(
+                "CSV {} record {} has {} fields, expected {}",
+                path.display(),
+                i + 2,
+                record.len(),
+                header.len()
+            );
+        }
+        let row: Map<String, hcl::Value> = header
+            .iter()
+            .cloned()
+            .zip(record.into_iter().map(field))
+            .collect();
+        rows.push(hcl::Value::Object(row));
+    }
+    root.insert(
+        "columns".to_string(),
+        hcl::Value::Array(header.into_iter().map(hcl::Value::String).collect()),
+    );
+    root.insert("rows".to_string(), hcl::Value::Array(rows));
+    Ok(hcl::Value::Object(root))
+}
+
+/// `data "json_file"` / `data "yaml_file"`: the parsed document as an HCL value.
+fn load_structured_file(
+    dtype: &str,
+    loader: &dyn Loader,
+    base: &Path,
+    body: &Body,
+    env: &EnvVars,
+) -> Result<hcl::Value> {
+    let file = get_attr_string(body, "file", env)?
+        .with_context(|| format!("{dtype} data source requires 'file' attribute"))?;
+    let path = resolve_relative(base, &file);
+    let contents = loader
+        .load(&path)
+        .with_context(|| format!("reading {}", path.display()))?;
+    let parsed: serde_json::Value = if dtype == "yaml_file" {
+        serde_norway::from_str(&contents)
+            .with_context(|| format!("parsing YAML {}", path.display()))?
+    } else {
+        serde_json::from_str(&contents)
+            .with_context(|| format!("parsing JSON {}", path.display()))?
+    };
+    hcl::to_value(parsed).with_context(|| format!("converting {}", path.display()))
+}
+
+/// `data "http" "x" { url = "..." }`: `body` holds the response text. With
+/// `format = "json"` the body is also parsed into `json`. An optional `hash`
+/// pins the expected content.
+fn load_http(loader: &dyn Loader, body: &Body, env: &EnvVars) -> Result<hcl::Value> {
+    let url =
+        get_attr_string(body, "url", env)?.context("http data source requires 'url' attribute")?;
+    let format = get_attr_string(body, "format", env)?.unwrap_or_else(|| "text".to_string());
+    let pinned = get_attr_string(body, "hash", env)?;
+    if let Some(hash) = &pinned
+        && !is_content_hash(hash)
+    {
+        bail!("hash for {url} must be 'sha256:' followed by 64 lowercase hex digits, got '{hash}'");
+    }
+    let text = match &pinned {
+        Some(hash) => loader.fetch_pinned(&url, hash),
+        None => loader.fetch(&url),
+    }
+    .with_context(|| format!("fetching {url}"))?;
+    if let Some(expected) = &pinned {
+        verify_hash(&url, &text, expected)?;
+    }
+    let mut root = Map::<String, hcl::Value>::new();
+    match format.as_str() {
+        "text" => {}
+        "json" => {
+            let parsed: serde_json::Value = serde_json::from_str(&text)
+                .with_context(|| format!("parsing JSON response from {url}"))?;
+            root.insert(
+                "json".to_string(),
+                hcl::to_value(parsed).with_context(|| format!("converting {url}"))?,
+            );
+        }
+        other => bail!("http data source has invalid format '{other}': expected text or json"),
+    }
+    root.insert("body".to_string(), hcl::Value::String(text));
+    Ok(hcl::Value::Object(root))
+}
+
+/// Content hash used to pin external data: `sha256:` followed by the hex
+/// digest.
+pub fn content_hash(contents: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(contents.as_bytes()))
+}
+
+/// Whether `s` has the exact form produced by [`content_hash`].
+pub fn is_content_hash(s: &str) -> bool {
+    s.strip_prefix("sha256:").is_some_and(|hex| {
+        hex.len() == 64 && hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    })
+}
+
+fn verify_hash(source: &str, contents: &str, expected: &str) -> Result<()> {
+    let actual = content_hash(contents);
+    if actual != expected {
+        bail!(
+            "content of {source} does not match its pinned hash: expected {expected}, got {actual}; update `hash` if the change is intended"
+        );
+    }
+    Ok(())
+}
+
+/// Numbers and `true`/`false` become typed values; anything else, including
+/// empty fields, stays a string.
+fn infer_csv_value(s: String) -> hcl::Value {
+    match s.as_str() {
+        "true" => return hcl::Value::Bool(true),
+        "false" => return hcl::Value::Bool(false),
+        _ => {}
+    }
+    if let Ok(i) = s.parse::<i64>() {
+        return hcl::Value::Number(hcl::Number::from(i));
+    }
+    if let Some(n) = s.parse::<f64>().ok().and_then(hcl::Number::from_f64) {
+        return hcl::Value::Number(n);
+    }
+    hcl::Value::String(s)
+}
+
+/// Split CSV text into records (RFC 4180): fields may be wrapped in double
+/// quotes to contain commas, newlines or `""`-escaped quotes. Blank lines are
+/// skipped.
+fn parse_csv(input: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            ',' => {
+                record.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if !record.is_empty() || !field.is_empty() || quoted {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                quoted = false;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("unterminated quoted field");
+    }
+    if !record.is_empty() || !field.is_empty() || quoted {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
 fn resolve_relative(base: &Path, value: &str) -> PathBuf {
     let p = Path::new(value);
     if p.is_absolute() {
@@ -73,11 +387,12 @@ fn resolve_relative(base: &Path, value: &str) -> PathBuf {
 
 fn schema_to_value(schema: Schema) -> hcl::Value {
     let mut root = Map::<String, hcl::Value>::new();
-    root.insert("models".into(), models_to_value(&schema.models));
-    root.insert("views".into(), views_to_value(&schema.views));
+    let enums = &schema.enums;
+    root.insert("models".into(), models_to_value(&schema.models, enums));
+    root.insert("views".into(), views_to_value(&schema.views, enums));
     root.insert(
         "composite_types".into(),
-        composite_types_to_value(&schema.composite_types),
+        composite_types_to_value(&schema.composite_types, enums),
     );
     root.insert(
         "type_aliases".into(),
@@ -99,23 +414,23 @@ fn schema_to_value(schema: Schema) -> hcl::Value {
     hcl::Value::Object(root)
 }
 
-fn models_to_value(models: &[Model]) -> hcl::Value {
+fn models_to_value(models: &[Model], enums: &[prisma::Enum]) -> hcl::Value {
     let mut map = Map::<String, hcl::Value>::new();
     for model in models {
         map.insert(
             model.name.to_string(),
-            model_like_to_value(&model.name, &model.fields, &model.attributes),
+            model_like_to_value(&model.name, &model.fields, &model.attributes, enums),
         );
     }
     hcl::Value::Object(map)
 }
 
-fn views_to_value(views: &[View]) -> hcl::Value {
+fn views_to_value(views: &[View], enums: &[prisma::Enum]) -> hcl::Value {
     let mut map = Map::<String, hcl::Value>::new();
     for view in views {
         map.insert(
             view.name.to_string(),
-            model_like_to_value(&view.name, &view.fields, &view.attributes),
+            model_like_to_value(&view.name, &view.fields, &view.attributes, enums),
         );
     }
     hcl::Value::Object(map)
@@ -125,20 +440,21 @@ fn model_like_to_value(
     name: &prisma::Identifier,
     fields: &[prisma::Field],
     attributes: &[BlockAttribute],
+    enums: &[prisma::Enum],
 ) -> hcl::Value {
     let mut model_map = Map::new();
     model_map.insert("name".into(), hcl::Value::String(name.to_string()));
-    model_map.insert("fields".into(), fields_to_value(fields));
+    model_map.insert("fields".into(), fields_to_value(fields, enums));
     model_map.insert("attributes".into(), block_attributes_to_value(attributes));
     hcl::Value::Object(model_map)
 }
 
-fn composite_types_to_value(types: &[prisma::CompositeType]) -> hcl::Value {
+fn composite_types_to_value(types: &[prisma::CompositeType], enums: &[prisma::Enum]) -> hcl::Value {
     let mut map = Map::<String, hcl::Value>::new();
     for ct in types {
         let mut ct_map = Map::new();
         ct_map.insert("name".into(), hcl::Value::String(ct.name.to_string()));
-        ct_map.insert("fields".into(), fields_to_value(&ct.fields));
+        ct_map.insert("fields".into(), fields_to_value(&ct.fields, enums));
         map.insert(ct.name.to_string(), hcl::Value::Object(ct_map));
     }
     hcl::Value::Object(map)
@@ -179,7 +495,7 @@ fn custom_blocks_to_value(blocks: &[prisma::CustomBlock]) -> hcl::Value {
     hcl::Value::Object(map)
 }
 
-fn fields_to_value(fields: &[prisma::Field]) -> hcl::Value {
+fn fields_to_value(fields: &[prisma::Field], enums: &[prisma::Enum]) -> hcl::Value {
     let mut map = Map::<String, hcl::Value>::new();
     for field in fields {
         let mut field_map = Map::new();
@@ -189,11 +505,62 @@ fn fields_to_value(fields: &[prisma::Field]) -> hcl::Value {
             "attributes".into(),
             field_attributes_to_value(&field.attributes),
         );
+        field_map.insert("is_id".into(), hcl::Value::Bool(has_id(&field.attributes)));
+        field_map.insert(
+            "is_unique".into(),
+            hcl::Value::Bool(has_unique(&field.attributes)),
+        );
+        field_map.insert(
+            "is_relation".into(),
+            hcl::Value::Bool(has_relation(&field.attributes)),
+        );
+        if let Some(default) = sql_default(field, enums) {
+            field_map.insert("default".into(), hcl::Value::String(default));
+        }
         map.insert(field.name.to_string(), hcl::Value::Object(field_map));
     }
     hcl::Value::Object(map)
 }
 
+/// The SQL column default matching a field's `@default`, when Postgres can
+/// express it. Enum defaults use the value's `@map` name, the label the
+/// database stores. `autoincrement()`, `cuid()` and the like have no SQL
+/// counterpart here and yield `None`.
+fn sql_default(field: &prisma::Field, enums: &[prisma::Enum]) -> Option<String> {
+    let default = field.attributes.iter().find_map(|a| match a {
+        FieldAttribute::Default(d) => Some(d),
+        _ => None,
+    })?;
+    match default {
+        DefaultValue::Now => Some("now()".into()),
+        DefaultValue::Uuid => Some("gen_random_uuid()".into()),
+        DefaultValue::AutoIncrement => None,
+        DefaultValue::DbGenerated(expr) => Some(expr.clone()),
+        DefaultValue::Expression(expr) => {
+            if let Some(e) = enums
+                .iter()
+                .find(|e| e.name.to_string() == field.r#type.name)
+            {
+                let value = e.values.iter().find(|v| v.name.to_string() == *expr)?;
+                let label = value.mapped_name.as_deref().unwrap_or(expr);
+                return Some(sql_string_literal(label));
+            }
+            if expr.starts_with('"') {
+                let s: String = serde_json::from_str(expr).ok()?;
+                Some(sql_string_literal(&s))
+            } else if expr.parse::<f64>().is_ok() || expr == "true" || expr == "false" {
+                Some(expr.clone())
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn sql_string_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
 fn enums_to_value(enums: &[prisma::Enum]) -> hcl::Value {
     let mut map = Map::<String, hcl::Value>::new();
     for enm in enums {
@@ -268,18 +635,15 @@ fn field_attributes_to_value(attrs: &[FieldAttribute]) -> hcl::Value {
         .collect();
     map.insert("raw".into(), hcl::Value::Array(raw));
 
-    if attrs.iter().any(|a| {
-        matches!(a, FieldAttribute::Id)
-            || matches!(a, FieldAttribute::Raw(raw) if raw_field_attribute_name(raw) == Some("id"))
-    }) {
+    if has_id(attrs) {
         map.insert("id".into(), hcl::Value::Bool(true));
     }
-    if attrs.iter().any(|a| {
-        matches!(a, FieldAttribute::Unique)
-            || matches!(a, FieldAttribute::Raw(raw) if raw_field_attribute_name(raw) == Some("unique"))
-    }) {
+    if has_unique(attrs) {
         map.insert("unique".into(), hcl::Value::Bool(true));
     }
+    if attrs.iter().any(is_updated_at) {
+        map.insert("updated_at".into(), hcl::Value::Bool(true));
+    }
     if let Some(default) = attrs.iter().find_map(|a| match a {
         FieldAttribute::Default(d) => Some(d),
         _ => None,
@@ -308,6 +672,27 @@ fn field_attributes_to_value(attrs: &[FieldAttribute]) -> hcl::Value {
     hcl::Value::Object(map)
 }
 
+fn has_id(attrs: &[FieldAttribute]) -> bool {
+    attrs.iter().any(|a| {
+        matches!(a, FieldAttribute::Id)
+            || matches!(a, FieldAttribute::Raw(raw) if raw_field_attribute_name(raw) == Some("id"))
+    })
+}
+
+fn has_unique(attrs: &[FieldAttribute]) -> bool {
+    attrs.iter().any(|a| {
+        matches!(a, FieldAttribute::Unique)
+            || matches!(a, FieldAttribute::Raw(raw) if raw_field_attribute_name(raw) == Some("unique"))
+    })
+}
+
+fn has_relation(attrs: &[FieldAttribute]) -> bool {
+    attrs.iter().any(|a| {
+        matches!(a, FieldAttribute::Relation(_))
+            || matches!(a, FieldAttribute::Raw(raw) if raw_field_attribute_name(raw) == Some("relation"))
+    })
+}
+
 fn block_attributes_to_value(attrs: &[BlockAttribute]) -> hcl::Value {
     let mut map = Map::<String, hcl::Value>::new();
     let raw: Vec<hcl::Value> = attrs
@@ -323,6 +708,13 @@ fn block_attributes_to_value(attrs: &[BlockAttribute]) -> hcl::Value {
         map.insert("map".into(), hcl::Value::String(name));
     }
 
+    if let Some(schema) = attrs.iter().find_map(|a| match a {
+        BlockAttribute::Schema(s) => Some(s.clone()),
+        _ => None,
+    }) {
+        map.insert("schema".into(), hcl::Value::String(schema));
+    }
+
     hcl::Value::Object(map)
 }
 
@@ -13,6 +13,7 @@ use super::ast::VarValidation;
 /// - `data.<type>.<name>` for values loaded from data sources
 /// - `each.key`/`each.value` inside `for_each` blocks
 /// - `count.index` inside blocks using the `count` attribute
+/// - `<type>.<name>` for resources such as `table.users`, inside `output` values
 ///
 /// # Example
 /// ```
@@ -25,8 +26,10 @@ use super::ast::VarValidation;
 ///     locals: HashMap::from([( "name".into(), Value::from("bob"))]),
 ///     modules: HashMap::new(),
 ///     data: HashMap::new(),
+///     resources: HashMap::new(),
 ///     each: None,
 ///     count: None,
+///     limits: Default::default(),
 /// };
 /// // `local.name` resolves to "bob" while `var.name` resolves to "world".
 /// ```
@@ -40,10 +43,46 @@ pub struct EnvVars {
     pub modules: HashMap<String, HashMap<String, Value>>,
     /// Values loaded via `data` blocks, referenced as `data.<type>.<name>`.
     pub data: HashMap<String, HashMap<String, Value>>,
+    /// Resources parsed so far, referenced as `<type>.<name>` (e.g.
+    /// `table.users.name`). Only populated while evaluating outputs.
+    pub resources: HashMap<String, HashMap<String, Value>>,
     /// Key/value for the current iteration of a `for_each` block, enabling `each.key` and `each.value`.
     pub each: Option<(Value, Value)>, // (key, value)
     /// Index for `count`-based iterations, enabling `count.index`.
     pub count: Option<usize>,
+    /// Evaluation limits guarding against runaway input.
+    pub limits: Limits,
+}
+
+/// Default for [`Limits::max_expr_depth`].
+pub const DEFAULT_MAX_EXPR_DEPTH: usize = 64;
+/// Default for [`Limits::max_resources`].
+pub const DEFAULT_MAX_RESOURCES: usize = 100_000;
+/// Default for [`Limits::max_module_depth`].
+pub const DEFAULT_MAX_MODULE_DEPTH: usize = 32;
+
+/// Upper bounds applied while evaluating a configuration. Exceeding any of
+/// them aborts loading with an error instead of exhausting the stack or memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum nesting depth of a single expression (conditionals, objects,
+    /// arrays, operators, ...).
+    pub max_expr_depth: usize,
+    /// Maximum number of resources a configuration may expand to, including
+    /// everything produced by `for_each`, `count` and modules.
+    pub max_resources: usize,
+    /// Maximum depth of nested `module` blocks.
+    pub max_module_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_expr_depth: DEFAULT_MAX_EXPR_DEPTH,
+            max_resources: DEFAULT_MAX_RESOURCES,
+            max_module_depth: DEFAULT_MAX_MODULE_DEPTH,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -109,6 +148,7 @@ pub struct VarSpec {
     pub default: Option<Value>,
     pub r#type: Option<VarType>,
     pub validation: Option<VarValidation>,
+    pub sensitive: bool,
 }
 
 struct TypeParser<'a> {
@@ -29,6 +29,12 @@ pub fn execute_for_each<T: ForEachSupport>(
     }
     if let Some(fe) = for_each_expr {
         let coll = core::expr_to_value(fe.expr(), env)?;
+        let len = match &coll {
+            hcl::Value::Array(arr) => arr.len(),
+            hcl::Value::Object(obj) => obj.len(),
+            _ => 0,
+        };
+        check_resource_limit(config, len, env)?;
         for_each_iter(&coll, &mut |k, v| {
             let mut iter_env = env.clone();
             iter_env.each = Some((k.clone(), v.clone()));
@@ -45,6 +51,7 @@ pub fn execute_for_each<T: ForEachSupport>(
                 as usize,
             other => bail!("count expects number, got {other:?}"),
         };
+        check_resource_limit(config, times, env)?;
         for i in 0..times {
             let mut iter_env = env.clone();
             iter_env.count = Some(i);
@@ -52,12 +59,23 @@ pub fn execute_for_each<T: ForEachSupport>(
             T::add_to_config(item, config);
         }
     } else {
+        check_resource_limit(config, 1, env)?;
         let item = T::parse_one(name, body, env)?;
         T::add_to_config(item, config);
     }
     Ok(())
 }
 
+/// Fail if adding `additional` resources to `config` would exceed
+/// [`Limits::max_resources`](crate::frontend::env::Limits::max_resources).
+pub fn check_resource_limit(config: &ast::Config, additional: usize, env: &EnvVars) -> Result<()> {
+    let max = env.limits.max_resources;
+    if config.resource_count().saturating_add(additional) > max {
+        bail!("configuration expands to more than {max} resources (max_resources limit)");
+    }
+    Ok(())
+}
+
 /// Iterator function for for_each loops
 pub fn for_each_iter<F>(collection: &hcl::Value, f: &mut F) -> Result<()>
 where
@@ -10,6 +10,9 @@ pub fn lower_config(ast: ast::Config) -> ir::Config {
         });
     }
 
+    let tables: Vec<ir::TableSpec> = ast.tables.into_iter().map(Into::into).collect();
+    let policies = lower_policies(ast.policies, &tables);
+
     ir::Config {
         providers,
         functions: ast.functions.into_iter().map(Into::into).collect(),
@@ -26,12 +29,12 @@ pub fn lower_config(ast: ast::Config) -> ir::Config {
         enums: ast.enums.into_iter().map(Into::into).collect(),
         domains: ast.domains.into_iter().map(Into::into).collect(),
         types: ast.types.into_iter().map(Into::into).collect(),
-        tables: ast.tables.into_iter().map(Into::into).collect(),
+        tables,
         indexes: ast.indexes.into_iter().map(Into::into).collect(),
         statistics: ast.statistics.into_iter().map(Into::into).collect(),
         views: ast.views.into_iter().map(Into::into).collect(),
         materialized: ast.materialized.into_iter().map(Into::into).collect(),
-        policies: ast.policies.into_iter().map(Into::into).collect(),
+        policies,
         roles: ast.roles.into_iter().map(Into::into).collect(),
         tablespaces: ast.tablespaces.into_iter().map(Into::into).collect(),
         grants: ast.grants.into_iter().map(Into::into).collect(),
@@ -67,6 +70,7 @@ pub fn lower_config(ast: ast::Config) -> ir::Config {
         tests: ast.tests.into_iter().map(Into::into).collect(),
         invariants: ast.invariants.into_iter().map(Into::into).collect(),
         outputs: ast.outputs.into_iter().map(Into::into).collect(),
+        sensitive_values: ast.sensitive_values,
     }
 }
 
@@ -79,6 +83,17 @@ impl From<ast::AstProvider> for ir::ProviderSpec {
     }
 }
 
+impl From<ast::AstFunctionParameter> for ir::FunctionParameterSpec {
+    fn from(p: ast::AstFunctionParameter) -> Self {
+        Self {
+            mode: p.mode,
+            name: p.name,
+            r#type: p.r#type,
+            default: p.default,
+        }
+    }
+}
+
 impl From<ast::AstFunction> for ir::FunctionSpec {
     fn from(f: ast::AstFunction) -> Self {
         Self {
@@ -86,7 +101,7 @@ impl From<ast::AstFunction> for ir::FunctionSpec {
             alt_name: f.alt_name,
             schema: f.schema,
             language: f.language,
-            parameters: f.parameters,
+            parameters: f.parameters.into_iter().map(Into::into).collect(),
             returns: f.returns,
             replace: f.replace,
             volatility: f.volatility,
@@ -94,6 +109,7 @@ impl From<ast::AstFunction> for ir::FunctionSpec {
             security: f.security,
             cost: f.cost,
             body: f.body,
+            lint_ignore: f.lint_ignore,
             comment: f.comment,
         }
     }
@@ -159,10 +175,17 @@ impl From<ast::AstTrigger> for ir::TriggerSpec {
             table: t.table,
             timing: t.timing,
             events: t.events,
+            update_columns: t.update_columns,
             level: t.level,
             function: t.function,
             function_schema: t.function_schema,
             when: t.when,
+            referencing_old_table: t.referencing_old_table,
+            referencing_new_table: t.referencing_new_table,
+            constraint: t.constraint,
+            deferrable: t.deferrable,
+            initially_deferred: t.initially_deferred,
+            create: t.create,
             comment: t.comment,
         }
     }
@@ -179,6 +202,7 @@ impl From<ast::AstRule> for ir::RuleSpec {
             r#where: r.r#where,
             instead: r.instead,
             command: r.command,
+            replace: r.replace,
             comment: r.comment,
         }
     }
@@ -269,6 +293,7 @@ impl From<ast::AstEnum> for ir::EnumSpec {
             alt_name: e.alt_name,
             schema: e.schema,
             values: e.values,
+            evolve: e.evolve,
             comment: e.comment,
         }
     }
@@ -318,7 +343,9 @@ impl From<ast::AstView> for ir::ViewSpec {
             alt_name: v.alt_name,
             schema: v.schema,
             replace: v.replace,
+            columns: v.columns,
             sql: v.sql,
+            check_option: v.check_option,
             comment: v.comment,
         }
     }
@@ -332,25 +359,61 @@ impl From<ast::AstMaterializedView> for ir::MaterializedViewSpec {
             schema: m.schema,
             with_data: m.with_data,
             sql: m.sql,
+            indexes: m.indexes.into_iter().map(Into::into).collect(),
             comment: m.comment,
         }
     }
 }
 
-impl From<ast::AstPolicy> for ir::PolicySpec {
-    fn from(p: ast::AstPolicy) -> Self {
-        Self {
-            name: p.name,
-            alt_name: p.alt_name,
-            schema: p.schema,
-            table: p.table,
-            command: p.command,
-            r#as: p.r#as,
-            roles: p.roles,
-            using: p.using,
-            check: p.check,
-            comment: p.comment,
-        }
+/// Lower policies, expanding each `for_tables` policy into one policy per
+/// matching table. A policy with a `schema` only matches tables in it.
+fn lower_policies(policies: Vec<ast::AstPolicy>, tables: &[ir::TableSpec]) -> Vec<ir::PolicySpec> {
+    let mut out = Vec::new();
+    for p in policies {
+        let selector = match &p.target {
+            ast::AstPolicyTarget::Table(table) => {
+                out.push(policy_spec(&p, p.schema.clone(), table.clone()));
+                continue;
+            }
+            ast::AstPolicyTarget::Tables(selector) => selector,
+        };
+        let matching: Vec<_> = tables
+            .iter()
+            .filter(|t| {
+                p.schema
+                    .as_ref()
+                    .is_none_or(|s| t.schema.as_deref().unwrap_or("public") == s)
+                    && t.columns.iter().any(|c| c.name == selector.has_column)
+            })
+            .collect();
+        if matching.is_empty() {
+            log::warn!(
+                "policy '{}' matches no table with column '{}'",
+                p.name,
+                selector.has_column
+            );
+        }
+        for t in matching {
+            let table = t.alt_name.clone().unwrap_or_else(|| t.name.clone());
+            out.push(policy_spec(&p, t.schema.clone(), table));
+        }
+    }
+    out
+}
+
+/// `p` applied to `table` in `schema`.
+fn policy_spec(p: &ast::AstPolicy, schema: Option<String>, table: String) -> ir::PolicySpec {
+    ir::PolicySpec {
+        name: p.name.clone(),
+        alt_name: p.alt_name.clone(),
+        schema,
+        table,
+        command: p.command.clone(),
+        r#as: p.r#as.clone(),
+        roles: p.roles.clone(),
+        using: p.using.clone(),
+        check: p.check.clone(),
+        comment: p.comment.clone(),
     }
 }
 
@@ -365,7 +428,9 @@ impl From<ast::AstRole> for ir::RoleSpec {
             createrole: r.createrole,
             replication: r.replication,
             password: r.password,
+            valid_until: r.valid_until,
             in_role: r.in_role,
+            config: r.config,
             comment: r.comment,
         }
     }
@@ -392,9 +457,13 @@ impl From<ast::AstGrant> for ir::GrantSpec {
             privileges: g.privileges,
             schema: g.schema,
             table: g.table,
+            columns: g.columns,
             function: g.function,
             database: g.database,
             sequence: g.sequence,
+            all_in_schema: g.all_in_schema,
+            with_grant_option: g.with_grant_option,
+            revoke: g.revoke,
         }
     }
 }
@@ -515,9 +584,13 @@ impl From<ast::AstTable> for ir::TableSpec {
             primary_key: t.primary_key.map(Into::into),
             indexes: t.indexes.into_iter().map(Into::into).collect(),
             checks: t.checks.into_iter().map(Into::into).collect(),
+            exclusions: t.exclusions.into_iter().map(Into::into).collect(),
             foreign_keys: t.foreign_keys.into_iter().map(Into::into).collect(),
             partition_by: t.partition_by.map(Into::into),
             partitions: t.partitions.into_iter().map(Into::into).collect(),
+            storage_parameters: t.storage_parameters,
+            row_level_security: t.row_level_security,
+            tablespace: t.tablespace,
             back_references: t.back_references.into_iter().map(Into::into).collect(),
             lint_ignore: t.lint_ignore,
             comment: t.comment,
@@ -534,6 +607,8 @@ impl From<ast::AstColumn> for ir::ColumnSpec {
             nullable: c.nullable,
             default: c.default,
             db_type: c.db_type,
+            collation: c.collation,
+            updated_at: c.updated_at,
             lint_ignore: c.lint_ignore,
             comment: c.comment,
             count: c.count,
@@ -560,6 +635,11 @@ impl From<ast::AstIndex> for ir::IndexSpec {
             orders: i.orders,
             operator_classes: i.operator_classes,
             unique: i.unique,
+            nulls_not_distinct: i.nulls_not_distinct,
+            concurrently: i.concurrently,
+            method: i.method,
+            storage_parameters: i.storage_parameters,
+            tablespace: i.tablespace,
         }
     }
 }
@@ -573,6 +653,27 @@ impl From<ast::AstCheck> for ir::CheckSpec {
     }
 }
 
+impl From<ast::AstExclusionConstraint> for ir::ExclusionConstraintSpec {
+    fn from(e: ast::AstExclusionConstraint) -> Self {
+        Self {
+            name: e.name,
+            method: e.method,
+            elements: e.elements.into_iter().map(Into::into).collect(),
+            r#where: e.r#where,
+        }
+    }
+}
+
+impl From<ast::AstExclusionElement> for ir::ExclusionElementSpec {
+    fn from(e: ast::AstExclusionElement) -> Self {
+        Self {
+            column: e.column,
+            expression: e.expression,
+            operator: e.operator,
+        }
+    }
+}
+
 impl From<ast::AstForeignKey> for ir::ForeignKeySpec {
     fn from(fk: ast::AstForeignKey) -> Self {
         Self {
@@ -581,8 +682,11 @@ impl From<ast::AstForeignKey> for ir::ForeignKeySpec {
             ref_schema: fk.ref_schema,
             ref_table: fk.ref_table,
             ref_columns: fk.ref_columns,
+            r#match: fk.r#match,
             on_delete: fk.on_delete,
             on_update: fk.on_update,
+            deferrable: fk.deferrable,
+            initially_deferred: fk.initially_deferred,
             back_reference_name: fk.back_reference_name,
         }
     }
@@ -618,6 +722,11 @@ impl From<ast::AstStandaloneIndex> for ir::StandaloneIndexSpec {
             orders: i.orders,
             operator_classes: i.operator_classes,
             unique: i.unique,
+            nulls_not_distinct: i.nulls_not_distinct,
+            concurrently: i.concurrently,
+            method: i.method,
+            storage_parameters: i.storage_parameters,
+            tablespace: i.tablespace,
         }
     }
 }
@@ -729,6 +838,7 @@ impl From<ast::AstTest> for ir::TestSpec {
     fn from(t: ast::AstTest) -> Self {
         Self {
             name: t.name,
+            tags: t.tags,
             setup: t.setup,
             asserts: t.asserts,
             assert_fail: t.assert_fail,
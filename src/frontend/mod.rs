@@ -10,6 +10,6 @@ pub mod resource_impls;
 // Re-export commonly used functions for convenience
 pub use builtins::create_context;
 pub use core::{
-    expr_to_string, expr_to_string_vec, expr_to_value, find_attr, get_attr_bool, get_attr_string,
-    load_root_with_loader, resolve_module_path,
+    evaluate_in_root, expr_to_string, expr_to_string_vec, expr_to_value, find_attr, get_attr_bool,
+    get_attr_string, load_root_with_loader, resolve_module_path,
 };
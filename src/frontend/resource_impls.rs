@@ -3,10 +3,11 @@ use hcl::{Body, Value};
 
 use crate::frontend::ast::*;
 use crate::frontend::core::{
-    expr_to_string_vec, expr_to_value, find_attr, get_attr_bool, get_attr_string,
+    expr_to_string_vec, expr_to_value, find_attr, get_attr_bool, get_attr_string, value_to_string,
 };
 use crate::frontend::env::EnvVars;
 use crate::frontend::for_each::ForEachSupport;
+use crate::ir::TriggerCreate;
 
 // Schema implementation
 impl ForEachSupport for AstSchema {
@@ -105,6 +106,8 @@ impl ForEachSupport for AstTable {
             let nullable = get_attr_bool(cb, "nullable", env)?.unwrap_or(true);
             let default = get_attr_string(cb, "default", env)?;
             let db_type = get_attr_string(cb, "db_type", env)?;
+            let collation = get_attr_string(cb, "collation", env)?;
+            let updated_at = get_attr_bool(cb, "updated_at", env)?.unwrap_or(false);
             let comment = get_attr_string(cb, "comment", env)?;
             let lint_ignore = match find_attr(cb, "lint_ignore") {
                 Some(attr) => expr_to_string_vec(attr.expr(), env)?,
@@ -121,6 +124,8 @@ impl ForEachSupport for AstTable {
                     nullable,
                     default,
                     db_type,
+                    collation,
+                    updated_at,
                     lint_ignore,
                     comment,
                     count,
@@ -144,38 +149,7 @@ impl ForEachSupport for AstTable {
         }
 
         // indexes
-        let mut indexes = Vec::new();
-        for iblk in body.blocks().filter(|bb| bb.identifier() == "index") {
-            let name_attr = iblk.labels().get(0).map(|s| s.as_str().to_string());
-            let ib = iblk.body();
-            let cols = match find_attr(ib, "columns") {
-                Some(attr) => expr_to_string_vec(attr.expr(), env)?,
-                None => bail!("index requires columns = [..]"),
-            };
-            let exprs = match find_attr(ib, "expressions") {
-                Some(attr) => expr_to_string_vec(attr.expr(), env)?,
-                None => Vec::new(),
-            };
-            let where_clause = get_attr_string(ib, "where", env)?;
-            let orders = match find_attr(ib, "orders") {
-                Some(attr) => expr_to_string_vec(attr.expr(), env)?,
-                None => Vec::new(),
-            };
-            let operator_classes = match find_attr(ib, "operator_classes") {
-                Some(attr) => expr_to_string_vec(attr.expr(), env)?,
-                None => Vec::new(),
-            };
-            let unique = get_attr_bool(ib, "unique", env)?.unwrap_or(false);
-            indexes.push(AstIndex {
-                name: name_attr,
-                columns: cols,
-                expressions: exprs,
-                r#where: where_clause,
-                orders,
-                operator_classes,
-                unique,
-            });
-        }
+        let mut indexes = parse_index_blocks(body, env)?;
         for ublk in body.blocks().filter(|bb| bb.identifier() == "unique") {
             let name_attr = ublk.labels().get(0).map(|s| s.as_str().to_string());
             let ub = ublk.body();
@@ -196,6 +170,9 @@ impl ForEachSupport for AstTable {
                 Some(attr) => expr_to_string_vec(attr.expr(), env)?,
                 None => Vec::new(),
             };
+            let nulls_not_distinct =
+                get_attr_bool(ub, "nulls_not_distinct", env)?.unwrap_or(false);
+            let tablespace = get_attr_string(ub, "tablespace", env)?;
             indexes.push(AstIndex {
                 name: name_attr,
                 columns: cols,
@@ -204,6 +181,11 @@ impl ForEachSupport for AstTable {
                 orders,
                 operator_classes,
                 unique: true,
+                nulls_not_distinct,
+                concurrently: false,
+                method: None,
+                storage_parameters: hcl::Map::new(),
+                tablespace,
             });
         }
 
@@ -220,6 +202,40 @@ impl ForEachSupport for AstTable {
             });
         }
 
+        // exclusion constraints
+        let mut exclusions = Vec::new();
+        for eblk in body.blocks().filter(|bb| bb.identifier() == "exclude") {
+            let name_attr = eblk.labels().first().map(|s| s.as_str().to_string());
+            let eb = eblk.body();
+            let method = get_attr_string(eb, "using", env)?.unwrap_or_else(|| "gist".to_string());
+            let mut elements = Vec::new();
+            for elblk in eb.blocks().filter(|bb| bb.identifier() == "element") {
+                let elb = elblk.body();
+                let column = get_attr_string(elb, "column", env)?;
+                let expression = get_attr_string(elb, "expression", env)?;
+                if column.is_some() == expression.is_some() {
+                    bail!("exclude.element requires exactly one of column or expression");
+                }
+                let operator = get_attr_string(elb, "operator", env)?
+                    .context("exclude.element requires operator")?;
+                elements.push(AstExclusionElement {
+                    column,
+                    expression,
+                    operator,
+                });
+            }
+            if elements.is_empty() {
+                bail!("exclude requires at least one element block");
+            }
+            let where_clause = get_attr_string(eb, "where", env)?;
+            exclusions.push(AstExclusionConstraint {
+                name: name_attr,
+                method,
+                elements,
+                r#where: where_clause,
+            });
+        }
+
         // foreign keys
         let mut fks = Vec::new();
         for fkblk in body.blocks().filter(|bb| bb.identifier() == "foreign_key") {
@@ -242,8 +258,11 @@ impl ForEachSupport for AstTable {
                 });
             }
             let name = get_attr_string(fb, "name", env)?;
+            let r#match = get_attr_string(fb, "match", env)?;
             let on_delete = get_attr_string(fb, "on_delete", env)?;
             let on_update = get_attr_string(fb, "on_update", env)?;
+            let deferrable = get_attr_bool(fb, "deferrable", env)?.unwrap_or(false);
+            let initially_deferred = get_attr_bool(fb, "initially_deferred", env)?.unwrap_or(false);
             let back_reference_name = get_attr_string(fb, "back_reference_name", env)?;
             let ref_table = ref_table.context("foreign_key.ref requires table")?;
             let ref_columns = ref_columns.context("foreign_key.ref requires columns = [..]")?;
@@ -253,8 +272,11 @@ impl ForEachSupport for AstTable {
                 ref_schema,
                 ref_table,
                 ref_columns,
+                r#match,
                 on_delete,
                 on_update,
+                deferrable,
+                initially_deferred,
                 back_reference_name,
             });
         }
@@ -285,6 +307,25 @@ impl ForEachSupport for AstTable {
             partitions.push(AstPartition { name, values });
         }
 
+        let row_level_security = get_attr_bool(body, "row_level_security", env)?.unwrap_or(false);
+        let mut storage_parameters = parse_storage_parameters(body, env)?;
+        // Older dumps carry `WITH (OIDS=false)`, which PostgreSQL 12 rejects
+        // although it was already the default; drop it instead of failing.
+        storage_parameters.retain(|k, v| {
+            !(k.eq_ignore_ascii_case("oids") && matches!(v, Value::Bool(false)))
+        });
+        if let Some(k) = storage_parameters
+            .keys()
+            .find(|k| k.eq_ignore_ascii_case("oids"))
+        {
+            bail!(
+                "table '{}': storage parameter '{}' is no longer supported since PostgreSQL 12",
+                name,
+                k
+            );
+        }
+        let tablespace = get_attr_string(body, "tablespace", env)?;
+
         let lint_ignore = match find_attr(body, "lint_ignore") {
             Some(attr) => expr_to_string_vec(attr.expr(), env)?,
             None => Vec::new(),
@@ -299,9 +340,13 @@ impl ForEachSupport for AstTable {
             primary_key,
             indexes,
             checks,
+            exclusions,
             foreign_keys: fks,
             partition_by,
             partitions,
+            storage_parameters,
+            row_level_security,
+            tablespace,
             back_references: Vec::new(),
             lint_ignore,
             comment,
@@ -322,14 +367,21 @@ impl ForEachSupport for AstView {
         let alt_name = get_attr_string(body, "name", env)?;
         let schema = get_attr_string(body, "schema", env)?;
         let replace = get_attr_bool(body, "replace", env)?.unwrap_or(true);
+        let columns = match find_attr(body, "columns") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => Vec::new(),
+        };
         let sql = get_attr_string(body, "sql", env)?.context("view 'sql' is required")?;
+        let check_option = get_attr_string(body, "check_option", env)?;
         let comment = get_attr_string(body, "comment", env)?;
         Ok(AstView {
             name: name.to_string(),
             alt_name,
             schema,
             replace,
+            columns,
             sql,
+            check_option,
             comment,
         })
     }
@@ -348,6 +400,7 @@ impl ForEachSupport for AstMaterializedView {
         let schema = get_attr_string(body, "schema", env)?;
         let with_data = get_attr_bool(body, "with_data", env)?.unwrap_or(true);
         let sql = get_attr_string(body, "sql", env)?.context("materialized 'sql' is required")?;
+        let indexes = parse_index_blocks(body, env)?;
         let comment = get_attr_string(body, "comment", env)?;
         Ok(AstMaterializedView {
             name: name.to_string(),
@@ -355,6 +408,7 @@ impl ForEachSupport for AstMaterializedView {
             schema,
             with_data,
             sql,
+            indexes,
             comment,
         })
     }
@@ -364,6 +418,79 @@ impl ForEachSupport for AstMaterializedView {
     }
 }
 
+/// The optional `storage_parameters` object of a table or index.
+fn parse_storage_parameters(body: &Body, env: &EnvVars) -> Result<hcl::Map<String, Value>> {
+    match find_attr(body, "storage_parameters") {
+        Some(attr) => match expr_to_value(attr.expr(), env)? {
+            Value::Object(map) => Ok(map),
+            _ => bail!("storage_parameters must be an object"),
+        },
+        None => Ok(hcl::Map::new()),
+    }
+}
+
+fn parse_table_selector(value: &Value) -> Result<AstTableSelector> {
+    let Value::Object(map) = value else {
+        bail!("for_tables must be an object such as {{ has_column = \"tenant_id\" }}");
+    };
+    if let Some(key) = map.keys().find(|k| k.as_str() != "has_column") {
+        bail!("for_tables: unknown selector '{key}' (expected has_column)");
+    }
+    match map.get("has_column") {
+        Some(Value::String(column)) => Ok(AstTableSelector {
+            has_column: column.clone(),
+        }),
+        _ => bail!("for_tables: has_column must be a string"),
+    }
+}
+
+/// Parse the `index` blocks of a table or materialized view.
+fn parse_index_blocks(body: &Body, env: &EnvVars) -> Result<Vec<AstIndex>> {
+    let mut indexes = Vec::new();
+    for iblk in body.blocks().filter(|bb| bb.identifier() == "index") {
+        let name_attr = iblk.labels().get(0).map(|s| s.as_str().to_string());
+        let ib = iblk.body();
+        let cols = match find_attr(ib, "columns") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => bail!("index requires columns = [..]"),
+        };
+        let exprs = match find_attr(ib, "expressions") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => Vec::new(),
+        };
+        let where_clause = get_attr_string(ib, "where", env)?;
+        let orders = match find_attr(ib, "orders") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => Vec::new(),
+        };
+        let operator_classes = match find_attr(ib, "operator_classes") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => Vec::new(),
+        };
+        let unique = get_attr_bool(ib, "unique", env)?.unwrap_or(false);
+        let nulls_not_distinct = get_attr_bool(ib, "nulls_not_distinct", env)?.unwrap_or(false);
+        let concurrently = get_attr_bool(ib, "concurrently", env)?.unwrap_or(false);
+        let method = get_attr_string(ib, "method", env)?;
+        let storage_parameters = parse_storage_parameters(ib, env)?;
+        let tablespace = get_attr_string(ib, "tablespace", env)?;
+        indexes.push(AstIndex {
+            name: name_attr,
+            columns: cols,
+            expressions: exprs,
+            r#where: where_clause,
+            orders,
+            operator_classes,
+            unique,
+            nulls_not_distinct,
+            concurrently,
+            method,
+            storage_parameters,
+            tablespace,
+        });
+    }
+    Ok(indexes)
+}
+
 // Policy implementation
 impl ForEachSupport for AstPolicy {
     type Item = Self;
@@ -371,7 +498,17 @@ impl ForEachSupport for AstPolicy {
     fn parse_one(name: &str, body: &Body, env: &EnvVars) -> Result<Self::Item> {
         let alt_name = get_attr_string(body, "name", env)?;
         let schema = get_attr_string(body, "schema", env)?;
-        let table = get_attr_string(body, "table", env)?.context("policy 'table' is required")?;
+        let table = get_attr_string(body, "table", env)?;
+        let for_tables = match find_attr(body, "for_tables") {
+            Some(attr) => Some(parse_table_selector(&expr_to_value(attr.expr(), env)?)?),
+            None => None,
+        };
+        let target = match (table, for_tables) {
+            (Some(_), Some(_)) => bail!("policy '{name}' sets both 'table' and 'for_tables'"),
+            (Some(table), None) => AstPolicyTarget::Table(table),
+            (None, Some(selector)) => AstPolicyTarget::Tables(selector),
+            (None, None) => bail!("policy 'table' or 'for_tables' is required"),
+        };
         let command = get_attr_string(body, "command", env)?.unwrap_or_else(|| "ALL".to_string());
         let as_kind = get_attr_string(body, "as", env)?;
         let roles = match find_attr(body, "roles") {
@@ -385,7 +522,7 @@ impl ForEachSupport for AstPolicy {
             name: name.to_string(),
             alt_name,
             schema,
-            table,
+            target,
             command,
             r#as: as_kind,
             roles,
@@ -414,7 +551,7 @@ impl ForEachSupport for AstFunction {
             get_attr_string(body, "returns", env)?.unwrap_or_else(|| "trigger".to_string());
         let schema = get_attr_string(body, "schema", env)?;
         let parameters = match find_attr(body, "parameters") {
-            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            Some(attr) => parse_function_parameters(name, &expr_to_value(attr.expr(), env)?)?,
             None => Vec::new(),
         };
         let replace = get_attr_bool(body, "replace", env)?.unwrap_or(true);
@@ -428,6 +565,10 @@ impl ForEachSupport for AstFunction {
             ),
             None => None,
         };
+        let lint_ignore = match find_attr(body, "lint_ignore") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => Vec::new(),
+        };
         let comment = get_attr_string(body, "comment", env)?;
         Ok(AstFunction {
             name: name.to_string(),
@@ -442,6 +583,7 @@ impl ForEachSupport for AstFunction {
             security,
             cost,
             body: body_sql,
+            lint_ignore,
             comment,
         })
     }
@@ -451,6 +593,42 @@ impl ForEachSupport for AstFunction {
     }
 }
 
+/// Parse `parameters`: each element is either a bare string such as `"a int"`
+/// or an object with `type` and optional `mode`, `name` and `default`.
+fn parse_function_parameters(function: &str, value: &Value) -> Result<Vec<AstFunctionParameter>> {
+    let Value::Array(items) = value else {
+        bail!("function '{}' parameters must be a list", function);
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            Value::String(s) => Ok(AstFunctionParameter {
+                mode: None,
+                name: None,
+                r#type: s.clone(),
+                default: None,
+            }),
+            Value::Object(obj) => {
+                let field = |key: &str| -> Result<Option<String>> {
+                    obj.get(key).map(value_to_string).transpose()
+                };
+                Ok(AstFunctionParameter {
+                    mode: field("mode")?,
+                    name: field("name")?,
+                    r#type: field("type")?.with_context(|| {
+                        format!("function '{}' parameter is missing 'type'", function)
+                    })?,
+                    default: field("default")?,
+                })
+            }
+            _ => bail!(
+                "function '{}' parameters must be strings or objects",
+                function
+            ),
+        })
+        .collect()
+}
+
 // Procedure implementation
 impl ForEachSupport for AstProcedure {
     type Item = Self;
@@ -461,6 +639,12 @@ impl ForEachSupport for AstProcedure {
             get_attr_string(body, "language", env)?.unwrap_or_else(|| "plpgsql".to_string());
         let body_sql =
             get_attr_string(body, "body", env)?.context("procedure 'body' is required")?;
+        if find_attr(body, "returns").is_some() {
+            bail!(
+                "procedure '{}' cannot declare 'returns'; use a function, or INOUT parameters to return values",
+                name
+            );
+        }
         let schema = get_attr_string(body, "schema", env)?;
         let parameters = match find_attr(body, "parameters") {
             Some(attr) => expr_to_string_vec(attr.expr(), env)?,
@@ -534,8 +718,12 @@ impl ForEachSupport for AstOperator {
         let schema = get_attr_string(body, "schema", env)?;
         let left = get_attr_string(body, "left", env)?;
         let right = get_attr_string(body, "right", env)?;
-        let procedure =
-            get_attr_string(body, "procedure", env)?.context("operator 'procedure' is required")?;
+        let procedure = match get_attr_string(body, "function", env)? {
+            Some(function) => function,
+            None => get_attr_string(body, "procedure", env)?.with_context(|| {
+                format!("operator '{}' requires 'function' (or 'procedure')", name)
+            })?,
+        };
         let commutator = get_attr_string(body, "commutator", env)?;
         let negator = get_attr_string(body, "negator", env)?;
         let restrict = get_attr_string(body, "restrict", env)?;
@@ -574,11 +762,35 @@ impl ForEachSupport for AstTrigger {
             Some(attr) => expr_to_string_vec(attr.expr(), env)?,
             None => vec!["UPDATE".to_string()],
         };
+        let update_columns = match find_attr(body, "update_columns") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => Vec::new(),
+        };
         let level = get_attr_string(body, "level", env)?.unwrap_or_else(|| "ROW".to_string());
         let function =
             get_attr_string(body, "function", env)?.context("trigger 'function' is required")?;
         let function_schema = get_attr_string(body, "function_schema", env)?;
         let when = get_attr_string(body, "when", env)?;
+        let referencing_old_table = get_attr_string(body, "referencing_old_table", env)?;
+        let referencing_new_table = get_attr_string(body, "referencing_new_table", env)?;
+        let constraint = get_attr_bool(body, "constraint", env)?.unwrap_or(false);
+        let deferrable = get_attr_bool(body, "deferrable", env)?.unwrap_or(false);
+        let initially_deferred = get_attr_bool(body, "initially_deferred", env)?.unwrap_or(false);
+        // `replace = "drop"` drops and recreates the trigger, which works
+        // before PostgreSQL 14 too.
+        let create = match find_attr(body, "replace") {
+            None => TriggerCreate::Create,
+            Some(attr) => match expr_to_value(attr.expr(), env)? {
+                Value::Bool(false) => TriggerCreate::Create,
+                Value::Bool(true) => TriggerCreate::CreateOrReplace,
+                Value::String(s) if s == "drop" => TriggerCreate::DropAndCreate,
+                other => bail!(
+                    "trigger '{}': replace must be true, false or \"drop\", got {}",
+                    name,
+                    other
+                ),
+            },
+        };
         let comment = get_attr_string(body, "comment", env)?;
         Ok(AstTrigger {
             name: name.to_string(),
@@ -587,10 +799,17 @@ impl ForEachSupport for AstTrigger {
             table,
             timing,
             events,
+            update_columns,
             level,
             function,
             function_schema,
             when,
+            referencing_old_table,
+            referencing_new_table,
+            constraint,
+            deferrable,
+            initially_deferred,
+            create,
             comment,
         })
     }
@@ -613,6 +832,7 @@ impl ForEachSupport for AstRule {
         let instead = get_attr_bool(body, "instead", env)?.unwrap_or(false);
         let command =
             get_attr_string(body, "command", env)?.context("rule 'command' is required")?;
+        let replace = get_attr_bool(body, "replace", env)?.unwrap_or(true);
         let comment = get_attr_string(body, "comment", env)?;
         Ok(AstRule {
             name: name.to_string(),
@@ -623,6 +843,7 @@ impl ForEachSupport for AstRule {
             r#where,
             instead,
             command,
+            replace,
             comment,
         })
     }
@@ -737,12 +958,14 @@ impl ForEachSupport for AstEnum {
             Some(attr) => expr_to_string_vec(attr.expr(), env)?,
             None => bail!("enum '{}' requires values = [..]", name),
         };
+        let evolve = get_attr_bool(body, "evolve", env)?.unwrap_or(false);
         let comment = get_attr_string(body, "comment", env)?;
         Ok(AstEnum {
             name: name.to_string(),
             alt_name,
             schema,
             values,
+            evolve,
             comment,
         })
     }
@@ -834,10 +1057,18 @@ impl ForEachSupport for AstRole {
         let createrole = get_attr_bool(body, "createrole", env)?.unwrap_or(false);
         let replication = get_attr_bool(body, "replication", env)?.unwrap_or(false);
         let password = get_attr_string(body, "password", env)?;
+        let valid_until = get_attr_string(body, "valid_until", env)?;
         let in_role = match find_attr(body, "in_role") {
             Some(attr) => expr_to_string_vec(attr.expr(), env)?,
             None => Vec::new(),
         };
+        let config = match find_attr(body, "config") {
+            Some(attr) => match expr_to_value(attr.expr(), env)? {
+                Value::Object(map) => map,
+                _ => bail!("role 'config' must be an object"),
+            },
+            None => hcl::Map::new(),
+        };
         let comment = get_attr_string(body, "comment", env)?;
         Ok(AstRole {
             name: name.to_string(),
@@ -848,7 +1079,9 @@ impl ForEachSupport for AstRole {
             createrole,
             replication,
             password,
+            valid_until,
             in_role,
+            config,
             comment,
         })
     }
@@ -900,9 +1133,16 @@ impl ForEachSupport for AstGrant {
         };
         let schema = get_attr_string(body, "schema", env)?;
         let table = get_attr_string(body, "table", env)?;
+        let columns = match find_attr(body, "columns") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => Vec::new(),
+        };
         let function = get_attr_string(body, "function", env)?;
         let database = get_attr_string(body, "database", env)?;
         let sequence = get_attr_string(body, "sequence", env)?;
+        let all_in_schema = get_attr_string(body, "all_in_schema", env)?;
+        let with_grant_option = get_attr_bool(body, "with_grant_option", env)?.unwrap_or(false);
+        let revoke = get_attr_bool(body, "revoke", env)?.unwrap_or(false);
         if table.is_none()
             && function.is_none()
             && schema.is_none()
@@ -918,9 +1158,13 @@ impl ForEachSupport for AstGrant {
             privileges,
             schema,
             table,
+            columns,
             function,
             database,
             sequence,
+            all_in_schema,
+            with_grant_option,
+            revoke,
         })
     }
 
@@ -954,6 +1198,11 @@ impl ForEachSupport for AstStandaloneIndex {
             None => Vec::new(),
         };
         let unique = get_attr_bool(body, "unique", env)?.unwrap_or(false);
+        let nulls_not_distinct = get_attr_bool(body, "nulls_not_distinct", env)?.unwrap_or(false);
+        let concurrently = get_attr_bool(body, "concurrently", env)?.unwrap_or(false);
+        let method = get_attr_string(body, "method", env)?;
+        let storage_parameters = parse_storage_parameters(body, env)?;
+        let tablespace = get_attr_string(body, "tablespace", env)?;
         Ok(AstStandaloneIndex {
             name: name.to_string(),
             table,
@@ -964,6 +1213,11 @@ impl ForEachSupport for AstStandaloneIndex {
             orders,
             operator_classes,
             unique,
+            nulls_not_distinct,
+            concurrently,
+            method,
+            storage_parameters,
+            tablespace,
         })
     }
 
@@ -1090,6 +1344,7 @@ impl ForEachSupport for AstForeignTable {
             let nullable = get_attr_bool(cb, "nullable", env)?.unwrap_or(true);
             let default = get_attr_string(cb, "default", env)?;
             let db_type = get_attr_string(cb, "db_type", env)?;
+            let collation = get_attr_string(cb, "collation", env)?;
             let comment = get_attr_string(cb, "comment", env)?;
             let lint_ignore = match find_attr(cb, "lint_ignore") {
                 Some(attr) => expr_to_string_vec(attr.expr(), env)?,
@@ -1106,6 +1361,8 @@ impl ForEachSupport for AstForeignTable {
                     nullable,
                     default,
                     db_type,
+                    collation,
+                    updated_at: false,
                     lint_ignore,
                     comment,
                     count,
@@ -1365,6 +1622,10 @@ impl ForEachSupport for AstTest {
         } else {
             name.to_string()
         };
+        let tags = match find_attr(body, "tags") {
+            Some(attr) => expr_to_string_vec(attr.expr(), env)?,
+            None => Vec::new(),
+        };
         let setup = match find_attr(body, "setup") {
             Some(attr) => expr_to_string_vec(attr.expr(), env)?,
             None => Vec::new(),
@@ -1469,6 +1730,7 @@ impl ForEachSupport for AstTest {
         };
         Ok(AstTest {
             name: test_name,
+            tags,
             setup,
             asserts,
             assert_fail,
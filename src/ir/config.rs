@@ -39,6 +39,34 @@ pub struct Config {
     pub tests: Vec<TestSpec>,
     pub invariants: Vec<InvariantSpec>,
     pub outputs: Vec<OutputSpec>,
+    /// String values that originate from `sensitive` variables. Not part of the
+    /// serialized config; descriptive outputs use it to redact those values.
+    #[serde(skip)]
+    pub sensitive_values: Vec<String>,
+}
+
+/// Placeholder substituted for sensitive values in descriptive output.
+pub const REDACTED: &str = "(sensitive)";
+
+impl Config {
+    /// Whether `value` is a string equal to a value from a `sensitive` variable.
+    pub fn is_sensitive(&self, value: &Value) -> bool {
+        matches!(value, Value::String(s) if self.sensitive_values.contains(s))
+    }
+
+    /// `value` with every sensitive string inside it replaced by [`REDACTED`].
+    pub fn redact(&self, value: &Value) -> Value {
+        match value {
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.redact(v)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.redact(v)))
+                    .collect(),
+            ),
+            v if self.is_sensitive(v) => Value::from(REDACTED),
+            v => v.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -53,7 +81,7 @@ pub struct FunctionSpec {
     pub alt_name: Option<String>,
     pub schema: Option<String>,
     pub language: String,
-    pub parameters: Vec<String>,
+    pub parameters: Vec<FunctionParameterSpec>,
     pub returns: String,
     pub replace: bool,
     pub volatility: Option<String>,
@@ -61,9 +89,22 @@ pub struct FunctionSpec {
     pub security: Option<String>,
     pub cost: Option<f64>,
     pub body: String,
+    pub lint_ignore: Vec<String>,
     pub comment: Option<String>,
 }
 
+/// A function parameter. A bare string in HCL becomes a positional parameter
+/// with the whole string in `type`, e.g. `"a int"`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionParameterSpec {
+    /// `in`, `out`, `inout` or `variadic`; `None` means the default (`IN`).
+    pub mode: Option<String>,
+    pub name: Option<String>,
+    pub r#type: String,
+    /// SQL expression used when the argument is omitted.
+    pub default: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ProcedureSpec {
     pub name: String,
@@ -112,15 +153,37 @@ pub struct TriggerSpec {
     pub alt_name: Option<String>,
     pub schema: Option<String>,
     pub table: String,
-    pub timing: String,      // BEFORE | AFTER
+    pub timing: String,      // BEFORE | AFTER | INSTEAD OF
     pub events: Vec<String>, // INSERT | UPDATE | DELETE
+    /// Narrows an UPDATE event to `UPDATE OF col, ...`
+    pub update_columns: Vec<String>,
     pub level: String,       // ROW | STATEMENT
     pub function: String,    // function name (unqualified)
     pub function_schema: Option<String>,
     pub when: Option<String>, // optional condition, raw SQL
+    /// Transition table names: `REFERENCING OLD TABLE AS ... NEW TABLE AS ...`
+    pub referencing_old_table: Option<String>,
+    pub referencing_new_table: Option<String>,
+    /// `CREATE CONSTRAINT TRIGGER`
+    pub constraint: bool,
+    pub deferrable: bool,
+    pub initially_deferred: bool,
+    pub create: TriggerCreate,
     pub comment: Option<String>,
 }
 
+/// How a trigger's migration creates it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum TriggerCreate {
+    /// `CREATE TRIGGER` only when no trigger of that name exists yet.
+    #[default]
+    Create,
+    /// `CREATE OR REPLACE TRIGGER` (PostgreSQL 14+), set with `replace = true`.
+    CreateOrReplace,
+    /// `DROP TRIGGER IF EXISTS` then `CREATE TRIGGER`, set with `replace = "drop"`.
+    DropAndCreate,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct RuleSpec {
     pub name: String,
@@ -131,6 +194,7 @@ pub struct RuleSpec {
     pub r#where: Option<String>,
     pub instead: bool,
     pub command: String,
+    pub replace: bool,
     pub comment: Option<String>,
 }
 
@@ -203,6 +267,9 @@ pub struct EnumSpec {
     pub alt_name: Option<String>,
     pub schema: Option<String>,
     pub values: Vec<String>,
+    /// Also emit `ALTER TYPE ... ADD VALUE IF NOT EXISTS` so values appended
+    /// in HCL reach databases where the type already exists.
+    pub evolve: bool,
     pub comment: Option<String>,
 }
 
@@ -239,8 +306,10 @@ pub struct ViewSpec {
     pub name: String,
     pub alt_name: Option<String>,
     pub schema: Option<String>,
-    pub replace: bool, // OR REPLACE
-    pub sql: String,   // SELECT ... body
+    pub replace: bool,                // OR REPLACE
+    pub columns: Vec<String>,         // explicit output column names
+    pub sql: String,                  // SELECT ... body
+    pub check_option: Option<String>, // WITH CASCADED|LOCAL CHECK OPTION
     pub comment: Option<String>,
 }
 
@@ -251,6 +320,7 @@ pub struct MaterializedViewSpec {
     pub schema: Option<String>,
     pub with_data: bool, // WITH [NO] DATA
     pub sql: String,     // SELECT ... body
+    pub indexes: Vec<IndexSpec>,
     pub comment: Option<String>,
 }
 
@@ -277,8 +347,13 @@ pub struct RoleSpec {
     pub createdb: bool,
     pub createrole: bool,
     pub replication: bool,
+    /// Plaintext, or a `SCRAM-SHA-256$...`/`md5...` hash emitted verbatim
     pub password: Option<String>,
+    /// Password expiry timestamp (`VALID UNTIL`)
+    pub valid_until: Option<String>,
     pub in_role: Vec<String>,
+    /// Per-role parameter defaults (`ALTER ROLE ... SET key = value`)
+    pub config: hcl::Map<String, Value>,
     pub comment: Option<String>,
 }
 
@@ -299,9 +374,18 @@ pub struct GrantSpec {
     pub privileges: Vec<String>,
     pub schema: Option<String>,
     pub table: Option<String>,
+    /// Restrict a table grant to these columns
+    pub columns: Vec<String>,
     pub function: Option<String>,
     pub database: Option<String>,
     pub sequence: Option<String>,
+    /// Grant on every object of a kind in `schema`: TABLES, SEQUENCES,
+    /// FUNCTIONS, PROCEDURES or ROUTINES
+    pub all_in_schema: Option<String>,
+    /// Let the grantee re-grant the privileges (`WITH GRANT OPTION`)
+    pub with_grant_option: bool,
+    /// Emit `REVOKE ... FROM` instead of `GRANT ... TO`
+    pub revoke: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -371,15 +455,42 @@ pub struct TableSpec {
     pub primary_key: Option<PrimaryKeySpec>,
     pub indexes: Vec<IndexSpec>,
     pub checks: Vec<CheckSpec>,
+    pub exclusions: Vec<ExclusionConstraintSpec>,
     pub foreign_keys: Vec<ForeignKeySpec>,
     pub partition_by: Option<PartitionBySpec>,
     pub partitions: Vec<PartitionSpec>,
+    /// `WITH (...)` storage parameters such as `fillfactor`.
+    pub storage_parameters: hcl::Map<String, Value>,
+    /// Enable row-level security, so only rows allowed by a `policy` are visible.
+    pub row_level_security: bool,
+    pub tablespace: Option<String>,
     pub back_references: Vec<BackReferenceSpec>,
     pub lint_ignore: Vec<String>,
     pub comment: Option<String>,
     pub map: Option<String>,
 }
 
+impl TableSpec {
+    /// The relation name shared by both ends of `fk`, one of this table's
+    /// foreign keys: its `back_reference_name` when set, else, when the table
+    /// has several foreign keys to the same table, one derived from the table
+    /// and key columns (`posts_editor_id`) so the relations stay apart.
+    pub fn relation_name(&self, fk: &ForeignKeySpec) -> Option<String> {
+        if let Some(name) = &fk.back_reference_name {
+            return Some(name.clone());
+        }
+        let siblings = self
+            .foreign_keys
+            .iter()
+            .filter(|other| other.ref_table == fk.ref_table)
+            .count();
+        (siblings > 1).then(|| {
+            let table = self.alt_name.as_ref().unwrap_or(&self.name);
+            format!("{}_{}", table.to_lowercase(), fk.columns.join("_"))
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PartitionBySpec {
     pub strategy: String,
@@ -406,6 +517,8 @@ pub struct ColumnSpec {
     pub nullable: bool,
     pub default: Option<String>,
     pub db_type: Option<String>, // NEW: Database-specific type like "CHAR(32)", "VARCHAR(255)"
+    pub collation: Option<String>,
+    pub updated_at: bool, // set on every update; Prisma `@updatedAt`
     pub lint_ignore: Vec<String>,
     pub comment: Option<String>,
     pub count: usize,
@@ -423,6 +536,24 @@ pub struct CheckSpec {
     pub expression: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ExclusionConstraintSpec {
+    pub name: Option<String>,
+    /// Index access method, e.g. `gist`.
+    pub method: String,
+    pub elements: Vec<ExclusionElementSpec>,
+    pub r#where: Option<String>,
+}
+
+/// A single `<column or expression> WITH <operator>` element. Exactly one of
+/// `column` and `expression` is set.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExclusionElementSpec {
+    pub column: Option<String>,
+    pub expression: Option<String>,
+    pub operator: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct IndexSpec {
     pub name: Option<String>,
@@ -432,6 +563,15 @@ pub struct IndexSpec {
     pub orders: Vec<String>,
     pub operator_classes: Vec<String>,
     pub unique: bool,
+    /// Treat NULLs as equal in a unique index (`NULLS NOT DISTINCT`, PostgreSQL 15+).
+    pub nulls_not_distinct: bool,
+    /// Build with `CREATE INDEX CONCURRENTLY`, which cannot run in a transaction.
+    pub concurrently: bool,
+    /// Index access method, e.g. `gin` or `hnsw`; `None` means the default (btree).
+    pub method: Option<String>,
+    /// Rendered as `WITH (key = value, ...)`, e.g. `{ m = 16 }` for hnsw.
+    pub storage_parameters: hcl::Map<String, Value>,
+    pub tablespace: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -441,8 +581,11 @@ pub struct ForeignKeySpec {
     pub ref_schema: Option<String>,
     pub ref_table: String,
     pub ref_columns: Vec<String>,
+    pub r#match: Option<String>,
     pub on_delete: Option<String>,
     pub on_update: Option<String>,
+    pub deferrable: bool,
+    pub initially_deferred: bool,
     pub back_reference_name: Option<String>,
 }
 
@@ -457,6 +600,15 @@ pub struct StandaloneIndexSpec {
     pub orders: Vec<String>,
     pub operator_classes: Vec<String>,
     pub unique: bool,
+    /// Treat NULLs as equal in a unique index (`NULLS NOT DISTINCT`, PostgreSQL 15+).
+    pub nulls_not_distinct: bool,
+    /// Build with `CREATE INDEX CONCURRENTLY`, which cannot run in a transaction.
+    pub concurrently: bool,
+    /// Index access method, e.g. `gin` or `hnsw`; `None` means the default (btree).
+    pub method: Option<String>,
+    /// Rendered as `WITH (key = value, ...)`, e.g. `{ m = 16 }` for hnsw.
+    pub storage_parameters: hcl::Map<String, Value>,
+    pub tablespace: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -552,6 +704,7 @@ pub struct InvariantSpec {
 #[derive(Debug, Clone, Serialize)]
 pub struct TestSpec {
     pub name: String,
+    pub tags: Vec<String>,
     pub setup: Vec<String>,
     pub asserts: Vec<String>,
     pub assert_fail: Vec<String>,
@@ -1,14 +1,15 @@
 pub mod config;
 
 pub use config::{
+    REDACTED,
     AggregateSpec, BackReferenceSpec, CheckSpec, CollationSpec, ColumnSpec, CompositeTypeFieldSpec,
     CompositeTypeSpec, Config, DomainSpec, EnumSpec, EqAssertSpec, ErrorAssertSpec,
-    EventTriggerSpec, ExtensionSpec,
-    ForeignDataWrapperSpec, ForeignKeySpec, ForeignServerSpec, ForeignTableSpec, FunctionSpec,
+    EventTriggerSpec, ExclusionConstraintSpec, ExclusionElementSpec, ExtensionSpec,
+    ForeignDataWrapperSpec, ForeignKeySpec, ForeignServerSpec, ForeignTableSpec, FunctionParameterSpec, FunctionSpec,
     GrantSpec, IndexSpec, MaterializedViewSpec, NotifyAssertSpec, OperatorSpec, OutputSpec, PartitionBySpec,
     PartitionSpec, PolicySpec, PrimaryKeySpec, ProcedureSpec, ProviderSpec, PublicationSpec,
     PublicationTableSpec, RoleSpec, RuleSpec, SchemaSpec, SequenceSpec, StandaloneIndexSpec,
     InvariantSpec, SnapshotAssertSpec, StatisticsSpec, SubscriptionSpec, TableSpec, TablespaceSpec, TestSpec,
     TextSearchConfigurationMappingSpec, TextSearchConfigurationSpec, TextSearchDictionarySpec,
-    TextSearchParserSpec, TextSearchTemplateSpec, TriggerSpec, ViewSpec,
+    TextSearchParserSpec, TextSearchTemplateSpec, TriggerCreate, TriggerSpec, ViewSpec,
 };
@@ -3,16 +3,22 @@
 pub extern "C" fn __rust_probestack() {}
 
 pub mod backends;
+#[cfg(any(test, feature = "bench"))]
+pub mod bench;
+pub mod cache;
 pub mod config;
 pub mod frontend;
 pub mod ir;
 pub mod lint;
 pub mod passes;
 pub mod prisma;
+pub mod profile;
 pub mod provider;
 pub mod test_runner;
+#[cfg(test)]
+mod test_support;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 // Keep types public via re-exports
 use std::path::Path;
 
@@ -24,10 +30,42 @@ pub use ir::{
     PolicySpec, ProcedureSpec, RoleSpec, SchemaSpec, SequenceSpec, TableSpec, TablespaceSpec,
     TriggerSpec, ViewSpec,
 };
+pub use test_runner::{TestFilter, TestSummary, run_tests};
 
 // Loader abstraction: lets callers control how files are read.
 pub trait Loader {
     fn load(&self, path: &Path) -> Result<String>;
+
+    /// Fetch the body of `url` for `data "http"` sources. The library never
+    /// touches the network itself; loaders that can reach it (like the CLI's)
+    /// override this.
+    fn fetch(&self, url: &str) -> Result<String> {
+        anyhow::bail!("cannot fetch '{url}': this loader does not support http data sources")
+    }
+
+    /// Fetch a source pinned to `hash` (see
+    /// [`content_hash`](frontend::data_sources::content_hash)). Because the
+    /// content is fixed, loaders may serve it from a cache. The caller checks
+    /// that `hash` is well-formed before fetching and still verifies the body.
+    fn fetch_pinned(&self, url: &str, _hash: &str) -> Result<String> {
+        self.fetch(url)
+    }
+}
+
+/// In-memory [`Loader`] backed by a path -> contents map. Useful for tests,
+/// benchmarks and embedding dbschema without touching the filesystem.
+#[derive(Debug, Clone, Default)]
+pub struct MapLoader {
+    pub files: std::collections::HashMap<std::path::PathBuf, String>,
+}
+
+impl Loader for MapLoader {
+    fn load(&self, path: &Path) -> Result<String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("missing file: {}", path.display()))
+    }
 }
 
 // Pure API: parse + evaluate HCL config starting at `root_path` using a Loader.
@@ -35,6 +73,22 @@ pub fn load_config(root_path: &Path, loader: &dyn Loader, env: EnvVars) -> Resul
     frontend::load_root_with_loader(root_path, loader, env)
 }
 
+/// Load the configuration at `root_path` and evaluate `expr` in the scope
+/// the root file's outputs see: variables, locals, data sources, module
+/// outputs and resources. Also returns the config, so callers can redact
+/// values derived from sensitive variables.
+pub fn evaluate_expression(
+    root_path: &Path,
+    loader: &dyn Loader,
+    env: EnvVars,
+    expr: &str,
+) -> Result<(hcl::Value, Config)> {
+    let expr: hcl::Expression = expr
+        .parse()
+        .with_context(|| format!("expected a single expression, got '{expr}'"))?;
+    frontend::evaluate_in_root(root_path, loader, env, &expr)
+}
+
 // Pure validation: check references etc.
 pub fn validate(cfg: &Config, strict: bool) -> Result<()> {
     passes::validate(cfg, strict)
@@ -89,6 +143,7 @@ where
         subscriptions: maybe!(Subscriptions, subscriptions),
         tests: maybe!(Tests, tests),
         outputs: cfg.outputs.clone(),
+        sensitive_values: cfg.sensitive_values.clone(),
         ..Default::default()
     }
 }
@@ -133,6 +188,25 @@ pub fn generate_with_backend(backend: &str, cfg: &Config, strict: bool) -> Resul
     be.generate(cfg, strict)
 }
 
+/// Generate the migration for `cfg` wrapped in `BEGIN;`/`COMMIT;` using the
+/// named backend.
+pub fn generate_in_transaction_with_backend(
+    backend: &str,
+    cfg: &Config,
+    strict: bool,
+) -> Result<String> {
+    let be = backends::get_backend(backend)
+        .ok_or_else(|| anyhow::anyhow!(format!("unknown backend '{backend}'")))?;
+    be.generate_in_transaction(cfg, strict)
+}
+
+/// Generate statements that drop every resource in `cfg` using the named backend.
+pub fn generate_down_with_backend(backend: &str, cfg: &Config, strict: bool) -> Result<String> {
+    let be = backends::get_backend(backend)
+        .ok_or_else(|| anyhow::anyhow!(format!("unknown backend '{backend}'")))?;
+    be.generate_down(cfg, strict)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,18 +214,6 @@ mod tests {
     use std::collections::HashMap;
     use std::path::PathBuf;
 
-    struct MapLoader {
-        files: HashMap<PathBuf, String>,
-    }
-    impl Loader for MapLoader {
-        fn load(&self, path: &Path) -> Result<String> {
-            self.files
-                .get(path)
-                .cloned()
-                .ok_or_else(|| anyhow::anyhow!("missing file: {}", path.display()))
-        }
-    }
-
     fn p(s: &str) -> PathBuf {
         PathBuf::from(s)
     }
@@ -294,6 +356,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn evaluate_expression_uses_the_root_scope() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            variable "prefix" { default = "app" }
+            locals {
+              table = "${var.prefix}_users"
+            }
+            output "existing" { value = 1 }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let env = || EnvVars {
+            vars: HashMap::from([("prefix".into(), hcl::Value::from("crm"))]),
+            ..EnvVars::default()
+        };
+
+        let (value, cfg) = evaluate_expression(
+            &p("/root/main.hcl"),
+            &loader,
+            env(),
+            r#"upper(local.table) == "CRM_USERS" ? var.prefix : "no""#,
+        )
+        .unwrap();
+        assert_eq!(value, hcl::Value::from("crm"));
+        assert_eq!(cfg.outputs.len(), 1);
+
+        let err =
+            evaluate_expression(&p("/root/main.hcl"), &loader, env(), "var.nope").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("undefined variable 'nope'"),
+            "{err:#}"
+        );
+
+        let err = evaluate_expression(&p("/root/main.hcl"), &loader, env(), "1\ntable \"x\" {}")
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("expected a single expression"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn outputs_reference_resolved_resource_names() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            table "users" {
+              schema = "auth"
+              table_name = "app_users"
+              column "id" { type = "integer" }
+              column "email" { type = "text" }
+            }
+            output "user_table" { value = table.users.name }
+            output "qualified" { value = "${table.users.schema}.${table.users.name}" }
+            output "columns" { value = join(",", table.users.columns) }
+            "#
+            .to_string(),
+        );
+
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let outputs: Vec<(&str, &hcl::Value)> = cfg
+            .outputs
+            .iter()
+            .map(|o| (o.name.as_str(), &o.value))
+            .collect();
+        assert_eq!(
+            outputs,
+            [
+                ("user_table", &hcl::Value::from("app_users")),
+                ("qualified", &hcl::Value::from("auth.app_users")),
+                ("columns", &hcl::Value::from("id,email")),
+            ]
+        );
+
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            table "users" {
+              column "id" { type = "integer" }
+            }
+            output "missing" { value = table.nope.name }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let err = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("undefined table 'nope'"),
+            "{err:#}"
+        );
+    }
+
     #[test]
     fn module_for_each_can_use_data_sources() {
         let mut files = HashMap::new();
@@ -432,6 +593,74 @@ mod tests {
         assert!(sql.contains("\"orders\""));
     }
 
+    #[test]
+    fn jsonencode_builds_jsonb_column_default() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            locals {
+              settings = { theme = "dark", notify = true }
+            }
+
+            table "users" {
+              column "settings" {
+                type    = "jsonb"
+                default = "'${jsonencode(local.settings)}'::jsonb"
+              }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(
+            sql.contains(r#"DEFAULT '{"theme":"dark","notify":true}'::jsonb"#),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn jsondecode_drives_for_each() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            variable "tables" { default = "{\"users\": \"id\", \"orders\": \"order_id\"}" }
+
+            table "t" {
+              for_each = jsondecode(var.tables)
+              table_name = each.key
+              column "pk" {
+                type = "integer"
+              }
+              column "key" {
+                type    = "text"
+                default = "'${each.value}'"
+              }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let tables: Vec<_> = cfg
+            .tables
+            .iter()
+            .map(|t| {
+                (
+                    t.alt_name.as_deref().unwrap(),
+                    t.columns[1].default.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            tables,
+            [("users", Some("'id'")), ("orders", Some("'order_id'"))]
+        );
+    }
+
     #[test]
     fn count_creates_multiple_triggers() {
         let mut files = HashMap::new();
@@ -461,6 +690,88 @@ mod tests {
         assert_eq!(cfg.triggers.len(), 2);
     }
 
+    #[test]
+    fn exceeding_resource_limit_errors() {
+        use crate::frontend::env::Limits;
+        let limited = || EnvVars {
+            limits: Limits {
+                max_resources: 5,
+                ..Limits::default()
+            },
+            ..EnvVars::default()
+        };
+
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            schema "s" {
+              count = 1000000000
+              name  = "s_${count.index}"
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let err = load_config(&p("/root/main.hcl"), &loader, limited()).unwrap_err();
+        assert!(format!("{err:#}").contains("more than 5 resources"));
+
+        // Resources contributed by module instances count towards the limit too.
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            module "m" {
+              source   = "/root/mod"
+              for_each = ["a", "b", "c"]
+              name     = each.value
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/mod/main.hcl"),
+            r#"
+            variable "name" {}
+            schema "s" { name = "${var.name}_1" }
+            schema "t" { name = "${var.name}_2" }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let err = load_config(&p("/root/main.hcl"), &loader, limited()).unwrap_err();
+        assert!(format!("{err:#}").contains("more than 5 resources"));
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        assert_eq!(cfg.schemas.len(), 6);
+    }
+
+    #[test]
+    fn exceeding_module_depth_errors() {
+        use crate::frontend::env::Limits;
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"module "a" { source = "/root/a" }"#.to_string(),
+        );
+        files.insert(
+            p("/root/a/main.hcl"),
+            r#"module "b" { source = "/root/b" }"#.to_string(),
+        );
+        files.insert(p("/root/b/main.hcl"), r#"schema "s" {}"#.to_string());
+        let loader = MapLoader { files };
+        load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+
+        let env = EnvVars {
+            limits: Limits {
+                max_module_depth: 1,
+                ..Limits::default()
+            },
+            ..EnvVars::default()
+        };
+        let err = load_config(&p("/root/main.hcl"), &loader, env).unwrap_err();
+        assert!(format!("{err:#}").contains("module nesting exceeds maximum depth of 1"));
+    }
+
     #[test]
     fn dynamic_block_expands_columns() {
         let mut files = HashMap::new();
@@ -499,99 +810,332 @@ mod tests {
     }
 
     #[test]
-    fn data_prisma_schema_exposes_models_and_enums() {
+    fn csv_data_source_drives_test_for_each() {
         let mut files = HashMap::new();
         files.insert(
             p("/root/main.hcl"),
             r#"
-            data "prisma_schema" "app" {
-              file = "/root/schema.prisma"
+            data "csv" "cases" {
+              file = "cases.csv"
             }
 
-            table "audit_log" {
-              schema = "public"
-
-              column "user_id" {
-                type     = "bigint"
-                nullable = data.prisma_schema.app.models.User.fields.id.type.optional
-                comment  = data.prisma_schema.app.models.User.fields.id.type.name
-              }
-
-              column "middle_name" {
-                type     = "text"
-                nullable = data.prisma_schema.app.models.User.fields.middleName.type.optional
-                comment  = data.prisma_schema.app.models.User.fields.middleName.type.name
-              }
-
-              column "status" {
-                type    = "text"
-                comment = data.prisma_schema.app.enums.Status.values.ACTIVE.name
-              }
-
-              column "inactive_label" {
-                type    = "text"
-                comment = data.prisma_schema.app.enums.Status.values.INACTIVE.mapped_name
+            test "roundtrip" {
+              for_each = data.csv.cases.rows
+              assert_eq {
+                query    = "SELECT upper('${each.value.input}')"
+                expected = each.value.expected
               }
             }
             "#
             .to_string(),
         );
         files.insert(
-            p("/root/schema.prisma"),
+            p("/root/cases.csv"),
+            "input,expected\r\nabc,ABC\n\"a,b\",\"A,B\"\n\"say \"\"hi\"\"\",\"SAY \"\"HI\"\"\"\n"
+                .to_string(),
+        );
+
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let names: Vec<_> = cfg.tests.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["roundtrip[0]", "roundtrip[1]", "roundtrip[2]"]);
+        let asserts: Vec<_> = cfg
+            .tests
+            .iter()
+            .map(|t| {
+                (
+                    t.assert_eq[0].query.as_str(),
+                    t.assert_eq[0].expected.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            asserts,
+            [
+                ("SELECT upper('abc')", "ABC"),
+                ("SELECT upper('a,b')", "A,B"),
+                ("SELECT upper('say \"hi\"')", "SAY \"HI\""),
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_data_source_rows_feed_attributes() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
             r#"
-            model User {
-              id          Int      @id @default(autoincrement())
-              email       String   @unique
-              middleName  String?
-              status      Status   @default(ACTIVE)
+            data "csv" "owners" {
+              file = "owners.csv"
+              infer_types = true
+            }
+            data "csv" "raw" {
+              file = "owners.csv"
+              header = false
             }
 
-            enum Status {
-              ACTIVE
-              INACTIVE @map("inactive")
+            table "accounts" {
+              comment = "Owned by ${data.csv.owners.rows[1].team} (${data.csv.owners.rows[1].size})"
+              column "id" {
+                type     = "int"
+                nullable = data.csv.owners.rows[1].optional
+              }
+            }
+            output "header" {
+              value = data.csv.raw.rows[0]
+            }
+            output "size" {
+              value = data.csv.owners.rows[0].size
             }
             "#
             .to_string(),
         );
+        files.insert(
+            p("/root/owners.csv"),
+            "team,size,optional\n\"Core, Platform\",12,true\nBilling,3,false\n".to_string(),
+        );
 
         let loader = MapLoader { files };
         let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
-        assert_eq!(cfg.tables.len(), 1);
-        let table = &cfg.tables[0];
-        assert_eq!(table.columns.len(), 4);
-        assert_eq!(table.columns[0].nullable, false);
-        assert_eq!(table.columns[0].comment.as_deref(), Some("Int"));
-        assert_eq!(table.columns[1].nullable, true);
-        assert_eq!(table.columns[1].comment.as_deref(), Some("String"));
-        assert_eq!(table.columns[2].comment.as_deref(), Some("ACTIVE"));
-        assert_eq!(table.columns[3].comment.as_deref(), Some("inactive"));
+        assert_eq!(
+            cfg.tables[0].comment.as_deref(),
+            Some("Owned by Billing (3)")
+        );
+        assert!(!cfg.tables[0].columns[0].nullable);
+        assert_eq!(
+            cfg.outputs[0].value,
+            hcl::Value::Array(vec!["team".into(), "size".into(), "optional".into()])
+        );
+        assert_eq!(cfg.outputs[1].value, hcl::Value::from(12));
     }
 
     #[test]
-    fn clone_prisma_table_with_dynamic_columns() {
+    fn json_file_data_source_drives_module_for_each() {
         let mut files = HashMap::new();
         files.insert(
             p("/root/main.hcl"),
             r#"
-            data "prisma_schema" "source" {
-              file = "/root/schema.prisma"
+            data "json_file" "tables" {
+              file = "tables.json"
             }
 
-            table "user_clone" {
-              schema = "public"
+            module "audit" {
+              source   = "/root/mod"
+              for_each = data.json_file.tables
+              name     = each.value.name
+              comment  = each.value.comment
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/tables.json"),
+            r#"[
+              { "name": "users_audit", "comment": "Audit trail for users" },
+              { "name": "orders_audit", "comment": "Audit trail for orders" }
+            ]"#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/mod/main.hcl"),
+            r#"
+            variable "name" {}
+            variable "comment" {}
 
-              dynamic "column" {
-                for_each = data.prisma_schema.source.models.User.fields
-                labels   = [each.key]
+            table "audit" {
+              table_name = var.name
+              comment    = var.comment
+              column "id" { type = "bigint" }
+            }
+            "#
+            .to_string(),
+        );
 
-                content {
-                  type     = each.value.type.name == "Int" ? "integer" : each.value.type.name == "String" ? "text" : each.value.type.name == "DateTime" ? "timestamptz" : "text"
-                  nullable = each.value.type.optional
-                }
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let tables: Vec<_> = cfg
+            .tables
+            .iter()
+            .map(|t| (t.alt_name.as_deref(), t.comment.as_deref()))
+            .collect();
+        assert_eq!(
+            tables,
+            [
+                (Some("users_audit"), Some("Audit trail for users")),
+                (Some("orders_audit"), Some("Audit trail for orders")),
+            ]
+        );
+    }
+
+    #[test]
+    fn yaml_file_data_source_parses_document() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            data "yaml_file" "settings" {
+              file = "settings.yaml"
+            }
+
+            table "accounts" {
+              comment = "${data.yaml_file.settings.owner} v${data.yaml_file.settings.version}"
+              column "id" { type = "int" }
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/settings.yaml"),
+            "owner: platform\nversion: 2\nregions:\n  - eu\n  - us\n".to_string(),
+        );
+
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        assert_eq!(cfg.tables[0].comment.as_deref(), Some("platform v2"));
+    }
+
+    struct FetchLoader {
+        files: MapLoader,
+        responses: HashMap<String, String>,
+    }
+
+    impl Loader for FetchLoader {
+        fn load(&self, path: &Path) -> Result<String> {
+            self.files.load(path)
+        }
+
+        fn fetch(&self, url: &str) -> Result<String> {
+            self.responses
+                .get(url)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("unexpected url {url}"))
+        }
+    }
+
+    #[test]
+    fn http_data_source_uses_loader_fetch() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            data "http" "shared" {
+              url    = "https://config.example.com/billing.json"
+              format = "json"
+            }
+
+            table "billing" {
+              for_each   = data.http.shared.json.tables
+              table_name = each.value
+              comment    = "Owned by ${data.http.shared.json.owner}"
+              column "id" { type = "bigint" }
+            }
+            "#
+            .to_string(),
+        );
+        let mut responses = HashMap::new();
+        responses.insert(
+            "https://config.example.com/billing.json".to_string(),
+            r#"{ "owner": "billing", "tables": ["invoices", "payments"] }"#.to_string(),
+        );
+
+        let loader = FetchLoader {
+            files: MapLoader {
+                files: files.clone(),
+            },
+            responses,
+        };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let tables: Vec<_> = cfg
+            .tables
+            .iter()
+            .map(|t| (t.alt_name.as_deref(), t.comment.as_deref()))
+            .collect();
+        assert_eq!(
+            tables,
+            [
+                (Some("invoices"), Some("Owned by billing")),
+                (Some("payments"), Some("Owned by billing")),
+            ]
+        );
+
+        // Plain loaders stay offline and refuse to fetch.
+        let offline = MapLoader { files };
+        let err = load_config(&p("/root/main.hcl"), &offline, EnvVars::default()).unwrap_err();
+        assert!(format!("{err:#}").contains("does not support http data sources"));
+    }
+
+    #[test]
+    fn http_data_source_verifies_pinned_hash() {
+        let body = r#"{ "owner": "billing" }"#;
+        let load = |hash: &str| {
+            let mut files = HashMap::new();
+            files.insert(
+                p("/root/main.hcl"),
+                format!(
+                    r#"
+                    data "http" "shared" {{
+                      url    = "https://config.example.com/owner.json"
+                      format = "json"
+                      hash   = "{hash}"
+                    }}
+
+                    table "invoices" {{
+                      comment = data.http.shared.json.owner
+                      column "id" {{ type = "bigint" }}
+                    }}
+                    "#
+                ),
+            );
+            let mut responses = HashMap::new();
+            responses.insert(
+                "https://config.example.com/owner.json".to_string(),
+                body.to_string(),
+            );
+            let loader = FetchLoader {
+                files: MapLoader { files },
+                responses,
+            };
+            load_config(&p("/root/main.hcl"), &loader, EnvVars::default())
+        };
+
+        let pinned = frontend::data_sources::content_hash(body);
+        let cfg = load(&pinned).unwrap();
+        assert_eq!(cfg.tables[0].comment.as_deref(), Some("billing"));
+
+        let stale = frontend::data_sources::content_hash("{}");
+        let err = format!("{:#}", load(&stale).unwrap_err());
+        assert!(err.contains("does not match its pinned hash"), "{err}");
+        assert!(err.contains(&pinned), "{err}");
+
+        for malformed in ["sha256:../../etc/passwd", &pinned.to_uppercase()] {
+            let err = format!("{:#}", load(malformed).unwrap_err());
+            assert!(
+                err.contains("must be 'sha256:' followed by 64 lowercase hex digits"),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn try_and_can_handle_missing_prisma_fields() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            data "prisma_schema" "app" {
+              file = "/root/schema.prisma"
+            }
+
+            table "people" {
+              column "middle_name" {
+                type    = "text"
+                comment = try(data.prisma_schema.app.models.User.fields.middleName.type.name, "none")
               }
 
-              primary_key {
-                columns = ["id"]
+              column "nickname" {
+                type     = "text"
+                nullable = can(data.prisma_schema.app.models.User.fields.nickname)
+                comment  = upper(try(data.prisma_schema.app.models.User.fields.nickname.type.name, "missing"))
               }
             }
             "#
@@ -601,11 +1145,8 @@ mod tests {
             p("/root/schema.prisma"),
             r#"
             model User {
-              id        Int      @id @default(autoincrement())
-              email     String   @unique
-              name      String?
-              createdAt DateTime @default(now())
-              updatedAt DateTime @updatedAt
+              id          Int     @id
+              middleName  String?
             }
             "#
             .to_string(),
@@ -613,75 +1154,1238 @@ mod tests {
 
         let loader = MapLoader { files };
         let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let columns = &cfg.tables[0].columns;
+        assert_eq!(columns[0].comment.as_deref(), Some("String"));
+        assert!(!columns[1].nullable);
+        assert_eq!(columns[1].comment.as_deref(), Some("MISSING"));
+
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            locals {
+              value = try(var.missing, local.also_missing)
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let err = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("try: no argument evaluated without error"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn data_prisma_schema_exposes_models_and_enums() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            data "prisma_schema" "app" {
+              file = "/root/schema.prisma"
+            }
+
+            table "audit_log" {
+              schema = "public"
+
+              column "user_id" {
+                type     = "bigint"
+                nullable = data.prisma_schema.app.models.User.fields.id.type.optional
+                comment  = data.prisma_schema.app.models.User.fields.id.type.name
+              }
+
+              column "middle_name" {
+                type     = "text"
+                nullable = data.prisma_schema.app.models.User.fields.middleName.type.optional
+                comment  = data.prisma_schema.app.models.User.fields.middleName.type.name
+              }
+
+              column "status" {
+                type    = "text"
+                comment = data.prisma_schema.app.enums.Status.values.ACTIVE.name
+              }
+
+              column "inactive_label" {
+                type    = "text"
+                comment = data.prisma_schema.app.enums.Status.values.INACTIVE.mapped_name
+              }
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/schema.prisma"),
+            r#"
+            model User {
+              id          Int      @id @default(autoincrement())
+              email       String   @unique
+              middleName  String?
+              status      Status   @default(ACTIVE)
+            }
+
+            enum Status {
+              ACTIVE
+              INACTIVE @map("inactive")
+            }
+            "#
+            .to_string(),
+        );
 
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
         assert_eq!(cfg.tables.len(), 1);
         let table = &cfg.tables[0];
-        assert_eq!(table.name, "user_clone");
+        assert_eq!(table.columns.len(), 4);
+        assert_eq!(table.columns[0].nullable, false);
+        assert_eq!(table.columns[0].comment.as_deref(), Some("Int"));
+        assert_eq!(table.columns[1].nullable, true);
+        assert_eq!(table.columns[1].comment.as_deref(), Some("String"));
+        assert_eq!(table.columns[2].comment.as_deref(), Some("ACTIVE"));
+        assert_eq!(table.columns[3].comment.as_deref(), Some("inactive"));
+    }
 
-        // Verify all 5 columns were created from Prisma model
-        assert_eq!(table.columns.len(), 5);
+    #[test]
+    fn data_prisma_schema_exposes_sql_defaults() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            data "prisma_schema" "app" {
+              file = "/root/schema.prisma"
+            }
 
-        // Verify column names match Prisma fields
-        let col_names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
-        assert!(col_names.contains(&"id"));
-        assert!(col_names.contains(&"email"));
-        assert!(col_names.contains(&"name"));
-        assert!(col_names.contains(&"createdAt"));
-        assert!(col_names.contains(&"updatedAt"));
+            enum "Status" {
+              values = ["active", "inactive"]
+            }
 
-        // Verify types were mapped correctly
-        let id_col = table.columns.iter().find(|c| c.name == "id").unwrap();
-        assert_eq!(id_col.r#type, "integer");
-        assert!(!id_col.nullable); // Int is not optional in Prisma
+            table "users" {
+              schema = "public"
 
-        let email_col = table.columns.iter().find(|c| c.name == "email").unwrap();
-        assert_eq!(email_col.r#type, "text");
-        assert!(!email_col.nullable); // String is not optional
+              column "status" {
+                type    = "Status"
+                default = data.prisma_schema.app.models.User.fields.status.default
+              }
 
-        let name_col = table.columns.iter().find(|c| c.name == "name").unwrap();
-        assert_eq!(name_col.r#type, "text");
-        assert!(name_col.nullable); // String? is optional in Prisma
+              column "nickname" {
+                type    = "text"
+                default = data.prisma_schema.app.models.User.fields.nickname.default
+              }
+
+              column "created_at" {
+                type    = "timestamptz"
+                default = data.prisma_schema.app.models.User.fields.createdAt.default
+              }
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/schema.prisma"),
+            r#"
+            model User {
+              id        Int      @id @default(autoincrement())
+              status    Status   @default(INACTIVE)
+              nickname  String   @default("it's me")
+              createdAt DateTime @default(now())
+            }
+
+            enum Status {
+              ACTIVE   @map("active")
+              INACTIVE @map("inactive")
+            }
+            "#
+            .to_string(),
+        );
+
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let columns = &cfg.tables[0].columns;
+        assert_eq!(columns[0].default.as_deref(), Some("'inactive'"));
+        assert_eq!(columns[1].default.as_deref(), Some("'it''s me'"));
+        assert_eq!(columns[2].default.as_deref(), Some("now()"));
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("\"status\" \"Status\" DEFAULT 'inactive'"));
+    }
+
+    #[test]
+    fn prisma_updated_at_fields_synthesize_trigger() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            data "prisma_schema" "app" {
+              file                = "/root/schema.prisma"
+              updated_at_triggers = true
+            }
+
+            table "posts" {
+              schema = "public"
+
+              column "id" {
+                type = "integer"
+              }
+
+              column "updated_at" {
+                type    = "timestamptz"
+                comment = "updated_at=${data.prisma_schema.app.models.Post.fields.updatedAt.attributes.updated_at}"
+              }
+            }
+
+            table "User" {
+              schema = "public"
+
+              column "id" {
+                type = "integer"
+              }
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/schema.prisma"),
+            r#"
+            model Post {
+              id        Int      @id
+              updatedAt DateTime @updatedAt @map("updated_at")
+
+              @@map("posts")
+            }
+
+            model User {
+              id Int @id
+            }
+            "#
+            .to_string(),
+        );
+
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let updated_at = &cfg.tables[0].columns[1];
+        assert_eq!(updated_at.comment.as_deref(), Some("updated_at=true"));
+        assert_eq!(cfg.functions.len(), 1);
+        assert_eq!(cfg.triggers.len(), 1);
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("CREATE OR REPLACE FUNCTION \"public\".\"posts_set_updated_at\""));
+        assert!(sql.contains("NEW.\"updated_at\" = now();"), "{sql}");
+        assert!(sql.contains("CREATE TRIGGER \"posts_set_updated_at\""));
+        assert!(sql.contains("BEFORE UPDATE ON \"public\".\"posts\""));
+    }
+
+    #[test]
+    fn clone_prisma_table_with_dynamic_columns() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            data "prisma_schema" "source" {
+              file = "/root/schema.prisma"
+            }
+
+            table "user_clone" {
+              schema = "public"
+
+              dynamic "column" {
+                for_each = data.prisma_schema.source.models.User.fields
+                labels   = [each.key]
+
+                content {
+                  type     = each.value.type.name == "Int" ? "integer" : each.value.type.name == "String" ? "text" : each.value.type.name == "DateTime" ? "timestamptz" : "text"
+                  nullable = each.value.type.optional
+                }
+              }
+
+              primary_key {
+                columns = ["id"]
+              }
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/schema.prisma"),
+            r#"
+            model User {
+              id        Int      @id @default(autoincrement())
+              email     String   @unique
+              name      String?
+              createdAt DateTime @default(now())
+              updatedAt DateTime @updatedAt
+            }
+            "#
+            .to_string(),
+        );
+
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+
+        assert_eq!(cfg.tables.len(), 1);
+        let table = &cfg.tables[0];
+        assert_eq!(table.name, "user_clone");
+
+        // Verify all 5 columns were created from Prisma model
+        assert_eq!(table.columns.len(), 5);
+
+        // Verify column names match Prisma fields
+        let col_names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert!(col_names.contains(&"id"));
+        assert!(col_names.contains(&"email"));
+        assert!(col_names.contains(&"name"));
+        assert!(col_names.contains(&"createdAt"));
+        assert!(col_names.contains(&"updatedAt"));
+
+        // Verify types were mapped correctly
+        let id_col = table.columns.iter().find(|c| c.name == "id").unwrap();
+        assert_eq!(id_col.r#type, "integer");
+        assert!(!id_col.nullable); // Int is not optional in Prisma
+
+        let email_col = table.columns.iter().find(|c| c.name == "email").unwrap();
+        assert_eq!(email_col.r#type, "text");
+        assert!(!email_col.nullable); // String is not optional
+
+        let name_col = table.columns.iter().find(|c| c.name == "name").unwrap();
+        assert_eq!(name_col.r#type, "text");
+        assert!(name_col.nullable); // String? is optional in Prisma
+
+        let created_at_col = table
+            .columns
+            .iter()
+            .find(|c| c.name == "createdAt")
+            .unwrap();
+        assert_eq!(created_at_col.r#type, "timestamptz");
+        assert!(!created_at_col.nullable);
+
+        let updated_at_col = table
+            .columns
+            .iter()
+            .find(|c| c.name == "updatedAt")
+            .unwrap();
+        assert_eq!(updated_at_col.r#type, "timestamptz");
+        assert!(!updated_at_col.nullable);
+
+        // Verify primary key was created from Prisma @id attribute
+        assert!(table.primary_key.is_some());
+        let pk = table.primary_key.as_ref().unwrap();
+        assert_eq!(pk.columns.len(), 1);
+        assert_eq!(pk.columns[0], "id");
+
+        validate(&cfg, false).unwrap();
+    }
+
+    #[test]
+    fn prisma_field_flags_drive_keys_in_cloned_table() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            data "prisma_schema" "app" {
+              file = "/root/schema.prisma"
+            }
+
+            table "post_clone" {
+              schema = "public"
+
+              dynamic "column" {
+                for_each = { for k, f in data.prisma_schema.app.models.Post.fields : k => f if !f.is_relation }
+                labels   = [each.key]
+
+                content {
+                  type = each.value.type.name == "Int" ? "integer" : "text"
+                }
+              }
+
+              primary_key {
+                columns = [for k, f in data.prisma_schema.app.models.Post.fields : k if f.is_id]
+              }
+
+              dynamic "index" {
+                for_each = { for k, f in data.prisma_schema.app.models.Post.fields : k => f if f.is_unique }
+                labels   = ["post_clone_${each.key}_key"]
+
+                content {
+                  columns = [each.key]
+                  unique  = true
+                }
+              }
+            }
+            "#
+            .to_string(),
+        );
+        files.insert(
+            p("/root/schema.prisma"),
+            r#"
+            model User {
+              id    Int    @id
+              posts Post[]
+            }
+
+            model Post {
+              id       Int    @id
+              slug     String @unique
+              title    String
+              authorId Int
+              author   User   @relation(fields: [authorId], references: [id])
+            }
+            "#
+            .to_string(),
+        );
+
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let table = &cfg.tables[0];
+        let col_names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(col_names, vec!["id", "slug", "title", "authorId"]);
+        assert_eq!(table.primary_key.as_ref().unwrap().columns, vec!["id"]);
+        assert_eq!(table.indexes.len(), 1);
+        assert!(table.indexes[0].unique);
+        assert_eq!(table.indexes[0].columns, vec!["slug"]);
+
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("CREATE UNIQUE INDEX IF NOT EXISTS \"post_clone_slug_key\""));
+    }
+
+    #[test]
+    fn composite_types_precede_tables_and_nest_in_order() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            type "Contact" {
+              field "name" {
+                type = "text"
+              }
+              field "addresses" {
+                type = "Address[]"
+              }
+            }
+
+            type "Address" {
+              field "street" {
+                type = "text"
+              }
+            }
+
+            table "customers" {
+              schema = "public"
+
+              column "home" {
+                type = "Address"
+              }
+
+              column "previous" {
+                type = "Address[]"
+              }
+
+              column "contact" {
+                type = "Contact"
+              }
+            }
+            "#
+            .to_string(),
+        );
+
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        validate(&cfg, true).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        let address = sql.find("CREATE TYPE \"public\".\"Address\"").unwrap();
+        let contact = sql.find("CREATE TYPE \"public\".\"Contact\"").unwrap();
+        let table = sql.find("CREATE TABLE").unwrap();
+        assert!(address < contact && contact < table);
+        assert!(sql.contains("\"addresses\" \"Address\"[]"));
+        assert!(sql.contains("\"home\" \"Address\""));
+        assert!(sql.contains("\"previous\" \"Address\"[]"));
+
+        let mut cfg = cfg;
+        cfg.tables[0].columns[0].r#type = "Adress".into();
+        let err = validate(&cfg, true).unwrap_err().to_string();
+        assert!(err.contains("type 'Adress'"), "{err}");
+
+        cfg.types[1].fields[0].r#type = "Contact".into();
+        let err = validate(&cfg, false).unwrap_err().to_string();
+        assert!(err.contains("contains itself"), "{err}");
+    }
+
+    #[test]
+    fn new_enum_and_column_emit_type_before_table() {
+        let generate = |hcl: &str| {
+            let files = HashMap::from([(p("/root/main.hcl"), hcl.to_string())]);
+            let loader = MapLoader { files };
+            let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+            validate(&cfg, true).unwrap();
+            generate_with_backend("postgres", &cfg, false).unwrap()
+        };
+
+        let before = generate(
+            r#"
+            table "orders" {
+              column "id" {
+                type = "integer"
+              }
+            }
+            "#,
+        );
+        assert!(!before.contains("CREATE TYPE"));
+
+        // Migrations are generated from the full desired state, so adding an
+        // enum and a column using it in one step still creates the type first.
+        let after = generate(
+            r#"
+            enum "OrderStatus" {
+              values = ["open", "shipped"]
+            }
+
+            table "orders" {
+              column "id" {
+                type = "integer"
+              }
+
+              column "status" {
+                type = "OrderStatus"
+              }
+            }
+            "#,
+        );
+        let create_type = after.find("CREATE TYPE \"public\".\"OrderStatus\"");
+        let create_type = create_type.unwrap();
+        let column = after.find("\"status\" \"OrderStatus\"").unwrap();
+        assert!(create_type < column);
+    }
+
+    #[test]
+    fn parse_extension_and_generate_sql() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            extension "pgcrypto" {}
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        assert_eq!(cfg.extensions.len(), 1);
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("CREATE EXTENSION IF NOT EXISTS \"pgcrypto\";"));
+    }
+
+    #[test]
+    fn sequence_owned_by_emits_alter_sequence() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            sequence "users_id_seq" {
+              schema   = "app"
+              owned_by = "users.id"
+            }
+            sequence "orders_id_seq" {
+              schema   = "app"
+              owned_by = "app.orders.id"
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("CREATE SEQUENCE IF NOT EXISTS \"app\".\"users_id_seq\";"));
+        assert!(sql.contains("ALTER SEQUENCE \"app\".\"users_id_seq\" OWNED BY \"users\".\"id\";"));
+        assert!(sql.contains("CREATE SEQUENCE IF NOT EXISTS \"app\".\"orders_id_seq\";"));
+        assert!(sql.contains(
+            "ALTER SEQUENCE \"app\".\"orders_id_seq\" OWNED BY \"app\".\"orders\".\"id\";"
+        ));
+        let create = sql
+            .find("CREATE SEQUENCE IF NOT EXISTS \"app\".\"users_id_seq\"")
+            .unwrap();
+        let alter = sql.find("ALTER SEQUENCE \"app\".\"users_id_seq\"").unwrap();
+        assert!(create < alter);
+    }
+
+    #[test]
+    fn down_migration_drops_children_before_parents() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            table "parent" {
+              column "id" { type = "int" }
+              primary_key { columns = ["id"] }
+            }
+            table "child" {
+              column "id" { type = "int" }
+              column "parent_id" { type = "int" }
+              foreign_key {
+                columns = ["parent_id"]
+                ref {
+                  table = "parent"
+                  columns = ["id"]
+                }
+              }
+            }
+            function "touch" {
+              language = "plpgsql"
+              returns = "trigger"
+              body = "BEGIN RETURN NEW; END;"
+            }
+            trigger "child_touch" {
+              table = "child"
+              timing = "BEFORE"
+              events = ["UPDATE"]
+              function = "touch"
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let sql = generate_down_with_backend("postgres", &cfg, false).unwrap();
+        let trigger = sql
+            .find("DROP TRIGGER IF EXISTS \"child_touch\" ON \"public\".\"child\";")
+            .unwrap();
+        let function = sql
+            .find("DROP FUNCTION IF EXISTS \"public\".\"touch\"();")
+            .unwrap();
+        let child = sql
+            .find("DROP TABLE IF EXISTS \"public\".\"child\";")
+            .unwrap();
+        let parent = sql
+            .find("DROP TABLE IF EXISTS \"public\".\"parent\";")
+            .unwrap();
+        assert!(trigger < function);
+        assert!(function < child);
+        assert!(child < parent);
+        assert!(!sql.contains("CREATE"));
+    }
+
+    #[test]
+    fn deferrable_foreign_keys_generate_sql() {
+        let hcl = |deferrable: bool, initially_deferred: bool| {
+            format!(
+                r#"
+                table "a" {{
+                  column "id" {{ type = "int" }}
+                  column "b_id" {{ type = "int" }}
+                  foreign_key {{
+                    name = "a_b_fk"
+                    columns = ["b_id"]
+                    ref {{
+                      table = "b"
+                      columns = ["id"]
+                    }}
+                    on_delete = "CASCADE"
+                    deferrable = {deferrable}
+                    initially_deferred = {initially_deferred}
+                  }}
+                }}
+                "#
+            )
+        };
+        let sql_for = |deferrable: bool, initially_deferred: bool| {
+            let mut files = HashMap::new();
+            files.insert(p("/root/main.hcl"), hcl(deferrable, initially_deferred));
+            let loader = MapLoader { files };
+            let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+            validate(&cfg, false)?;
+            generate_with_backend("postgres", &cfg, false)
+        };
+
+        let sql = sql_for(true, true).unwrap();
+        assert!(sql.contains("ON DELETE CASCADE DEFERRABLE INITIALLY DEFERRED"));
+        let sql = sql_for(true, false).unwrap();
+        assert!(sql.contains("ON DELETE CASCADE DEFERRABLE INITIALLY IMMEDIATE"));
+        let sql = sql_for(false, false).unwrap();
+        assert!(!sql.contains("DEFERRABLE"));
+        let err = sql_for(false, true).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("sets initially_deferred without deferrable = true")
+        );
+    }
+
+    #[test]
+    fn foreign_key_match_generates_sql() {
+        let sql_for = |m: &str| {
+            let mut files = HashMap::new();
+            files.insert(
+                p("/root/main.hcl"),
+                format!(
+                    r#"
+                    table "a" {{
+                      column "b_x" {{ type = "int" }}
+                      column "b_y" {{ type = "int" }}
+                      foreign_key {{
+                        columns = ["b_x", "b_y"]
+                        ref {{
+                          table = "b"
+                          columns = ["x", "y"]
+                        }}
+                        match = "{m}"
+                        on_delete = "CASCADE"
+                      }}
+                    }}
+                    "#
+                ),
+            );
+            let loader = MapLoader { files };
+            let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+            validate(&cfg, false)?;
+            generate_with_backend("postgres", &cfg, false)
+        };
+
+        let sql = sql_for("full").unwrap();
+        assert!(
+            sql.contains("REFERENCES \"public\".\"b\" (\"x\", \"y\") MATCH FULL ON DELETE CASCADE")
+        );
+        assert!(
+            sql_for("SIMPLE")
+                .unwrap()
+                .contains("MATCH SIMPLE ON DELETE")
+        );
+        let err = sql_for("loose").unwrap_err();
+        assert!(err.to_string().contains("invalid match 'loose'"));
+    }
+
+    #[test]
+    fn postgres_sql_splits_into_resources() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            sequence "counter" {}
+            table "users" {
+              column "id" { type = "int" }
+            }
+            view "v_users" {
+              sql = "SELECT id FROM users"
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let resources =
+            provider::postgres::backend::to_sql_resources(&cfg, Default::default()).unwrap();
+        let names: Vec<_> = resources.iter().map(|r| r.resource.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "sequence \"counter\"",
+                "table \"users\"",
+                "view \"v_users\""
+            ]
+        );
+        assert!(resources[1].sql.contains("CREATE TABLE"));
+        let body: String = resources.iter().map(|r| r.sql.as_str()).collect();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.ends_with(&body));
+    }
+
+    #[test]
+    fn table_storage_parameters_generate_sql() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            table "events" {
+              column "id" { type = "int" }
+              storage_parameters = {
+                fillfactor = 70
+                autovacuum_enabled = false
+                vacuum_index_cleanup = "auto"
+              }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains(
+            "\n) WITH (fillfactor = 70, autovacuum_enabled = false, vacuum_index_cleanup = 'auto');"
+        ));
+    }
+
+    #[test]
+    fn tablespaces_on_tables_and_indexes_generate_sql() {
+        let hcl = |tablespace: &str| {
+            format!(
+                r#"
+                tablespace "fast" {{
+                  location = "/mnt/fast"
+                }}
+                table "events" {{
+                  column "id" {{ type = "int" }}
+                  tablespace = "{tablespace}"
+                }}
+                index "events_id_idx" {{
+                  table = "events"
+                  columns = ["id"]
+                  where = "id > 0"
+                  tablespace = "fast"
+                }}
+                "#
+            )
+        };
+        let mut files = HashMap::new();
+        files.insert(p("/root/main.hcl"), hcl("fast"));
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("\n) TABLESPACE \"fast\";"));
+        assert!(
+            sql.contains("ON \"public\".\"events\" (\"id\") TABLESPACE \"fast\" WHERE id > 0;")
+        );
+
+        let mut files = HashMap::new();
+        files.insert(p("/root/main.hcl"), hcl("slow"));
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let err = validate(&cfg, false).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("table 'events' references undeclared tablespace 'slow'")
+        );
+    }
+
+    #[test]
+    fn function_parameters_support_defaults_and_modes() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            function "add" {
+              language = "sql"
+              returns = "int"
+              parameters = ["a int", { name = "b", type = "int", mode = "in", default = 0 }]
+              body = "SELECT a + b"
+              comment = "Adds"
+            }
+            function "split" {
+              language = "plpgsql"
+              returns = "record"
+              parameters = [
+                { name = "total", type = "int" },
+                { mode = "out", name = "half", type = "int" },
+              ]
+              body = "BEGIN half := total / 2; END;"
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("FUNCTION \"public\".\"add\"(a int, IN b int DEFAULT 0) RETURNS int"));
+        assert!(sql.contains("COMMENT ON FUNCTION \"public\".\"add\"(a int, IN b int) IS 'Adds';"));
+        assert!(
+            sql.contains("FUNCTION \"public\".\"split\"(total int, OUT half int) RETURNS record")
+        );
+
+        let down = generate_down_with_backend("postgres", &cfg, false).unwrap();
+        assert!(down.contains("DROP FUNCTION IF EXISTS \"public\".\"add\"(a int, IN b int);"));
+
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            function "bad" {
+              returns = "int"
+              parameters = [{ mode = "sideways", name = "x", type = "int" }]
+              body = "SELECT 1"
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let err = validate(&cfg, false).unwrap_err();
+        assert!(err.to_string().contains("invalid mode 'sideways'"));
+    }
+
+    #[test]
+    fn operator_generates_sql() {
+        let load = |hcl: &str| {
+            let mut files = HashMap::new();
+            files.insert(p("/root/main.hcl"), hcl.to_string());
+            let loader = MapLoader { files };
+            load_config(&p("/root/main.hcl"), &loader, EnvVars::default())
+        };
+        let cfg = load(
+            r#"
+            operator "distance" {
+              name       = "<->"
+              schema     = "geo"
+              left       = "point"
+              right      = "point"
+              function   = "geo.point_distance"
+              commutator = "<->"
+              comment    = "Distance between points"
+            }
+            "#,
+        )
+        .unwrap();
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains(
+            "CREATE OPERATOR \"geo\".<-> (LEFTARG = point, RIGHTARG = point, FUNCTION = geo.point_distance, COMMUTATOR = <->);"
+        ));
+        assert!(sql.contains(
+            "COMMENT ON OPERATOR \"geo\".<-> (point, point) IS 'Distance between points';"
+        ));
+        let down = generate_down_with_backend("postgres", &cfg, false).unwrap();
+        assert!(down.contains("DROP OPERATOR IF EXISTS \"geo\".<-> (point, point);"));
+
+        let cfg = load(
+            r#"
+            operator "distance" {
+              left     = "point"
+              function = "f"
+            }
+            "#,
+        )
+        .unwrap();
+        let err = validate(&cfg, false).unwrap_err();
+        assert!(err.to_string().contains("invalid name 'distance'"));
+
+        let err = load(r#"operator "+" { left = "point" }"#).unwrap_err();
+        assert!(format!("{err:#}").contains("operator '+' requires 'function' (or 'procedure')"));
+    }
+
+    #[test]
+    fn instead_of_and_constraint_triggers_generate_sql() {
+        let load = |hcl: &str| {
+            let mut files = HashMap::new();
+            files.insert(p("/root/main.hcl"), hcl.to_string());
+            let loader = MapLoader { files };
+            load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap()
+        };
+        let functions = r#"
+            function "write_active_users" {
+              returns = "trigger"
+              body = "BEGIN INSERT INTO users VALUES (NEW.*); RETURN NEW; END;"
+            }
+            function "check_balance" {
+              returns = "trigger"
+              body = "BEGIN RETURN NULL; END;"
+            }
+        "#;
+        let cfg = load(&format!(
+            r#"{functions}
+            view "active_users" {{
+              sql = "SELECT * FROM users"
+            }}
+            trigger "active_users_insert" {{
+              table    = "active_users"
+              timing   = "INSTEAD OF"
+              events   = ["INSERT"]
+              function = "write_active_users"
+            }}
+            trigger "ledger_balanced" {{
+              table              = "ledger"
+              timing             = "AFTER"
+              events             = ["INSERT", "UPDATE"]
+              function           = "check_balance"
+              constraint         = true
+              deferrable         = true
+              initially_deferred = true
+            }}
+            "#
+        ));
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains(
+            "CREATE TRIGGER \"active_users_insert\"\n    INSTEAD OF INSERT ON \"public\".\"active_users\"\n    FOR EACH ROW\n"
+        ));
+        assert!(sql.contains(
+            "CREATE CONSTRAINT TRIGGER \"ledger_balanced\"\n    AFTER INSERT OR UPDATE ON \"public\".\"ledger\"\n    DEFERRABLE INITIALLY DEFERRED\n    FOR EACH ROW\n"
+        ));
+
+        let invalid = [
+            (
+                r#"timing = "INSTEAD OF"
+                   level = "STATEMENT""#,
+                "must use level = \"ROW\"",
+            ),
+            (
+                r#"timing = "INSTEAD OF"
+                   when = "NEW.id > 0""#,
+                "cannot have a when condition",
+            ),
+            (
+                r#"timing = "BEFORE"
+                   constraint = true"#,
+                "must be AFTER",
+            ),
+            (
+                r#"timing = "AFTER"
+                   deferrable = true"#,
+                "without constraint = true",
+            ),
+        ];
+        for (attrs, expected) in invalid {
+            let cfg = load(&format!(
+                r#"{functions}
+                trigger "t" {{
+                  table    = "users"
+                  function = "check_balance"
+                  {attrs}
+                }}
+                "#
+            ));
+            let err = validate(&cfg, false).unwrap_err().to_string();
+            assert!(err.contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn trigger_update_of_columns_generates_sql() {
+        let load = |events: &str| {
+            let hcl = format!(
+                r#"
+                function "audit" {{
+                  returns = "trigger"
+                  body = "BEGIN RETURN NEW; END;"
+                }}
+                trigger "users_audit" {{
+                  table          = "users"
+                  timing         = "AFTER"
+                  events         = {events}
+                  update_columns = ["email", "name"]
+                  function       = "audit"
+                }}
+                "#
+            );
+            let mut files = HashMap::new();
+            files.insert(p("/root/main.hcl"), hcl);
+            let loader = MapLoader { files };
+            load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap()
+        };
+
+        let cfg = load(r#"["INSERT", "UPDATE"]"#);
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(
+            sql.contains("AFTER INSERT OR UPDATE OF \"email\", \"name\" ON \"public\".\"users\"")
+        );
 
-        let created_at_col = table
-            .columns
-            .iter()
-            .find(|c| c.name == "createdAt")
-            .unwrap();
-        assert_eq!(created_at_col.r#type, "timestamptz");
-        assert!(!created_at_col.nullable);
+        let err = validate(&load(r#"["INSERT"]"#), false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("UPDATE is not among its events"), "{err}");
+    }
 
-        let updated_at_col = table
-            .columns
-            .iter()
-            .find(|c| c.name == "updatedAt")
-            .unwrap();
-        assert_eq!(updated_at_col.r#type, "timestamptz");
-        assert!(!updated_at_col.nullable);
+    #[test]
+    fn trigger_transition_tables_generate_sql() {
+        let load = |attrs: &str| {
+            let hcl = format!(
+                r#"
+                function "audit_changes" {{
+                  returns = "trigger"
+                  body = "BEGIN RETURN NULL; END;"
+                }}
+                trigger "accounts_audit" {{
+                  table    = "accounts"
+                  function = "audit_changes"
+                  events   = ["UPDATE"]
+                  level    = "STATEMENT"
+                  {attrs}
+                }}
+                "#
+            );
+            let mut files = HashMap::new();
+            files.insert(p("/root/main.hcl"), hcl);
+            let loader = MapLoader { files };
+            load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap()
+        };
 
-        // Verify primary key was created from Prisma @id attribute
-        assert!(table.primary_key.is_some());
-        let pk = table.primary_key.as_ref().unwrap();
-        assert_eq!(pk.columns.len(), 1);
-        assert_eq!(pk.columns[0], "id");
+        let cfg = load(
+            r#"timing                = "AFTER"
+               referencing_old_table = "old_rows"
+               referencing_new_table = "new_rows""#,
+        );
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains(
+            "AFTER UPDATE ON \"public\".\"accounts\"\n    REFERENCING OLD TABLE AS \"old_rows\" NEW TABLE AS \"new_rows\"\n    FOR EACH STATEMENT\n"
+        ));
+
+        let cfg = load(
+            r#"timing                = "BEFORE"
+               referencing_new_table = "new_rows""#,
+        );
+        let err = validate(&cfg, false).unwrap_err().to_string();
+        assert!(err.contains("non-constraint AFTER trigger"), "{err}");
+    }
 
+    #[test]
+    fn rule_generates_sql() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            table "orders" {
+              column "id" { type = "int" }
+            }
+            table "orders_archive" {
+              column "id" { type = "int" }
+            }
+            rule "redirect_orders" {
+              table = "orders"
+              event = "insert"
+              where = "NEW.id < 0"
+              instead = true
+              command = "INSERT INTO orders_archive VALUES (NEW.id);"
+            }
+            rule "ignore_deletes" {
+              table = "orders"
+              event = "DELETE"
+              instead = true
+              replace = false
+              command = "nothing"
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
         validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains(
+            "CREATE OR REPLACE RULE \"redirect_orders\" AS ON INSERT TO \"public\".\"orders\" WHERE NEW.id < 0 DO INSTEAD (INSERT INTO orders_archive VALUES (NEW.id));"
+        ));
+        assert!(sql.contains(
+            "CREATE RULE \"ignore_deletes\" AS ON DELETE TO \"public\".\"orders\" DO INSTEAD NOTHING;"
+        ));
     }
 
     #[test]
-    fn parse_extension_and_generate_sql() {
+    fn evolving_enum_adds_each_value() {
         let mut files = HashMap::new();
         files.insert(
             p("/root/main.hcl"),
             r#"
-            extension "pgcrypto" {}
+            enum "status" {
+              schema = "app"
+              values = ["active", "paused", "archived"]
+              evolve = true
+            }
+            enum "fixed" {
+              values = ["a"]
+            }
             "#
             .to_string(),
         );
         let loader = MapLoader { files };
         let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
-        assert_eq!(cfg.extensions.len(), 1);
         let sql = generate_with_backend("postgres", &cfg, false).unwrap();
-        assert!(sql.contains("CREATE EXTENSION IF NOT EXISTS \"pgcrypto\";"));
+        let create = sql.find("CREATE TYPE \"app\".\"status\"").unwrap();
+        let end_do = create + sql[create..].find("END$$;").unwrap();
+        let add = sql
+            .find("ALTER TYPE \"app\".\"status\" ADD VALUE IF NOT EXISTS 'active';\n")
+            .unwrap();
+        // Emitted after the guarded DO block, never inside it.
+        assert!(end_do < add);
+        assert!(sql.contains(
+            "ALTER TYPE \"app\".\"status\" ADD VALUE IF NOT EXISTS 'paused' AFTER 'active';"
+        ));
+        assert!(sql.contains(
+            "ALTER TYPE \"app\".\"status\" ADD VALUE IF NOT EXISTS 'archived' AFTER 'paused';"
+        ));
+        assert!(!sql.contains("ALTER TYPE \"public\".\"fixed\""));
+    }
+
+    #[test]
+    fn column_collation_generates_sql() {
+        let hcl = |collation: &str| {
+            format!(
+                r#"
+                collation "case_insensitive" {{
+                  provider = "icu"
+                  locale = "und-u-ks-level2"
+                  deterministic = false
+                }}
+                table "users" {{
+                  column "name" {{
+                    type = "text"
+                    nullable = false
+                    collation = "{collation}"
+                  }}
+                }}
+                "#
+            )
+        };
+        let sql_for = |collation: &str| {
+            let mut files = HashMap::new();
+            files.insert(p("/root/main.hcl"), hcl(collation));
+            let loader = MapLoader { files };
+            let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+            validate(&cfg, false)?;
+            generate_with_backend("postgres", &cfg, false)
+        };
+
+        let sql = sql_for("und-x-icu").unwrap();
+        assert!(sql.contains("\"name\" text COLLATE \"und-x-icu\" NOT NULL"));
+        let sql = sql_for("case_insensitive").unwrap();
+        assert!(sql.contains("\"name\" text COLLATE \"case_insensitive\" NOT NULL"));
+        assert!(sql_for("en_US.utf8").is_ok());
+        let err = sql_for("Not A Collation").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unknown collation 'Not A Collation'")
+        );
+    }
+
+    #[test]
+    fn table_exclusion_constraint_generates_sql() {
+        let hcl = |column: &str| {
+            format!(
+                r#"
+                extension "btree_gist" {{}}
+                table "bookings" {{
+                  schema = "public"
+                  column "room_id" {{ type = "int" }}
+                  column "starts_at" {{ type = "timestamptz" }}
+                  column "ends_at" {{ type = "timestamptz" }}
+                  column "cancelled" {{ type = "boolean" }}
+                  exclude "no_overlap" {{
+                    using = "gist"
+                    element {{
+                      column   = "{column}"
+                      operator = "="
+                    }}
+                    element {{
+                      expression = "tstzrange(starts_at, ends_at)"
+                      operator   = "&&"
+                    }}
+                    where = "NOT cancelled"
+                  }}
+                }}
+                "#
+            )
+        };
+        let mut files = HashMap::new();
+        files.insert(p("/root/main.hcl"), hcl("room_id"));
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains(
+            "CONSTRAINT \"no_overlap\" EXCLUDE USING gist (\"room_id\" WITH =, (tstzrange(starts_at, ends_at)) WITH &&) WHERE (NOT cancelled)"
+        ));
+
+        let mut files = HashMap::new();
+        files.insert(p("/root/main.hcl"), hcl("room"));
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let err = validate(&cfg, false).unwrap_err();
+        assert!(err.to_string().contains("references missing column 'room'"));
     }
 
     #[test]
@@ -717,6 +2421,132 @@ mod tests {
         assert!(json.contains("\"extensions\""));
     }
 
+    #[test]
+    fn json_backend_output_is_deterministic() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            table "zebras" {
+              column "id" { type = "integer" }
+            }
+            table "apes" {
+              column "id" { type = "integer" }
+            }
+            schema "reporting" {}
+            schema "audit" {}
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+
+        let first = crate::generate_with_backend("json", &cfg, false).unwrap();
+        let second = crate::generate_with_backend("json", &cfg, false).unwrap();
+        assert_eq!(first, second);
+
+        let doc: serde_json::Value = serde_json::from_str(&first).unwrap();
+        assert_eq!(doc["version"], crate::backends::json::FORMAT_VERSION);
+        let names = |key: &str| -> Vec<String> {
+            doc["config"][key]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|t| t["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(names("tables"), ["apes", "zebras"]);
+        assert_eq!(names("schemas"), ["audit", "reporting"]);
+
+        let compact = crate::generate_with_backend("json-compact", &cfg, false).unwrap();
+        assert!(!compact.contains('\n'));
+        let reparsed: serde_json::Value = serde_json::from_str(&compact).unwrap();
+        assert_eq!(reparsed, doc);
+    }
+
+    #[test]
+    fn sensitive_variable_is_redacted_in_json() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            variable "app_password" {
+              sensitive = true
+            }
+            role "app" {
+              login    = true
+              password = var.app_password
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let env = EnvVars {
+            vars: HashMap::from([("app_password".into(), hcl::Value::from("s3cr3t"))]),
+            ..EnvVars::default()
+        };
+        let mut cfg = load_config(&p("/root/main.hcl"), &loader, env).unwrap();
+
+        let json = generate_with_backend("json", &cfg, false).unwrap();
+        assert!(!json.contains("s3cr3t"));
+        assert!(json.contains("\"password\": \"(sensitive)\""));
+        // SQL must still carry the real value.
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("s3cr3t"));
+
+        cfg.sensitive_values.clear();
+        let json = generate_with_backend("json", &cfg, false).unwrap();
+        assert!(json.contains("\"password\": \"s3cr3t\""));
+    }
+
+    #[test]
+    fn sensitive_numbers_and_bools_are_not_redacted() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            variable "db" {
+              sensitive = true
+            }
+            role "app" {
+              login    = true
+              password = "x"
+            }
+            table "servers" {
+              column "port" {
+                type    = "integer"
+                default = "5432"
+              }
+              column "ssl" {
+                type     = "boolean"
+                nullable = true
+              }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let db = hcl::Value::Object(hcl::Map::from_iter([
+            ("password".to_string(), hcl::Value::from("x")),
+            ("port".to_string(), hcl::Value::from(5432)),
+            ("ssl".to_string(), hcl::Value::from(true)),
+        ]));
+        let env = EnvVars {
+            vars: HashMap::from([("db".into(), db)]),
+            ..EnvVars::default()
+        };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, env).unwrap();
+
+        let json = generate_with_backend("json", &cfg, false).unwrap();
+        let doc: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let role = &doc["config"]["roles"][0];
+        assert_eq!(role["password"], "(sensitive)");
+        assert_eq!(role["login"], true);
+        let columns = &doc["config"]["tables"][0]["columns"];
+        assert_eq!(columns[0]["default"], "5432");
+        assert_eq!(columns[1]["nullable"], true);
+    }
+
     #[test]
     fn parse_view_and_generate_sql_and_json() {
         let mut files = HashMap::new();
@@ -764,6 +2594,90 @@ mod tests {
         assert!(json.contains("\"materialized\""));
     }
 
+    #[test]
+    fn materialized_view_unique_index_follows_view() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            materialized "daily_signups" {
+              schema = "public"
+              sql    = "SELECT current_date AS day, 1 AS signups"
+
+              index "daily_signups_day_key" {
+                columns = ["day"]
+                unique  = true
+              }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        assert_eq!(cfg.materialized[0].indexes.len(), 1);
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        let index = "CREATE UNIQUE INDEX IF NOT EXISTS \"daily_signups_day_key\" ON \"public\".\"daily_signups\" (\"day\");";
+        let index_at = sql.find(index).expect(&sql);
+        let view_at = sql.find("CREATE MATERIALIZED VIEW").unwrap();
+        assert!(view_at < index_at);
+    }
+
+    #[test]
+    fn wrap_transaction_moves_concurrent_index_after_commit() {
+        let files = HashMap::from([(
+            p("/root/main.hcl"),
+            r#"
+            enum "status" {
+              values = ["active", "archived"]
+              evolve = true
+            }
+
+            table "orders" {
+              column "id" {
+                type = "integer"
+              }
+              column "status" {
+                type = "status"
+              }
+
+              index "orders_id_idx" {
+                columns = ["id"]
+              }
+
+              index "orders_status_idx" {
+                columns      = ["status"]
+                concurrently = true
+              }
+            }
+            "#
+            .to_string(),
+        )]);
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+
+        let concurrent = "CREATE INDEX CONCURRENTLY IF NOT EXISTS \"orders_status_idx\" ON \"public\".\"orders\" (\"status\");";
+        let plain = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(plain.contains(concurrent), "{plain}");
+        assert!(!plain.contains("BEGIN;"));
+
+        let sql = generate_in_transaction_with_backend("postgres", &cfg, false).unwrap();
+        let begin = sql.find("BEGIN;").unwrap();
+        let commit = sql.find("COMMIT;").unwrap();
+        let inside = &sql[begin..commit];
+        assert!(inside.contains("CREATE TABLE"));
+        assert!(inside.contains("\"orders_id_idx\""));
+        assert!(!inside.contains("CONCURRENTLY"));
+        assert!(!inside.contains("ADD VALUE"));
+
+        let after = &sql[commit..];
+        let add_value = after
+            .find("ALTER TYPE \"public\".\"status\" ADD VALUE")
+            .expect(&sql);
+        let index = after.find(concurrent).expect(&sql);
+        assert!(add_value < index);
+        assert!(sql.trim_end().ends_with(concurrent));
+    }
+
     #[test]
     fn parse_enum_and_generate_sql_json_prisma() {
         let mut files = HashMap::new();
@@ -797,6 +2711,129 @@ mod tests {
         assert!(prisma.contains("status status"));
     }
 
+    #[test]
+    fn prisma_doc_comments_come_from_hcl_comments() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            enum "status" {
+              values  = ["active", "disabled"]
+              comment = "Account lifecycle state"
+            }
+            table "users" {
+              comment = "Registered users.\nOne row per login."
+              column "id" {
+                type     = "serial"
+                nullable = false
+              }
+              column "email" {
+                type    = "text"
+                comment = "Primary contact address"
+              }
+              primary_key { columns = ["id"] }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let prisma = crate::generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(
+            prisma.contains("/// Account lifecycle state\nenum status {"),
+            "{prisma}"
+        );
+        assert!(
+            prisma.contains("/// Registered users.\n/// One row per login.\nmodel Users {"),
+            "{prisma}"
+        );
+        assert!(
+            prisma.contains("  /// Primary contact address\n  email String?\n"),
+            "{prisma}"
+        );
+    }
+
+    #[test]
+    fn prisma_multi_schema_emits_schema_attributes() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            schema "app" {}
+            enum "status" {
+              schema = "app"
+              values = ["active", "disabled"]
+            }
+            table "accounts" {
+              schema = "app"
+              column "id" { type = "int" }
+            }
+            table "audit_log" {
+              column "id" { type = "int" }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let prisma = crate::generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(
+            prisma.contains("model Accounts {\n  id Int? @db.Integer\n  @@schema(\"app\")\n}"),
+            "{prisma}"
+        );
+        assert!(prisma.contains("  @@schema(\"public\")\n"), "{prisma}");
+        assert!(
+            prisma.contains("  active\n  disabled\n  @@schema(\"app\")\n"),
+            "{prisma}"
+        );
+        assert!(
+            prisma.contains("datasource db {\n  provider = \"postgresql\"\n  url = env(\"DATABASE_URL\")\n  schemas = [\"app\", \"public\"]\n}"),
+            "{prisma}"
+        );
+
+        // Single-schema output is unchanged.
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            table "audit_log" {
+              column "id" { type = "int" }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let prisma = crate::generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(!prisma.contains("@@schema"), "{prisma}");
+        assert!(!prisma.contains("schemas ="), "{prisma}");
+    }
+
+    #[test]
+    fn prisma_keeps_check_constraints_as_doc_comments() {
+        let mut files = HashMap::new();
+        files.insert(
+            p("/root/main.hcl"),
+            r#"
+            table "products" {
+              comment = "Sellable items"
+              column "price" { type = "numeric" }
+              check "price_positive" {
+                expression = "price > 0"
+              }
+            }
+            "#
+            .to_string(),
+        );
+        let loader = MapLoader { files };
+        let cfg = load_config(&p("/root/main.hcl"), &loader, EnvVars::default()).unwrap();
+        let prisma = crate::generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(
+            prisma.contains("/// Sellable items\n/// CHECK: price > 0\nmodel Products {"),
+            "{prisma}"
+        );
+    }
+
     #[test]
     fn prisma_back_reference_relations_have_names() {
         let mut files = HashMap::new();
@@ -936,6 +2973,7 @@ mod tests {
                 security: None,
                 cost: None,
                 body: String::new(),
+                lint_ignore: vec![],
                 comment: None,
             }],
             tables: vec![TableSpec {
@@ -947,9 +2985,13 @@ mod tests {
                 primary_key: None,
                 indexes: vec![],
                 checks: vec![],
+                exclusions: vec![],
                 foreign_keys: vec![],
                 partition_by: None,
                 partitions: vec![],
+                storage_parameters: Default::default(),
+                row_level_security: false,
+                tablespace: None,
                 back_references: vec![],
                 lint_ignore: vec![],
                 comment: None,
@@ -983,6 +3025,7 @@ mod tests {
                 security: None,
                 cost: None,
                 body: String::new(),
+                lint_ignore: vec![],
                 comment: None,
             }],
             tables: vec![TableSpec {
@@ -994,9 +3037,13 @@ mod tests {
                 primary_key: None,
                 indexes: vec![],
                 checks: vec![],
+                exclusions: vec![],
                 foreign_keys: vec![],
                 partition_by: None,
                 partitions: vec![],
+                storage_parameters: Default::default(),
+                row_level_security: false,
+                tablespace: None,
                 back_references: vec![],
                 lint_ignore: vec![],
                 comment: None,
@@ -1032,6 +3079,11 @@ mod tests {
                 orders: vec![],
                 operator_classes: vec![],
                 unique: false,
+                nulls_not_distinct: false,
+                concurrently: false,
+                method: None,
+                storage_parameters: Default::default(),
+                tablespace: None,
             }],
             statistics: vec![StatisticsSpec {
                 name: "stats".into(),
@@ -1070,6 +3122,8 @@ mod tests {
                     nullable: true,
                     default: None,
                     db_type: None,
+                    collation: None,
+                    updated_at: false,
                     lint_ignore: vec![],
                     comment: None,
                     count: 0,
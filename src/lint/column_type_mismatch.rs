@@ -90,6 +90,8 @@ mod tests {
                 nullable: false,
                 default: None,
                 db_type: None,
+                collation: None,
+                updated_at: false,
                 lint_ignore: vec![],
                 comment: None,
                 count: 1,
@@ -97,9 +99,13 @@ mod tests {
             primary_key: None,
             indexes: vec![],
             checks: vec![],
+            exclusions: vec![],
             foreign_keys: vec![],
             partition_by: None,
             partitions: vec![],
+            storage_parameters: Default::default(),
+            row_level_security: false,
+            tablespace: None,
             back_references: vec![],
             lint_ignore: vec![],
             comment: None,
@@ -116,6 +122,8 @@ mod tests {
                 nullable: false,
                 default: None,
                 db_type: None,
+                collation: None,
+                updated_at: false,
                 lint_ignore: vec![],
                 comment: None,
                 count: 1,
@@ -123,18 +131,25 @@ mod tests {
             primary_key: None,
             indexes: vec![],
             checks: vec![],
+            exclusions: vec![],
             foreign_keys: vec![ForeignKeySpec {
                 name: None,
                 columns: vec!["ref_id".into()],
                 ref_schema: None,
                 ref_table: "ref".into(),
                 ref_columns: vec!["id".into()],
+                r#match: None,
                 on_delete: None,
                 on_update: None,
+                deferrable: false,
+                initially_deferred: false,
                 back_reference_name: None,
             }],
             partition_by: None,
             partitions: vec![],
+            storage_parameters: Default::default(),
+            row_level_security: false,
+            tablespace: None,
             back_references: vec![],
             lint_ignore: vec![],
             comment: None,
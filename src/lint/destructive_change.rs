@@ -62,6 +62,8 @@ mod tests {
                 nullable: false,
                 default: None,
                 db_type: None,
+                collation: None,
+                updated_at: false,
                 lint_ignore: vec![],
                 comment: None,
                 count: 1,
@@ -72,18 +74,25 @@ mod tests {
             }),
             indexes: vec![],
             checks: vec![],
+            exclusions: vec![],
             foreign_keys: vec![ForeignKeySpec {
                 name: None,
                 columns: vec!["id".into()],
                 ref_schema: None,
                 ref_table: "other".into(),
                 ref_columns: vec!["id".into()],
+                r#match: None,
                 on_delete: Some("cascade".into()),
                 on_update: None,
+                deferrable: false,
+                initially_deferred: false,
                 back_reference_name: None,
             }],
             partition_by: None,
             partitions: vec![],
+            storage_parameters: Default::default(),
+            row_level_security: false,
+            tablespace: None,
             back_references: vec![],
             lint_ignore: vec![],
             comment: None,
@@ -98,6 +98,8 @@ mod tests {
                 nullable: false,
                 default: None,
                 db_type: None,
+                collation: None,
+                updated_at: false,
                 lint_ignore: vec![],
                 comment: None,
                 count: 1,
@@ -108,9 +110,13 @@ mod tests {
             }),
             indexes: vec![],
             checks: vec![],
+            exclusions: vec![],
             foreign_keys: vec![],
             partition_by: None,
             partitions: vec![],
+            storage_parameters: Default::default(),
+            row_level_security: false,
+            tablespace: None,
             back_references: vec![],
             lint_ignore: vec![],
             comment: None,
@@ -0,0 +1,107 @@
+use super::{LintCheck, LintMessage, LintSeverity, strip_literals};
+use crate::ir::Config;
+use regex::Regex;
+
+/// Warns about PL/pgSQL trigger functions without a `RETURN` statement,
+/// which fail at runtime with "control reached end of trigger procedure
+/// without RETURN". The body is only scanned for the keyword, so this is a
+/// warning by default.
+pub struct MissingTriggerReturn;
+
+impl MissingTriggerReturn {
+    fn ignored(ignores: &[String], rule: &str) -> bool {
+        ignores.iter().any(|i| i == rule)
+    }
+}
+
+impl LintCheck for MissingTriggerReturn {
+    fn name(&self) -> &'static str {
+        "missing-trigger-return"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warn
+    }
+
+    fn run(&self, cfg: &Config) -> Vec<LintMessage> {
+        let re = Regex::new(r"(?i)\breturn\b").unwrap();
+        let mut msgs = Vec::new();
+        for f in &cfg.functions {
+            if Self::ignored(&f.lint_ignore, self.name())
+                || !f.language.eq_ignore_ascii_case("plpgsql")
+                || !f.returns.trim().eq_ignore_ascii_case("trigger")
+            {
+                continue;
+            }
+            if !re.is_match(&strip_literals(&f.body)) {
+                msgs.push(LintMessage {
+                    check: self.name(),
+                    message: format!(
+                        "trigger function '{}' has no RETURN statement (add RETURN NEW, OLD or NULL)",
+                        f.name
+                    ),
+                    severity: LintSeverity::Warn,
+                });
+            }
+        }
+        msgs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::{LintSettings, run_with_checks};
+    use crate::test_support::load_hcl;
+
+    fn lint(hcl: &str) -> Vec<(String, LintSeverity)> {
+        let cfg = load_hcl(hcl);
+        run_with_checks(
+            &cfg,
+            vec![Box::new(MissingTriggerReturn)],
+            &LintSettings::default(),
+        )
+        .into_iter()
+        .map(|m| (m.message, m.severity))
+        .collect()
+    }
+
+    #[test]
+    fn warns_when_trigger_body_has_no_return() {
+        let msgs = lint(
+            r#"
+            function "set_updated_at" {
+              returns = "trigger"
+              body    = "BEGIN NEW.updated_at = now(); -- RETURN NEW;\nEND;"
+            }
+            "#,
+        );
+        assert_eq!(
+            msgs,
+            [(
+                "trigger function 'set_updated_at' has no RETURN statement (add RETURN NEW, OLD or NULL)"
+                    .to_string(),
+                LintSeverity::Warn
+            )]
+        );
+    }
+
+    #[test]
+    fn accepts_trigger_body_with_return() {
+        let msgs = lint(
+            r#"
+            function "set_updated_at" {
+              returns = "trigger"
+              body    = "BEGIN NEW.updated_at = now(); RETURN NEW; END;"
+            }
+
+            function "skip_me" {
+              returns     = "trigger"
+              body        = "BEGIN NEW.updated_at = now(); END;"
+              lint_ignore = ["missing-trigger-return"]
+            }
+            "#,
+        );
+        assert!(msgs.is_empty(), "{msgs:?}");
+    }
+}
@@ -6,14 +6,20 @@ mod column_type_mismatch;
 mod destructive_change;
 mod long_identifier;
 mod missing_foreign_key_index;
+mod missing_trigger_return;
+mod row_level_security;
 mod sql_syntax;
+mod trigger_variables;
 mod unused_index;
 
 use column_type_mismatch::ColumnTypeMismatch;
 use destructive_change::DestructiveChange;
 use long_identifier::LongIdentifier;
 use missing_foreign_key_index::MissingForeignKeyIndex;
+use missing_trigger_return::MissingTriggerReturn;
+use row_level_security::{PolicyWithoutRls, RlsWithoutPolicy};
 use sql_syntax::SqlSyntax;
+use trigger_variables::TriggerVariables;
 use unused_index::UnusedIndex;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,6 +46,11 @@ pub struct LintSettings {
 pub trait LintCheck {
     fn name(&self) -> &'static str;
     fn run(&self, cfg: &Config) -> Vec<LintMessage>;
+    /// Severity used when the settings do not configure one. Heuristic
+    /// checks default to a warning.
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Error
+    }
 }
 
 pub fn run(cfg: &Config, settings: &LintSettings) -> Vec<LintMessage> {
@@ -54,6 +65,10 @@ pub fn run(cfg: &Config, settings: &LintSettings) -> Vec<LintMessage> {
         Box::new(UnusedIndex),
         Box::new(LongIdentifier),
         Box::new(SqlSyntax),
+        Box::new(TriggerVariables),
+        Box::new(MissingTriggerReturn),
+        Box::new(RlsWithoutPolicy),
+        Box::new(PolicyWithoutRls),
     ];
     run_with_checks(cfg, checks, settings)
 }
@@ -69,7 +84,7 @@ pub fn run_with_checks(
             .severity
             .get(check.name())
             .copied()
-            .unwrap_or(LintSeverity::Error);
+            .unwrap_or_else(|| check.default_severity());
         if severity == LintSeverity::Allow {
             continue;
         }
@@ -81,6 +96,46 @@ pub fn run_with_checks(
     messages
 }
 
+/// `sql` with string literal contents and comments blanked out, so words in
+/// them do not count as references.
+fn strip_literals(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                }
+                out.push_str("''");
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                out.push('\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 struct NamingConvention;
 
 impl NamingConvention {
@@ -238,6 +293,8 @@ mod tests {
                 nullable: false,
                 default: None,
                 db_type: None,
+                collation: None,
+                updated_at: false,
                 lint_ignore: vec![],
                 comment: None,
                 count: 1,
@@ -248,9 +305,13 @@ mod tests {
             }),
             indexes: vec![],
             checks: vec![],
+            exclusions: vec![],
             foreign_keys: vec![],
             partition_by: None,
             partitions: vec![],
+            storage_parameters: Default::default(),
+            row_level_security: false,
+            tablespace: None,
             back_references: vec![],
             lint_ignore: vec![],
             comment: None,
@@ -0,0 +1,172 @@
+use super::{LintCheck, LintMessage, LintSeverity};
+use crate::ir::{Config, PolicySpec, TableSpec};
+
+/// Warns about tables with `row_level_security` enabled but no `policy`:
+/// every non-owner query then sees no rows, which is rarely intended.
+pub struct RlsWithoutPolicy;
+
+/// Warns about policies on tables without `row_level_security`: PostgreSQL
+/// stores the policy but never enforces it.
+pub struct PolicyWithoutRls;
+
+fn ignored(ignores: &[String], rule: &str) -> bool {
+    ignores.iter().any(|i| i == rule)
+}
+
+fn applies_to(policy: &PolicySpec, table: &TableSpec) -> bool {
+    let name = table.alt_name.as_deref().unwrap_or(&table.name);
+    (policy.table == name || policy.table == table.name)
+        && policy.schema.as_deref().unwrap_or("public")
+            == table.schema.as_deref().unwrap_or("public")
+}
+
+impl LintCheck for RlsWithoutPolicy {
+    fn name(&self) -> &'static str {
+        "rls-without-policy"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warn
+    }
+
+    fn run(&self, cfg: &Config) -> Vec<LintMessage> {
+        cfg.tables
+            .iter()
+            .filter(|t| t.row_level_security && !ignored(&t.lint_ignore, self.name()))
+            .filter(|t| !cfg.policies.iter().any(|p| applies_to(p, t)))
+            .map(|t| LintMessage {
+                check: self.name(),
+                message: format!(
+                    "table '{}' enables row level security but has no policy, so all rows are hidden",
+                    t.name
+                ),
+                severity: LintSeverity::Warn,
+            })
+            .collect()
+    }
+}
+
+impl LintCheck for PolicyWithoutRls {
+    fn name(&self) -> &'static str {
+        "policy-without-rls"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warn
+    }
+
+    fn run(&self, cfg: &Config) -> Vec<LintMessage> {
+        let mut msgs = Vec::new();
+        for p in &cfg.policies {
+            // Policies on tables declared elsewhere cannot be checked.
+            let Some(table) = cfg.tables.iter().find(|t| applies_to(p, t)) else {
+                continue;
+            };
+            if table.row_level_security || ignored(&table.lint_ignore, self.name()) {
+                continue;
+            }
+            msgs.push(LintMessage {
+                check: self.name(),
+                message: format!(
+                    "policy '{}' on table '{}' is not enforced: set row_level_security = true",
+                    p.name, table.name
+                ),
+                severity: LintSeverity::Warn,
+            });
+        }
+        msgs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::{LintSettings, run_with_checks};
+    use crate::test_support::load_hcl;
+
+    fn lint(hcl: &str) -> Vec<(&'static str, String)> {
+        let cfg = load_hcl(hcl);
+        run_with_checks(
+            &cfg,
+            vec![Box::new(RlsWithoutPolicy), Box::new(PolicyWithoutRls)],
+            &LintSettings::default(),
+        )
+        .into_iter()
+        .map(|m| {
+            assert_eq!(m.severity, LintSeverity::Warn);
+            (m.check, m.message)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn flags_rls_without_policy_and_policy_without_rls() {
+        let msgs = lint(
+            r#"
+            table "locked" {
+              row_level_security = true
+              column "id" { type = "int" }
+            }
+
+            table "open" {
+              column "owner" { type = "text" }
+            }
+
+            table "docs" {
+              table_name         = "documents"
+              row_level_security = true
+              column "owner" { type = "text" }
+            }
+
+            policy "open_select" {
+              table = "open"
+              using = "owner = current_user"
+            }
+
+            policy "docs_select" {
+              table = "documents"
+              using = "owner = current_user"
+            }
+            "#,
+        );
+        assert_eq!(
+            msgs,
+            [
+                (
+                    "rls-without-policy",
+                    "table 'locked' enables row level security but has no policy, so all rows are hidden"
+                        .to_string()
+                ),
+                (
+                    "policy-without-rls",
+                    "policy 'open_select' on table 'open' is not enforced: set row_level_security = true"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn respects_lint_ignore() {
+        let msgs = lint(
+            r#"
+            table "locked" {
+              row_level_security = true
+              lint_ignore        = ["rls-without-policy"]
+              column "id" { type = "int" }
+            }
+
+            table "open" {
+              lint_ignore = ["policy-without-rls"]
+              column "owner" { type = "text" }
+            }
+
+            policy "open_select" {
+              table = "open"
+              using = "owner = current_user"
+            }
+            "#,
+        );
+        assert!(msgs.is_empty(), "{msgs:?}");
+    }
+}
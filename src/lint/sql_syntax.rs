@@ -130,7 +130,9 @@ mod tests {
             alt_name: None,
             schema: None,
             replace: false,
+            columns: vec![],
             sql: "SELEC 1".into(),
+            check_option: None,
             comment: None,
         };
         let cfg = Config {
@@ -0,0 +1,129 @@
+use super::{LintCheck, LintMessage, LintSeverity, strip_literals};
+use crate::ir::{Config, FunctionSpec};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// `NEW`/`OLD` reads (`NEW.col`, `RETURN NEW`) and `TG_*` variables.
+static TRIGGER_VARIABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(new|old)\s*\.|\breturn\s+(new|old)\b|\btg_[a-z_]+\b").unwrap()
+});
+
+/// Warns about functions that use the trigger variables `NEW`, `OLD` and
+/// `TG_*` without returning `trigger`/`event_trigger`, which usually means
+/// the function is wired up wrong. The body is only scanned for the names,
+/// so this is a warning by default.
+pub struct TriggerVariables;
+
+impl TriggerVariables {
+    fn ignored(ignores: &[String], rule: &str) -> bool {
+        ignores.iter().any(|i| i == rule)
+    }
+
+    /// Whether `body` reads `NEW`/`OLD` (`NEW.col`, `RETURN NEW`) or a `TG_*`
+    /// variable outside string literals and comments.
+    fn uses_trigger_variables(body: &str) -> bool {
+        TRIGGER_VARIABLE.is_match(&strip_literals(body))
+    }
+}
+
+impl LintCheck for TriggerVariables {
+    fn name(&self) -> &'static str {
+        "trigger-variables"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warn
+    }
+
+    fn run(&self, cfg: &Config) -> Vec<LintMessage> {
+        let mut msgs = Vec::new();
+        for f in &cfg.functions {
+            if Self::ignored(&f.lint_ignore, self.name())
+                || !f.language.eq_ignore_ascii_case("plpgsql")
+            {
+                continue;
+            }
+            if is_trigger_return(f) || !Self::uses_trigger_variables(&f.body) {
+                continue;
+            }
+            msgs.push(LintMessage {
+                check: self.name(),
+                message: format!(
+                    "function '{}' references NEW, OLD or TG_* but returns '{}' instead of trigger",
+                    f.name, f.returns
+                ),
+                severity: LintSeverity::Warn,
+            });
+        }
+        msgs
+    }
+}
+
+fn is_trigger_return(f: &FunctionSpec) -> bool {
+    let returns = f.returns.trim().to_ascii_lowercase();
+    returns == "trigger" || returns == "event_trigger"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::{LintSettings, run_with_checks};
+    use crate::test_support::load_hcl;
+
+    fn lint(hcl: &str) -> Vec<String> {
+        let cfg = load_hcl(hcl);
+        run_with_checks(
+            &cfg,
+            vec![Box::new(TriggerVariables)],
+            &LintSettings::default(),
+        )
+        .into_iter()
+        .map(|m| m.message)
+        .collect()
+    }
+
+    #[test]
+    fn flags_misused_trigger_helper() {
+        let msgs = lint(
+            r#"
+            function "touch" {
+              returns = "void"
+              body    = "BEGIN NEW.updated_at = now(); END;"
+            }
+
+            function "audit" {
+              returns = "trigger"
+              body    = "BEGIN RAISE NOTICE 'NEW row'; RETURN NULL; END;"
+            }
+
+            function "set_updated_at" {
+              returns = "trigger"
+              body    = "BEGIN NEW.updated_at = now(); RETURN NEW; END;"
+            }
+
+            function "log_ddl" {
+              returns = "event_trigger"
+              body    = "BEGIN RAISE NOTICE '%', TG_TAG; END;"
+            }
+            "#,
+        );
+        assert_eq!(
+            msgs,
+            ["function 'touch' references NEW, OLD or TG_* but returns 'void' instead of trigger"]
+        );
+    }
+
+    #[test]
+    fn respects_lint_ignore() {
+        let msgs = lint(
+            r#"
+            function "touch" {
+              returns     = "void"
+              body        = "BEGIN PERFORM TG_OP; END;"
+              lint_ignore = ["trigger-variables"]
+            }
+            "#,
+        );
+        assert!(msgs.is_empty(), "{msgs:?}");
+    }
+}
@@ -87,6 +87,8 @@ mod tests {
                 nullable: false,
                 default: None,
                 db_type: None,
+                collation: None,
+                updated_at: false,
                 lint_ignore: vec![],
                 comment: None,
                 count: 1,
@@ -103,11 +105,20 @@ mod tests {
                 orders: vec![],
                 operator_classes: vec![],
                 unique: true,
+                nulls_not_distinct: false,
+                concurrently: false,
+                method: None,
+                storage_parameters: Default::default(),
+                tablespace: None,
             }],
             checks: vec![],
+            exclusions: vec![],
             foreign_keys: vec![],
             partition_by: None,
             partitions: vec![],
+            storage_parameters: Default::default(),
+            row_level_security: false,
+            tablespace: None,
             back_references: vec![],
             lint_ignore: vec![],
             comment: None,
@@ -1,16 +1,28 @@
-use anyhow::{anyhow, Context, Result};
-use clap::{Parser, Subcommand};
-use dbschema::frontend::env::EnvVars;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use dbschema::cache::{CachedValidation, TrackingLoader, ValidationCache};
+use dbschema::frontend::data_sources::{content_hash, is_content_hash};
+use dbschema::frontend::env::{
+    DEFAULT_MAX_EXPR_DEPTH, DEFAULT_MAX_MODULE_DEPTH, DEFAULT_MAX_RESOURCES, EnvVars, Limits,
+};
+use dbschema::profile;
+use dbschema::provider::postgres::apply::{ApplyOptions, apply_migration};
+use dbschema::provider::postgres::connect::{ConnectOptions, connect};
+use dbschema::provider::postgres::test_backend::PostgresTestBackend;
+use dbschema::provider::postgres::verify::verify as verify_database;
+use dbschema::provider::postgres::version::PgVersion;
+use dbschema::test_runner::TestBackend;
 use dbschema::{
     apply_filters,
     config::{self, Config as DbschemaConfig, ResourceKind, TargetConfig},
-    load_config, validate, Loader, OutputSpec,
+    load_config, validate, Loader,
 };
-use log::{error, info};
-use postgres::{Client, NoTls};
+use log::{error, info, warn};
+use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use walkdir::WalkDir;
 
 #[derive(Parser)]
@@ -33,10 +45,14 @@ struct Cli {
     #[arg(long)]
     var_file: Vec<PathBuf>,
 
-    /// Backend to use: postgres|prisma|json (ignored if using config file)
+    /// Backend to use: postgres|mysql|prisma|json|json-compact|yaml|mermaid|markdown (ignored if using config file)
     #[arg(long, default_value = "postgres")]
     backend: String,
 
+    /// PostgreSQL major version to generate SQL for: 12|14|15|16 (default: 16, ignored if using config file)
+    #[arg(long)]
+    target_version: Option<PgVersion>,
+
     /// Include only these resources (repeatable). If none, includes all.
     #[arg(long = "include", value_enum)]
     include_resources: Vec<ResourceKind>,
@@ -57,14 +73,68 @@ struct Cli {
     #[arg(long)]
     strict: bool,
 
+    /// Show values derived from `sensitive` variables instead of redacting them
+    #[arg(long)]
+    show_sensitive: bool,
+
+    /// Print elapsed time per phase (parse, evaluate, validate, lint, generate, apply) to stderr
+    #[arg(long)]
+    profile: bool,
+
+    /// Maximum nesting depth of an expression or bracketed HCL source
+    #[arg(long, default_value_t = DEFAULT_MAX_EXPR_DEPTH)]
+    max_expr_depth: usize,
+
+    /// Maximum number of resources after expanding for_each, count and modules
+    #[arg(long, default_value_t = DEFAULT_MAX_RESOURCES)]
+    max_resources: usize,
+
+    /// Maximum depth of nested module blocks
+    #[arg(long, default_value_t = DEFAULT_MAX_MODULE_DEPTH)]
+    max_module_depth: usize,
+
+    /// Directory for cached validation results and pinned http responses
+    /// (default: .dbschema/cache next to the root HCL file, or next to
+    /// dbschema.toml with --config)
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+impl Cli {
+    fn cache_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.cache_dir {
+            return dir.clone();
+        }
+        let root = if self.config {
+            Path::new("")
+        } else {
+            self.input.parent().unwrap_or(Path::new(""))
+        };
+        root.join(".dbschema/cache")
+    }
+
+    fn limits(&self) -> Limits {
+        Limits {
+            max_expr_depth: self.max_expr_depth,
+            max_resources: self.max_resources,
+            max_module_depth: self.max_module_depth,
+        }
+    }
+}
+
+// Parsed once per process; boxing the `Test` variant's flags would buy nothing.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Validate HCL and print a summary
-    Validate {},
+    Validate {
+        /// Always re-validate instead of reusing the result cached in .dbschema/cache
+        #[arg(long)]
+        no_cache: bool,
+    },
     /// Lint schema and report potential issues
     Lint {
         /// Lint rules to allow (suppress)
@@ -91,18 +161,47 @@ enum Commands {
         /// Optional migration name (used in filename when writing to a dir)
         #[arg(long)]
         name: Option<String>,
+        /// Also generate a down migration that drops every resource (requires --out-dir)
+        #[arg(long, requires = "out_dir")]
+        down: bool,
+        /// Wrap the migration in BEGIN/COMMIT; statements that cannot run in a
+        /// transaction (CREATE INDEX CONCURRENTLY, ALTER TYPE ADD VALUE) follow the COMMIT
+        #[arg(long)]
+        wrap_transaction: bool,
+    },
+    /// Evaluate one expression in the scope of the root file's outputs and print the result
+    #[command(hide = true)]
+    Eval {
+        /// Expression to evaluate, e.g. data.prisma_schema.app.models.User.fields.id.type.name
+        #[arg(long)]
+        expr: String,
+    },
+    /// Check that a database matches the HCL and report any drift
+    Verify {
+        #[command(flatten)]
+        db: DbArgs,
+        #[command(flatten)]
+        connect: ConnectArgs,
     },
     /// Run tests defined in HCL against a database
     Test {
-        /// Database connection string (falls back to env DATABASE_URL)
-        #[arg(long)]
-        dsn: Option<String>,
+        #[command(flatten)]
+        db: DbArgs,
         /// Test backend: postgres
         #[arg(long, default_value = "postgres")]
         backend: String,
         /// Names of tests to run (repeatable). If omitted, runs all.
         #[arg(long = "name")]
         names: Vec<String>,
+        /// Only run tests with this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Skip tests with this tag (repeatable)
+        #[arg(long = "exclude-tag")]
+        exclude_tags: Vec<String>,
+        /// Only run tests whose name matches this regular expression
+        #[arg(long)]
+        name_regex: Option<String>,
         /// Generate and apply migrations before running tests (postgres only)
         #[arg(long)]
         apply: bool,
@@ -115,13 +214,96 @@ enum Commands {
         /// Verbose: print SQL being executed (apply + test phases)
         #[arg(long)]
         verbose: bool,
+        /// Apply the migration in one transaction with a savepoint per resource (default)
+        #[arg(long, overrides_with = "no_transactional")]
+        transactional: bool,
+        /// Apply and commit each resource separately (e.g. for CREATE INDEX CONCURRENTLY)
+        #[arg(long, overrides_with = "transactional")]
+        no_transactional: bool,
+        /// Don't take the advisory lock that serializes concurrent applies to the same database
+        #[arg(long)]
+        no_lock: bool,
+        /// After --apply, check the database matches the HCL and fail on drift
+        #[arg(long)]
+        verify: bool,
+        /// Record the applied migration in `dbschema_migrations` and skip it if already applied
+        #[arg(long)]
+        track: bool,
+        /// Name recorded for the migration when using --track
+        #[arg(long, default_value = "schema")]
+        migration_name: String,
+        #[command(flatten)]
+        connect: ConnectArgs,
     },
 }
 
+/// Which database the `test` and `verify` commands connect to.
+#[derive(Args)]
+struct DbArgs {
+    /// Database connection string (falls back to env DATABASE_URL)
+    #[arg(long)]
+    dsn: Option<String>,
+    /// Database host used to assemble a DSN when --dsn is not given
+    #[arg(long)]
+    db_host: Option<String>,
+    /// Database port used to assemble a DSN
+    #[arg(long)]
+    db_port: Option<u16>,
+    /// Database name used to assemble a DSN
+    #[arg(long)]
+    db_name: Option<String>,
+    /// Database user used to assemble a DSN
+    #[arg(long)]
+    db_user: Option<String>,
+    /// Database password used to assemble a DSN (URL-encoded automatically)
+    #[arg(long)]
+    db_password: Option<String>,
+}
+
+/// Schema and database loaded for the `test` and `verify` commands.
+struct DbTarget {
+    config: dbschema::ir::Config,
+    /// DSN from --dsn, the --db-* flags or dbschema.toml; callers fall back to
+    /// DATABASE_URL.
+    dsn: Option<String>,
+    /// `test_backend` set in dbschema.toml.
+    test_backend: Option<String>,
+}
+
+/// How the `test` and `verify` commands open database connections.
+#[derive(Args)]
+struct ConnectArgs {
+    /// Seconds to wait for a single connection attempt
+    #[arg(long, default_value_t = 10)]
+    connect_timeout: u64,
+    /// Number of connection attempts (with exponential backoff) before giving up
+    #[arg(long, default_value_t = 3)]
+    connect_attempts: u32,
+}
+
+impl ConnectArgs {
+    fn options(&self) -> ConnectOptions {
+        ConnectOptions {
+            timeout: Duration::from_secs(self.connect_timeout),
+            attempts: self.connect_attempts,
+            ..ConnectOptions::default()
+        }
+    }
+}
+
 fn main() -> Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    let limits = cli.limits();
+    let cache_dir = cli.cache_dir();
+    let fs_loader = FsLoader {
+        cache_dir: cache_dir.clone(),
+    };
+    let profiling = cli.profile;
+    if profiling {
+        profile::enable();
+    }
 
     if cli.config && cli.command.is_none() {
         let dbschema_config = config::load_config()
@@ -147,61 +329,116 @@ fn main() -> Result<()> {
         };
 
         for target in targets_to_run {
-            run_target(&dbschema_config, &target, cli.strict)?;
+            run_target(
+                &dbschema_config,
+                &target,
+                &fs_loader,
+                cli.strict,
+                cli.show_sensitive,
+                limits,
+            )?;
         }
-    } else if let Some(command) = cli.command {
+    } else if let Some(command) = cli.command.take() {
         match command {
-            Commands::Validate {} => {
-                let mut vars: HashMap<String, hcl::Value> = HashMap::new();
-                for vf in &cli.var_file {
-                    let loaded = load_var_file(vf)
-                        .with_context(|| format!("loading var file {}", vf.display()))?;
-                    vars.extend(loaded);
-                }
-                for (k, v) in cli.var.iter() {
-                    vars.insert(k.clone(), hcl::Value::String(v.clone()));
-                }
+            Commands::Validate { no_cache } => {
+                let vars = load_vars(&cli.var_file, &cli.var)?;
+
+                let cache = ValidationCache::new(&cache_dir);
+                let fingerprint = validate_fingerprint(&cli, &vars);
+                if !no_cache && let Some(cached) = cache.lookup(&fingerprint, &fs_loader) {
+                    info!("{} (unchanged, cached)", cached.summary);
+                    print!("{}", cached.stdout);
+                } else {
+                    let tracking = TrackingLoader::new(&fs_loader);
+                    let env = EnvVars {
+                        vars,
+                        limits,
+                        ..EnvVars::default()
+                    };
+                    let config = load_config(&cli.input, &tracking, env.clone())
+                        .with_context(|| format!("loading root HCL {}", cli.input.display()))?;
+
+                    let (include_set, exclude_set) = cli_filter_sets(
+                        &cli.backend,
+                        &cli.include_resources,
+                        &cli.exclude_resources,
+                    );
+                    let mut filtered = apply_filters(&config, &include_set, &exclude_set);
+                    if cli.show_sensitive {
+                        filtered.sensitive_values.clear();
+                    }
 
-                let fs_loader = FsLoader;
+                    profile::time("validate", || dbschema::validate(&filtered, cli.strict))?;
+                    let summary = format!(
+                        "Valid: {} schema(s), {} enum(s), {} table(s), {} view(s), {} materialized view(s), {} function(s), {} procedure(s), {} trigger(s)",
+                        filtered.schemas.len(),
+                        filtered.enums.len(),
+                        filtered.tables.len(),
+                        filtered.views.len(),
+                        filtered.materialized.len(),
+                        filtered.functions.len(),
+                        filtered.procedures.len(),
+                        filtered.triggers.len()
+                    );
+                    info!("{summary}");
+                    let stdout = format_outputs(&filtered);
+                    print!("{stdout}");
+                    let result = CachedValidation { summary, stdout };
+                    if !no_cache && let Err(e) = cache.store(&fingerprint, &tracking, result) {
+                        log::warn!("could not write validation cache: {e}");
+                    }
+                }
+            }
+            Commands::Eval { expr } => {
                 let env = EnvVars {
-                    vars,
+                    vars: load_vars(&cli.var_file, &cli.var)?,
+                    limits,
                     ..EnvVars::default()
                 };
-                let config = load_config(&cli.input, &fs_loader, env.clone())
-                    .with_context(|| format!("loading root HCL {}", cli.input.display()))?;
+                let (value, mut config) =
+                    dbschema::evaluate_expression(&cli.input, &fs_loader, env, &expr)
+                        .with_context(|| {
+                            format!("evaluating '{expr}' in {}", cli.input.display())
+                        })?;
+                if cli.show_sensitive {
+                    config.sensitive_values.clear();
+                }
+                println!("{}", format_value(&value, &config));
+            }
+            Commands::Verify {
+                db,
+                connect: connect_args,
+            } => {
+                let target = load_db_target(
+                    &fs_loader,
+                    cli.config,
+                    &cli.input,
+                    &cli.var_file,
+                    &cli.var,
+                    db,
+                    limits,
+                )?;
+                let config = target.config;
 
                 let (include_set, exclude_set) =
                     cli_filter_sets(&cli.backend, &cli.include_resources, &cli.exclude_resources);
                 let filtered = apply_filters(&config, &include_set, &exclude_set);
 
-                dbschema::validate(&filtered, cli.strict)?;
-                info!(
-                    "Valid: {} schema(s), {} enum(s), {} table(s), {} view(s), {} materialized view(s), {} function(s), {} procedure(s), {} trigger(s)",
-                    filtered.schemas.len(),
-                    filtered.enums.len(),
-                    filtered.tables.len(),
-                    filtered.views.len(),
-                    filtered.materialized.len(),
-                    filtered.functions.len(),
-                    filtered.procedures.len(),
-                    filtered.triggers.len()
-                );
-                print_outputs(&filtered.outputs);
+                let dsn = target
+                    .dsn
+                    .or_else(|| std::env::var("DATABASE_URL").ok())
+                    .ok_or_else(|| {
+                        anyhow!("missing DSN: pass --dsn, --db-* flags, or set DATABASE_URL")
+                    })?;
+                let mut client = connect(&dsn, &connect_args.options())
+                    .with_context(|| "connecting to database for verification")?;
+                report_drift(&verify_database(&mut client, &filtered)?)?;
+                info!("Database matches the schema");
             }
             Commands::Lint { allow, warn, error } => {
-                let mut vars: HashMap<String, hcl::Value> = HashMap::new();
-                for vf in &cli.var_file {
-                    let loaded = load_var_file(vf)
-                        .with_context(|| format!("loading var file {}", vf.display()))?;
-                    vars.extend(loaded);
-                }
-                for (k, v) in cli.var.iter() {
-                    vars.insert(k.clone(), hcl::Value::String(v.clone()));
-                }
-
-                let fs_loader = FsLoader;
                 let env = EnvVars {
-                    vars,
+                    vars: load_vars(&cli.var_file, &cli.var)?,
+                    limits,
                     ..EnvVars::default()
                 };
                 let config = load_config(&cli.input, &fs_loader, env.clone())
@@ -229,7 +466,8 @@ fn main() -> Result<()> {
                         .severity
                         .insert(rule, dbschema::lint::LintSeverity::Error);
                 }
-                let lints = dbschema::lint::run(&filtered, &lint_settings);
+                let lints =
+                    profile::time("lint", || dbschema::lint::run(&filtered, &lint_settings));
                 if lints.is_empty() {
                     info!("No lint issues found");
                 } else {
@@ -250,20 +488,15 @@ fn main() -> Result<()> {
                     format_path(&p)?;
                 }
             }
-            Commands::CreateMigration { out_dir, name } => {
-                let mut vars: HashMap<String, hcl::Value> = HashMap::new();
-                for vf in &cli.var_file {
-                    let loaded = load_var_file(vf)
-                        .with_context(|| format!("loading var file {}", vf.display()))?;
-                    vars.extend(loaded);
-                }
-                for (k, v) in cli.var.iter() {
-                    vars.insert(k.clone(), hcl::Value::String(v.clone()));
-                }
-
-                let fs_loader = FsLoader;
+            Commands::CreateMigration {
+                out_dir,
+                name,
+                down,
+                wrap_transaction,
+            } => {
                 let env = EnvVars {
-                    vars,
+                    vars: load_vars(&cli.var_file, &cli.var)?,
+                    limits,
                     ..EnvVars::default()
                 };
                 let config = load_config(&cli.input, &fs_loader, env.clone())
@@ -271,92 +504,99 @@ fn main() -> Result<()> {
 
                 let (include_set, exclude_set) =
                     cli_filter_sets(&cli.backend, &cli.include_resources, &cli.exclude_resources);
-                let filtered = apply_filters(&config, &include_set, &exclude_set);
+                let mut filtered = apply_filters(&config, &include_set, &exclude_set);
+                if cli.show_sensitive {
+                    filtered.sensitive_values.clear();
+                }
 
-                dbschema::validate(&filtered, cli.strict)?;
-                let artifact =
-                    dbschema::generate_with_backend(&cli.backend, &filtered, cli.strict)?;
+                profile::time("validate", || dbschema::validate(&filtered, cli.strict))?;
+                let backend = dbschema::backends::get_backend_for_version(
+                    &cli.backend,
+                    cli.target_version.unwrap_or_default(),
+                )
+                .ok_or_else(|| anyhow!("unknown backend '{}'", cli.backend))?;
+                let artifact = profile::time("generate", || {
+                    if wrap_transaction {
+                        backend.generate_in_transaction(&filtered, cli.strict)
+                    } else {
+                        backend.generate(&filtered, cli.strict)
+                    }
+                })?;
+                let down_artifact = if down {
+                    Some(profile::time("generate", || {
+                        backend.generate_down(&filtered, cli.strict)
+                    })?)
+                } else {
+                    None
+                };
                 if let Some(dir) = out_dir {
                     let name = name.unwrap_or_else(|| "triggers".to_string());
-                    let ext = dbschema::backends::get_backend(&cli.backend)
-                        .as_ref()
-                        .map(|b| b.file_extension())
-                        .unwrap_or("txt");
+                    let ext = backend.file_extension();
                     let path = write_artifact(&dir, &name, ext, &artifact)?;
                     info!("Wrote migration: {}", path.display());
+                    if let Some(down_artifact) = down_artifact {
+                        let path =
+                            write_artifact(&dir, &name, &format!("down.{ext}"), &down_artifact)?;
+                        info!("Wrote down migration: {}", path.display());
+                    }
+                } else if backend.is_binary() {
+                    return Err(anyhow!(
+                        "backend '{}' produces binary output; pass --out-dir to write it to a file",
+                        cli.backend
+                    ));
                 } else {
                     print!("{}", artifact);
                 }
-                print_outputs(&filtered.outputs);
+                print_outputs(&filtered);
             }
             Commands::Test {
-                dsn,
+                db,
                 names,
+                tags,
+                exclude_tags,
+                name_regex,
                 backend,
                 apply,
                 create_db,
                 keep_db,
                 verbose,
+                transactional: _,
+                no_transactional,
+                no_lock,
+                verify,
+                track,
+                migration_name,
+                connect: connect_args,
             } => {
+                let connect_options = connect_args.options();
+                let target = load_db_target(
+                    &fs_loader,
+                    cli.config,
+                    &cli.input,
+                    &cli.var_file,
+                    &cli.var,
+                    db,
+                    limits,
+                )?;
+                let (dsn, mut config) = (target.dsn, target.config);
                 let mut backend = backend;
-                let (dsn, config) = if cli.config {
-                    let dbschema_config = config::load_config()
-                        .with_context(|| "failed to load dbschema.toml")?
-                        .ok_or_else(|| anyhow!("dbschema.toml not found"))?;
-                    for (key, value) in &dbschema_config.settings.env {
-                        unsafe {
-                            std::env::set_var(key, value);
-                        }
-                    }
-                    let mut vars: HashMap<String, hcl::Value> = HashMap::new();
-                    for vf in &dbschema_config.settings.var_files {
-                        vars.extend(load_var_file(&PathBuf::from(vf))?);
-                    }
-                    let input_path = dbschema_config
-                        .settings
-                        .input
-                        .as_deref()
-                        .unwrap_or("main.hcl");
-                    let fs_loader = FsLoader;
-                    let env = EnvVars {
-                        vars,
-                        ..EnvVars::default()
-                    };
-                    let cfg = load_config(&PathBuf::from(input_path), &fs_loader, env.clone())
-                        .with_context(|| format!("loading root HCL from {}", input_path))?;
-                    let dsn = dsn
-                        .or_else(|| dbschema_config.settings.test_dsn.clone())
-                        .or_else(|| std::env::var("DATABASE_URL").ok());
-                    if backend.eq_ignore_ascii_case("postgres") {
-                        if let Some(be) = &dbschema_config.settings.test_backend {
-                            backend = be.clone();
-                        }
-                    }
-                    (dsn, cfg)
-                } else {
-                    let mut vars: HashMap<String, hcl::Value> = HashMap::new();
-                    for vf in &cli.var_file {
-                        let loaded = load_var_file(vf)
-                            .with_context(|| format!("loading var file {}", vf.display()))?;
-                        vars.extend(loaded);
-                    }
-                    for (k, v) in cli.var.iter() {
-                        vars.insert(k.clone(), hcl::Value::String(v.clone()));
-                    }
-                    let fs_loader = FsLoader;
-                    let env = EnvVars {
-                        vars,
-                        ..EnvVars::default()
-                    };
-                    let cfg = load_config(&cli.input, &fs_loader, env.clone())
-                        .with_context(|| format!("loading root HCL {}", cli.input.display()))?;
-                    (dsn, cfg)
-                };
+                if backend.eq_ignore_ascii_case("postgres")
+                    && let Some(be) = target.test_backend
+                {
+                    backend = be;
+                }
+                if cli.show_sensitive {
+                    config.sensitive_values.clear();
+                }
                 let backend_name = backend;
                 let backend_key = backend_name.to_lowercase();
-                let backend_is_postgres = backend_key == "postgres";
+                let backend_is_postgres = matches!(backend_key.as_str(), "postgres" | "pg");
                 let registry = dbschema::test_runner::get_default_test_backend_registry();
-                let runner = match registry.get(&backend_key) {
+                let postgres_runner = PostgresTestBackend {
+                    connect: connect_options,
+                };
+                let runner: &dyn TestBackend = match registry.get(&backend_key) {
+                    Some(_) if backend_is_postgres => &postgres_runner,
                     Some(runner) => runner,
                     None => {
                         let mut available = registry.list_backends();
@@ -375,7 +615,9 @@ fn main() -> Result<()> {
                 };
                 let mut dsn = dsn
                     .or_else(|| std::env::var("DATABASE_URL").ok())
-                    .ok_or_else(|| anyhow!("missing DSN: pass --dsn or set DATABASE_URL"))?;
+                    .ok_or_else(|| {
+                        anyhow!("missing DSN: pass --dsn, --db-* flags, or set DATABASE_URL")
+                    })?;
                 let mut temp_database: Option<(String, String)> = None;
 
                 // Optionally create and later drop a temporary database for Postgres
@@ -393,17 +635,20 @@ fn main() -> Result<()> {
                 // Optionally generate and apply migrations for Postgres
                 if apply {
                     if backend_is_postgres {
-                        dbschema::validate(&config, cli.strict)?;
-                        let artifact =
-                            dbschema::generate_with_backend("postgres", &config, cli.strict)?;
-                        if verbose {
-                            info!("-- applying migration --\n{}", artifact);
-                        }
-                        let mut client = Client::connect(&dsn, NoTls)
+                        profile::time("validate", || dbschema::validate(&config, cli.strict))?;
+                        let mut client = connect(&dsn, &connect_options)
                             .with_context(|| format!("connecting to database: {}", &dsn))?;
-                        client
-                            .batch_execute(&artifact)
+                        let options = ApplyOptions {
+                            transactional: !no_transactional,
+                            lock: !no_lock,
+                            track: track.then(|| migration_name.clone()),
+                            verbose,
+                        };
+                        profile::time("apply", || apply_migration(&mut client, &config, &options))
                             .with_context(|| "applying generated migration to database")?;
+                        if verify {
+                            report_drift(&verify_database(&mut client, &config)?)?;
+                        }
                     } else {
                         return Err(anyhow!(
                             "--apply is only supported for the 'postgres' test backend (requested '{}')",
@@ -413,12 +658,18 @@ fn main() -> Result<()> {
                 }
 
                 dbschema::test_runner::set_verbose(verbose);
-                let only: Option<std::collections::HashSet<String>> = if names.is_empty() {
-                    None
-                } else {
-                    Some(names.into_iter().collect())
+                let name_regex = name_regex
+                    .map(|re| {
+                        Regex::new(&re).with_context(|| format!("invalid --name-regex '{re}'"))
+                    })
+                    .transpose()?;
+                let filter = dbschema::TestFilter {
+                    tags: tags.into_iter().collect(),
+                    exclude_tags: exclude_tags.into_iter().collect(),
+                    name_regex,
+                    ..dbschema::TestFilter::names(names)
                 };
-                let summary = runner.run(&config, &dsn, only.as_ref())?;
+                let summary = dbschema::run_tests(&config, runner, Some(&dsn), filter)?;
                 for r in summary.results {
                     if r.passed {
                         info!("ok - {}", r.name);
@@ -438,7 +689,7 @@ fn main() -> Result<()> {
                         summary.passed, summary.failed, summary.total
                     );
                 }
-                print_outputs(&config.outputs);
+                print_outputs(&config);
 
                 // Optionally drop the created database after tests complete
                 if !keep_db {
@@ -450,6 +701,10 @@ fn main() -> Result<()> {
         }
     }
 
+    if profiling {
+        profile::report(&mut std::io::stderr())?;
+    }
+
     Ok(())
 }
 
@@ -483,7 +738,89 @@ fn format_file(path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn run_target(dbschema_config: &DbschemaConfig, target: &TargetConfig, strict: bool) -> Result<()> {
+/// Load the schema and assemble the DSN for the `test` and `verify` commands.
+/// With `--config` the input file, var files, env and database settings come
+/// from dbschema.toml; otherwise from `--input`, `--var` and `--var-file`.
+/// The `--dsn` and `--db-*` flags take precedence either way.
+fn load_db_target(
+    loader: &FsLoader,
+    use_config: bool,
+    input: &Path,
+    var_files: &[PathBuf],
+    cli_vars: &[(String, String)],
+    db: DbArgs,
+    limits: Limits,
+) -> Result<DbTarget> {
+    let cli_db = config::DbConnection {
+        host: db.db_host,
+        port: db.db_port,
+        name: db.db_name,
+        user: db.db_user,
+        password: db.db_password,
+    };
+    if use_config {
+        let dbschema_config = config::load_config()
+            .with_context(|| "failed to load dbschema.toml")?
+            .ok_or_else(|| anyhow!("dbschema.toml not found"))?;
+        for (key, value) in &dbschema_config.settings.env {
+            unsafe {
+                std::env::set_var(key, value);
+            }
+        }
+        let mut vars: HashMap<String, hcl::Value> = HashMap::new();
+        for vf in &dbschema_config.settings.var_files {
+            vars.extend(load_var_file(&PathBuf::from(vf))?);
+        }
+        let input_path = dbschema_config
+            .settings
+            .input
+            .as_deref()
+            .unwrap_or("main.hcl");
+        let env = EnvVars {
+            vars,
+            limits,
+            ..EnvVars::default()
+        };
+        let config = load_config(&PathBuf::from(input_path), loader, env)
+            .with_context(|| format!("loading root HCL from {}", input_path))?;
+        let merged = dbschema_config.settings.db.clone().merge(cli_db);
+        let dsn = match db.dsn {
+            Some(dsn) => Some(dsn),
+            None => merged.to_dsn()?,
+        };
+        Ok(DbTarget {
+            config,
+            dsn: dsn.or_else(|| dbschema_config.settings.test_dsn.clone()),
+            test_backend: dbschema_config.settings.test_backend.clone(),
+        })
+    } else {
+        let env = EnvVars {
+            vars: load_vars(var_files, cli_vars)?,
+            limits,
+            ..EnvVars::default()
+        };
+        let config = load_config(input, loader, env)
+            .with_context(|| format!("loading root HCL {}", input.display()))?;
+        let dsn = match db.dsn {
+            Some(dsn) => Some(dsn),
+            None => cli_db.to_dsn()?,
+        };
+        Ok(DbTarget {
+            config,
+            dsn,
+            test_backend: None,
+        })
+    }
+}
+
+fn run_target(
+    dbschema_config: &DbschemaConfig,
+    target: &TargetConfig,
+    fs_loader: &FsLoader,
+    strict: bool,
+    show_sensitive: bool,
+    limits: Limits,
+) -> Result<()> {
     info!("Running target: {}", target.name);
 
     for (key, value) in &dbschema_config.settings.env {
@@ -509,21 +846,25 @@ fn run_target(dbschema_config: &DbschemaConfig, target: &TargetConfig, strict: b
         vars.insert(key.clone(), toml_to_hcl(value)?);
     }
 
-    let fs_loader = FsLoader;
     let env = EnvVars {
         vars,
+        limits,
         ..EnvVars::default()
     };
-    let config = load_config(&PathBuf::from(input_path), &fs_loader, env.clone())
+    let config = load_config(&PathBuf::from(input_path), fs_loader, env.clone())
         .with_context(|| format!("loading root HCL from {}", input_path))?;
 
     let include_set = target.get_include_set()?;
     let exclude_set = target.get_exclude_set()?;
 
-    let filtered = apply_filters(&config, &include_set, &exclude_set);
+    let mut filtered = apply_filters(&config, &include_set, &exclude_set);
+    if show_sensitive {
+        filtered.sensitive_values.clear();
+    }
 
-    validate(&filtered, strict)?;
-    let artifact = dbschema::generate_with_backend(&target.backend, &filtered, strict)?;
+    profile::time("validate", || validate(&filtered, strict))?;
+    let backend = target.create_backend()?;
+    let artifact = profile::time("generate", || backend.generate(&filtered, strict))?;
 
     if let Some(output_path) = &target.output {
         let path = Path::new(output_path);
@@ -536,7 +877,7 @@ fn run_target(dbschema_config: &DbschemaConfig, target: &TargetConfig, strict: b
         print!("{}", artifact);
     }
 
-    print_outputs(&filtered.outputs);
+    print_outputs(&filtered);
 
     Ok(())
 }
@@ -567,6 +908,19 @@ fn toml_to_hcl(value: &toml::Value) -> Result<hcl::Value> {
     }
 }
 
+fn report_drift(drift: &[String]) -> Result<()> {
+    if drift.is_empty() {
+        return Ok(());
+    }
+    for d in drift {
+        error!("drift: {d}");
+    }
+    Err(anyhow!(
+        "database does not match the schema ({} difference(s))",
+        drift.len()
+    ))
+}
+
 fn write_artifact(out_dir: &Path, name: &str, ext: &str, contents: &str) -> Result<PathBuf> {
     fs::create_dir_all(out_dir)?;
     let ts = chrono::Local::now().format("%Y%m%d%H%M%S");
@@ -576,15 +930,25 @@ fn write_artifact(out_dir: &Path, name: &str, ext: &str, contents: &str) -> Resu
     Ok(path)
 }
 
-fn print_outputs(outputs: &[OutputSpec]) {
-    for o in outputs {
-        let val = match &o.value {
-            hcl::Value::String(s) => s.clone(),
-            hcl::Value::Number(n) => n.to_string(),
-            hcl::Value::Bool(b) => b.to_string(),
-            _ => serde_json::to_string(&o.value).unwrap_or_default(),
-        };
-        println!("{} = {}", o.name, val);
+fn print_outputs(cfg: &dbschema::Config) {
+    print!("{}", format_outputs(cfg));
+}
+
+fn format_outputs(cfg: &dbschema::Config) -> String {
+    let mut out = String::new();
+    for o in &cfg.outputs {
+        out.push_str(&format!("{} = {}\n", o.name, format_value(&o.value, cfg)));
+    }
+    out
+}
+
+/// Scalars as plain text, anything else as JSON; redacted when sensitive.
+fn format_value(value: &hcl::Value, cfg: &dbschema::Config) -> String {
+    match cfg.redact(value) {
+        hcl::Value::String(s) => s,
+        hcl::Value::Number(n) => n.to_string(),
+        hcl::Value::Bool(b) => b.to_string(),
+        other => serde_json::to_string(&other).unwrap_or_default(),
     }
 }
 
@@ -600,6 +964,42 @@ fn sanitize_filename(s: &str) -> String {
         .collect()
 }
 
+/// Everything besides the files read that decides the outcome of `validate`,
+/// so a cached result is only reused for the same flags and variables.
+fn validate_fingerprint(cli: &Cli, vars: &HashMap<String, hcl::Value>) -> String {
+    let mut sorted_vars: Vec<_> = vars.iter().collect();
+    sorted_vars.sort_by(|a, b| a.0.cmp(b.0));
+    format!(
+        "{} {} strict={} sensitive={} backend={} include={:?} exclude={:?} limits={:?} vars={:?}",
+        env!("CARGO_PKG_VERSION"),
+        cli.input.display(),
+        cli.strict,
+        cli.show_sensitive,
+        cli.backend,
+        cli.include_resources,
+        cli.exclude_resources,
+        cli.limits(),
+        sorted_vars
+    )
+}
+
+/// Variables from `--var-file`s in order, then `--var`s, later ones winning.
+fn load_vars(
+    var_files: &[PathBuf],
+    cli_vars: &[(String, String)],
+) -> Result<HashMap<String, hcl::Value>> {
+    let mut vars = HashMap::new();
+    for vf in var_files {
+        let loaded =
+            load_var_file(vf).with_context(|| format!("loading var file {}", vf.display()))?;
+        vars.extend(loaded);
+    }
+    for (k, v) in cli_vars {
+        vars.insert(k.clone(), hcl::Value::String(v.clone()));
+    }
+    Ok(vars)
+}
+
 fn load_var_file(path: &Path) -> Result<HashMap<String, hcl::Value>> {
     let content = fs::read_to_string(path)?;
     // Try HCL body, collect top-level attributes as strings
@@ -649,11 +1049,61 @@ where
     Ok((key, value))
 }
 
-struct FsLoader;
+/// How long a `data "http"` request waits to connect.
+#[cfg(feature = "http")]
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a `data "http"` request waits for the response headers, and then
+/// for the body.
+#[cfg(feature = "http")]
+const HTTP_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Reads files from disk and fetches `data "http"` sources over the network.
+struct FsLoader {
+    /// Where pinned http responses are cached.
+    cache_dir: PathBuf,
+}
+
 impl Loader for FsLoader {
     fn load(&self, path: &Path) -> Result<String> {
         Ok(fs::read_to_string(path)?)
     }
+
+    #[cfg(feature = "http")]
+    fn fetch(&self, url: &str) -> Result<String> {
+        static AGENT: std::sync::LazyLock<ureq::Agent> = std::sync::LazyLock::new(|| {
+            ureq::Agent::config_builder()
+                .timeout_connect(Some(HTTP_CONNECT_TIMEOUT))
+                .timeout_recv_response(Some(HTTP_READ_TIMEOUT))
+                .timeout_recv_body(Some(HTTP_READ_TIMEOUT))
+                .build()
+                .into()
+        });
+        Ok(AGENT.get(url).call()?.body_mut().read_to_string()?)
+    }
+
+    // Pinned responses are content-addressed, so a cached copy can never be
+    // stale; only content matching the pin is written.
+    fn fetch_pinned(&self, url: &str, hash: &str) -> Result<String> {
+        // The hash becomes a file name, so only accept the exact pin format.
+        if !is_content_hash(hash) {
+            bail!("invalid hash '{hash}': expected 'sha256:' followed by 64 lowercase hex digits");
+        }
+        let cached = self.cache_dir.join(hash.replace(':', "-"));
+        if let Ok(body) = fs::read_to_string(&cached)
+            && content_hash(&body) == hash
+        {
+            return Ok(body);
+        }
+        let body = self.fetch(url)?;
+        if content_hash(&body) == hash {
+            let written =
+                fs::create_dir_all(&self.cache_dir).and_then(|()| fs::write(&cached, &body));
+            if let Err(e) = written {
+                warn!("could not cache {url} in {}: {e}", cached.display());
+            }
+        }
+        Ok(body)
+    }
 }
 
 fn cli_filter_sets(
@@ -675,9 +1125,11 @@ fn cli_filter_sets(
         include_set.remove(r);
     }
 
-    // Prisma backend supports tables and enums only; enforce that regardless of flags unless explicitly excluded
+    // Prisma backend supports tables, enums and views only; enforce that regardless of flags unless explicitly excluded
     if backend.eq_ignore_ascii_case("prisma") {
-        include_set = [R::Enums, R::Tables].into_iter().collect();
+        include_set = [R::Enums, R::Tables, R::Views, R::Materialized]
+            .into_iter()
+            .collect();
         for r in &exclude_set {
             include_set.remove(r);
         }
@@ -693,8 +1145,12 @@ mod tests {
 
     #[test]
     fn test_run_target() -> Result<()> {
+        let limits = Limits::default();
         let original_dir = std::env::current_dir()?;
         let dir = tempdir()?;
+        let loader = FsLoader {
+            cache_dir: dir.path().join(".dbschema/cache"),
+        };
         let dbschema_toml_path = dir.path().join("dbschema.toml");
         let main_hcl_path = dir.path().join("main.hcl");
         let another_hcl_path = dir.path().join("another.hcl");
@@ -777,7 +1233,7 @@ table_name = "from_file"
             .iter()
             .find(|t| t.name == "json_all")
             .unwrap();
-        run_target(&dbschema_config, target_all, false)?;
+        run_target(&dbschema_config, target_all, &loader, false, false, limits)?;
         let output_all = fs::read_to_string("all.json")?;
         assert!(output_all.contains("users"));
         assert!(output_all.contains("my_func"));
@@ -788,7 +1244,14 @@ table_name = "from_file"
             .iter()
             .find(|t| t.name == "json_tables")
             .unwrap();
-        run_target(&dbschema_config, target_tables, false)?;
+        run_target(
+            &dbschema_config,
+            target_tables,
+            &loader,
+            false,
+            false,
+            limits,
+        )?;
         let output_tables = fs::read_to_string("tables.json")?;
         assert!(output_tables.contains("users"));
         assert!(!output_tables.contains("my_func"));
@@ -799,7 +1262,14 @@ table_name = "from_file"
             .iter()
             .find(|t| t.name == "another_input")
             .unwrap();
-        run_target(&dbschema_config, target_another, false)?;
+        run_target(
+            &dbschema_config,
+            target_another,
+            &loader,
+            false,
+            false,
+            limits,
+        )?;
         let output_another = fs::read_to_string("another.json")?;
         assert!(output_another.contains("another_func"));
         assert!(!output_another.contains("my_func"));
@@ -810,7 +1280,7 @@ table_name = "from_file"
             .iter()
             .find(|t| t.name == "with_vars")
             .unwrap();
-        run_target(&dbschema_config, target_vars, false)?;
+        run_target(&dbschema_config, target_vars, &loader, false, false, limits)?;
         let output_vars = fs::read_to_string("with_vars.json")?;
         // The variable from the target should be used
         assert!(output_vars.contains("my_users_table"));
@@ -821,7 +1291,14 @@ table_name = "from_file"
             .iter()
             .find(|t| t.name == "with_alt_name")
             .unwrap();
-        run_target(&dbschema_config, target_alt_name, false)?;
+        run_target(
+            &dbschema_config,
+            target_alt_name,
+            &loader,
+            false,
+            false,
+            limits,
+        )?;
         let output_alt_name = fs::read_to_string("with_alt_name.json")?;
         assert!(output_alt_name.contains("from_file"));
 
@@ -829,4 +1306,40 @@ table_name = "from_file"
         dir.close()?;
         Ok(())
     }
+
+    #[test]
+    fn changing_a_limit_misses_the_validate_cache() {
+        let dir = tempdir().unwrap();
+        let loader = FsLoader {
+            cache_dir: dir.path().join("fetch"),
+        };
+        let cache = ValidationCache::new(dir.path());
+        let vars = HashMap::new();
+        let fingerprint = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["dbschema"], args, &["validate"]].concat()).unwrap();
+            validate_fingerprint(&cli, &vars)
+        };
+        let result = CachedValidation {
+            summary: "Valid".into(),
+            stdout: String::new(),
+        };
+        cache
+            .store(&fingerprint(&[]), &TrackingLoader::new(&loader), result)
+            .unwrap();
+
+        assert!(cache.lookup(&fingerprint(&[]), &loader).is_some());
+        for limit in ["--max-resources", "--max-expr-depth", "--max-module-depth"] {
+            let lowered = fingerprint(&[limit, "1"]);
+            assert!(cache.lookup(&lowered, &loader).is_none(), "{limit}");
+        }
+    }
+
+    #[test]
+    fn down_requires_out_dir() {
+        assert!(Cli::try_parse_from(["dbschema", "create-migration", "--down"]).is_err());
+        assert!(
+            Cli::try_parse_from(["dbschema", "create-migration", "--down", "--out-dir", "m"])
+                .is_ok()
+        );
+    }
 }
@@ -1,6 +1,6 @@
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 
-use crate::ir::{Config, EnumSpec};
+use crate::ir::{CompositeTypeSpec, Config, EnumSpec, TriggerCreate};
 
 pub fn validate(cfg: &Config, strict: bool) -> Result<()> {
     for t in &cfg.triggers {
@@ -23,6 +23,85 @@ pub fn validate(cfg: &Config, strict: bool) -> Result<()> {
         }
     }
 
+    for t in &cfg.triggers {
+        let timing = t.timing.to_uppercase();
+        let row_level = t.level.eq_ignore_ascii_case("ROW");
+        if timing == "INSTEAD OF" {
+            if !row_level {
+                bail!(
+                    "trigger '{}' is INSTEAD OF and must use level = \"ROW\"",
+                    t.name
+                );
+            }
+            if t.when.is_some() {
+                bail!(
+                    "trigger '{}' is INSTEAD OF and cannot have a when condition",
+                    t.name
+                );
+            }
+        }
+        if t.constraint && (timing != "AFTER" || !row_level) {
+            bail!(
+                "constraint trigger '{}' must be AFTER and level = \"ROW\"",
+                t.name
+            );
+        }
+        if t.deferrable && !t.constraint {
+            bail!(
+                "trigger '{}' sets deferrable without constraint = true",
+                t.name
+            );
+        }
+        if t.initially_deferred && !t.deferrable {
+            bail!(
+                "trigger '{}' sets initially_deferred without deferrable = true",
+                t.name
+            );
+        }
+        if t.create == TriggerCreate::CreateOrReplace && t.constraint {
+            bail!("constraint trigger '{}' cannot use replace = true", t.name);
+        }
+        if !t.update_columns.is_empty()
+            && !t.events.iter().any(|e| e.eq_ignore_ascii_case("UPDATE"))
+        {
+            bail!(
+                "trigger '{}' sets update_columns but UPDATE is not among its events",
+                t.name
+            );
+        }
+        if t.referencing_old_table.is_some() || t.referencing_new_table.is_some() {
+            if timing != "AFTER" || t.constraint {
+                bail!(
+                    "trigger '{}' uses transition tables, which require a non-constraint AFTER trigger",
+                    t.name
+                );
+            }
+            if !t.update_columns.is_empty() {
+                bail!(
+                    "trigger '{}' cannot combine transition tables with update_columns",
+                    t.name
+                );
+            }
+            let has_event = |names: &[&str]| {
+                t.events
+                    .iter()
+                    .any(|e| names.iter().any(|n| e.eq_ignore_ascii_case(n)))
+            };
+            if t.referencing_old_table.is_some() && !has_event(&["UPDATE", "DELETE"]) {
+                bail!(
+                    "trigger '{}' sets referencing_old_table but has no UPDATE or DELETE event",
+                    t.name
+                );
+            }
+            if t.referencing_new_table.is_some() && !has_event(&["INSERT", "UPDATE"]) {
+                bail!(
+                    "trigger '{}' sets referencing_new_table but has no INSERT or UPDATE event",
+                    t.name
+                );
+            }
+        }
+    }
+
     for t in &cfg.event_triggers {
         let fqn = format!(
             "{}.{}",
@@ -43,21 +122,343 @@ pub fn validate(cfg: &Config, strict: bool) -> Result<()> {
         }
     }
 
+    for f in &cfg.functions {
+        for param in &f.parameters {
+            if let Some(mode) = &param.mode
+                && !["IN", "OUT", "INOUT", "VARIADIC"].contains(&mode.to_uppercase().as_str())
+            {
+                bail!(
+                    "function '{}' has parameter with invalid mode '{}': expected in, out, inout or variadic",
+                    f.name,
+                    mode
+                );
+            }
+        }
+    }
+
+    for o in &cfg.operators {
+        let symbol = o.alt_name.as_deref().unwrap_or(&o.name);
+        if symbol.is_empty() || !symbol.chars().all(|c| "+-*/<>=~!@#%^&|`?".contains(c)) {
+            bail!(
+                "operator '{}' has invalid name '{}': use operator symbols (+ - * / < > = ~ ! @ # % ^ & | ` ?), setting name = \"...\" if the label is descriptive",
+                o.name,
+                symbol
+            );
+        }
+        if o.left.is_none() && o.right.is_none() {
+            bail!("operator '{}' needs at least one of left or right", o.name);
+        }
+    }
+
+    for r in &cfg.roles {
+        if let Some(key) = r.config.keys().find(|k| !is_setting_name(k)) {
+            bail!(
+                "role '{}' has invalid config key '{}': expected a setting name such as search_path or app.tenant",
+                r.name,
+                key
+            );
+        }
+    }
+
+    for v in &cfg.views {
+        if let Some(opt) = &v.check_option
+            && !matches!(opt.to_uppercase().as_str(), "CASCADED" | "LOCAL")
+        {
+            bail!(
+                "view '{}' has invalid check_option '{}': use CASCADED or LOCAL",
+                v.name,
+                opt
+            );
+        }
+    }
+
+    for g in &cfg.grants {
+        if !g.columns.is_empty() && g.table.is_none() {
+            bail!("grant '{}' lists columns but has no table", g.name);
+        }
+        if let Some(kind) = &g.all_in_schema {
+            if !["TABLES", "SEQUENCES", "FUNCTIONS", "PROCEDURES", "ROUTINES"]
+                .contains(&kind.to_uppercase().as_str())
+            {
+                bail!(
+                    "grant '{}' has invalid all_in_schema '{}': expected TABLES, SEQUENCES, FUNCTIONS, PROCEDURES or ROUTINES",
+                    g.name,
+                    kind
+                );
+            }
+            if g.schema.is_none()
+                || g.table.is_some()
+                || g.function.is_some()
+                || g.sequence.is_some()
+                || g.database.is_some()
+            {
+                bail!(
+                    "grant '{}' uses all_in_schema and must set schema and no other object",
+                    g.name
+                );
+            }
+        }
+    }
+
+    for r in &cfg.rules {
+        if !["SELECT", "INSERT", "UPDATE", "DELETE"].contains(&r.event.to_uppercase().as_str()) {
+            bail!(
+                "rule '{}' has invalid event '{}': expected SELECT, INSERT, UPDATE or DELETE",
+                r.name,
+                r.event
+            );
+        }
+    }
+
+    for table in &cfg.tables {
+        for fk in &table.foreign_keys {
+            if let Some(m) = &fk.r#match
+                && !["FULL", "PARTIAL", "SIMPLE"].contains(&m.to_uppercase().as_str())
+            {
+                bail!(
+                    "foreign key '{}' on table '{}' has invalid match '{}': expected FULL, PARTIAL or SIMPLE",
+                    fk.name.as_deref().unwrap_or("<unnamed>"),
+                    table.name,
+                    m
+                );
+            }
+            if fk.initially_deferred && !fk.deferrable {
+                bail!(
+                    "foreign key '{}' on table '{}' sets initially_deferred without deferrable = true",
+                    fk.name.as_deref().unwrap_or("<unnamed>"),
+                    table.name
+                );
+            }
+        }
+        if table.partition_by.is_some() && !table.storage_parameters.is_empty() {
+            bail!(
+                "table '{}' is partitioned and cannot have storage_parameters; set them on its partitions instead",
+                table.name
+            );
+        }
+        if let Some(partition_by) = &table.partition_by {
+            // Postgres enforces uniqueness per partition, so unique keys must
+            // contain every partition column.
+            let missing = |columns: &[String]| {
+                partition_by
+                    .columns
+                    .iter()
+                    .filter(|c| !columns.contains(c))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            if let Some(pk) = &table.primary_key {
+                let missing = missing(&pk.columns);
+                if !missing.is_empty() {
+                    bail!(
+                        "primary key of partitioned table '{}' must include partition column(s) {}: got ({})",
+                        table.name,
+                        missing.join(", "),
+                        pk.columns.join(", ")
+                    );
+                }
+            }
+            for ix in table
+                .indexes
+                .iter()
+                .filter(|ix| ix.unique && ix.expressions.is_empty())
+            {
+                let missing = missing(&ix.columns);
+                if !missing.is_empty() {
+                    bail!(
+                        "unique index '{}' on partitioned table '{}' must include partition column(s) {}: got ({})",
+                        ix.name.as_deref().unwrap_or("<unnamed>"),
+                        table.name,
+                        missing.join(", "),
+                        ix.columns.join(", ")
+                    );
+                }
+            }
+        }
+        for ex in &table.exclusions {
+            for col in ex.elements.iter().filter_map(|el| el.column.as_ref()) {
+                if !table.columns.iter().any(|c| &c.name == col) {
+                    bail!(
+                        "exclusion constraint '{}' on table '{}' references missing column '{}'",
+                        ex.name.as_deref().unwrap_or("<unnamed>"),
+                        table.name,
+                        col
+                    );
+                }
+            }
+        }
+    }
+
+    let check_tablespace = |tablespace: &Option<String>, what: String| -> Result<()> {
+        if let Some(ts) = tablespace
+            && !cfg
+                .tablespaces
+                .iter()
+                .any(|t| t.alt_name.as_deref().unwrap_or(&t.name) == ts)
+        {
+            bail!("{what} references undeclared tablespace '{ts}'");
+        }
+        Ok(())
+    };
+    for table in &cfg.tables {
+        check_tablespace(&table.tablespace, format!("table '{}'", table.name))?;
+        for idx in &table.indexes {
+            let name = idx.name.as_deref().unwrap_or("<unnamed>");
+            check_tablespace(
+                &idx.tablespace,
+                format!("index '{name}' on table '{}'", table.name),
+            )?;
+        }
+    }
+    for idx in &cfg.indexes {
+        check_tablespace(&idx.tablespace, format!("index '{}'", idx.name))?;
+    }
+
+    for table in &cfg.tables {
+        if let Some(idx) = table
+            .indexes
+            .iter()
+            .find(|ix| ix.nulls_not_distinct && !ix.unique)
+        {
+            bail!(
+                "index '{}' on table '{}' sets nulls_not_distinct but is not unique",
+                idx.name.as_deref().unwrap_or("<unnamed>"),
+                table.name
+            );
+        }
+    }
+    if let Some(idx) = cfg
+        .indexes
+        .iter()
+        .find(|ix| ix.nulls_not_distinct && !ix.unique)
+    {
+        bail!(
+            "index '{}' sets nulls_not_distinct but is not unique",
+            idx.name
+        );
+    }
+
+    let check_index_parameters = |method: &Option<String>,
+                                  params: &hcl::Map<String, hcl::Value>,
+                                  what: String|
+     -> Result<()> {
+        let Some(method) = method else {
+            return Ok(());
+        };
+        let allowed: &[&str] = match method.to_ascii_lowercase().as_str() {
+            "hnsw" => &["m", "ef_construction"],
+            "ivfflat" => &["lists"],
+            _ => return Ok(()),
+        };
+        if let Some(key) = params.keys().find(|k| !allowed.contains(&k.as_str())) {
+            bail!(
+                "{what} uses {method}, which does not accept storage parameter '{key}' (expected {})",
+                allowed.join(" or ")
+            );
+        }
+        Ok(())
+    };
+    for table in &cfg.tables {
+        for idx in &table.indexes {
+            let name = idx.name.as_deref().unwrap_or("<unnamed>");
+            check_index_parameters(
+                &idx.method,
+                &idx.storage_parameters,
+                format!("index '{name}' on table '{}'", table.name),
+            )?;
+        }
+    }
+    for idx in &cfg.indexes {
+        check_index_parameters(
+            &idx.method,
+            &idx.storage_parameters,
+            format!("index '{}'", idx.name),
+        )?;
+    }
+
+    for table in &cfg.tables {
+        for column in &table.columns {
+            if let Some(collation) = &column.collation
+                && !is_builtin_collation(collation)
+                && !cfg.collations.iter().any(|c| {
+                    let name = c.alt_name.as_deref().unwrap_or(&c.name);
+                    collation == name
+                })
+            {
+                bail!(
+                    "column '{}' on table '{}' uses unknown collation '{}': declare it with a collation block",
+                    column.name,
+                    table.name,
+                    collation
+                );
+            }
+            let ty = column.db_type.as_deref().unwrap_or(&column.r#type);
+            if let Some(extension) = extension_for_type(ty)
+                && !cfg
+                    .extensions
+                    .iter()
+                    .any(|e| e.alt_name.as_deref().unwrap_or(&e.name) == extension)
+                && !is_user_type(cfg, base_type_name(ty), table.schema.as_deref())
+            {
+                let message = format!(
+                    "column '{}' on table '{}' uses type '{}' from extension '{}': declare it with an extension block",
+                    column.name, table.name, ty, extension
+                );
+                if strict {
+                    bail!("Strict mode: {message}");
+                }
+                log::warn!("{message}");
+            }
+            if let Some(err) = vector_dimension_error(&column.r#type) {
+                bail!(
+                    "column '{}' on table '{}' has type '{}': {err}",
+                    column.name,
+                    table.name,
+                    column.r#type
+                );
+            }
+            let ty = column.r#type.to_ascii_lowercase();
+            if column.updated_at && !(ty.starts_with("timestamp") || ty == "date") {
+                bail!(
+                    "column '{}' on table '{}' sets updated_at but has type '{}': it must be a timestamp or date",
+                    column.name,
+                    table.name,
+                    column.r#type
+                );
+            }
+        }
+    }
+
+    composite_type_order(&cfg.types)?;
+
     if strict {
         for table in &cfg.tables {
             for column in &table.columns {
-                // Check if column type is an enum and if it's defined in HCL
-                if is_likely_enum(&column.r#type) {
-                    let found_enum =
-                        find_enum_for_type(&cfg.enums, &column.r#type, table.schema.as_deref());
-                    if found_enum.is_none() {
-                        bail!(
-                            "Strict mode: Enum type '{}' referenced in table '{}' column '{}' is not defined in HCL",
-                            column.r#type,
-                            table.name,
-                            column.name
-                        );
-                    }
+                // Check if column type (or array element type) is an enum,
+                // composite type or domain defined in HCL
+                let base = base_type_name(&column.r#type);
+                if is_likely_enum(base) && !is_user_type(cfg, base, table.schema.as_deref()) {
+                    bail!(
+                        "Strict mode: type '{}' referenced in table '{}' column '{}' is not defined in HCL",
+                        column.r#type,
+                        table.name,
+                        column.name
+                    );
+                }
+            }
+        }
+
+        for mv in &cfg.materialized {
+            let relations = pg_query::parse(&mv.sql)
+                .with_context(|| format!("parsing SQL of materialized view '{}'", mv.name))?
+                .tables();
+            for relation in relations {
+                if !relation_is_known(cfg, &relation) {
+                    bail!(
+                        "Strict mode: materialized view '{}' references unknown relation '{}'",
+                        mv.name,
+                        relation
+                    );
                 }
             }
         }
@@ -66,6 +467,110 @@ pub fn validate(cfg: &Config, strict: bool) -> Result<()> {
     Ok(())
 }
 
+/// Whether `relation` (as returned by `pg_query`'s `tables()`) names a
+/// table, view or materialized view in the config, or a system catalog.
+fn relation_is_known(cfg: &Config, relation: &str) -> bool {
+    let (schema, name) = match relation.rsplit_once('.') {
+        Some((s, n)) => (Some(s), n),
+        None => (None, relation),
+    };
+    if matches!(schema, Some("pg_catalog" | "information_schema"))
+        || schema.is_none() && name.starts_with("pg_")
+    {
+        return true;
+    }
+    let matches = |n: &str, alt: Option<&str>, s: Option<&str>| {
+        let n = alt.unwrap_or(n);
+        (n == name || n.to_lowercase() == name)
+            && schema.is_none_or(|schema| s.unwrap_or("public") == schema)
+    };
+    cfg.tables
+        .iter()
+        .any(|t| matches(&t.name, t.alt_name.as_deref(), t.schema.as_deref()))
+        || cfg
+            .views
+            .iter()
+            .any(|v| matches(&v.name, v.alt_name.as_deref(), v.schema.as_deref()))
+        || cfg
+            .materialized
+            .iter()
+            .any(|m| matches(&m.name, m.alt_name.as_deref(), m.schema.as_deref()))
+        || cfg
+            .foreign_tables
+            .iter()
+            .any(|f| matches(&f.name, f.alt_name.as_deref(), f.schema.as_deref()))
+}
+
+/// Strip array suffixes such as `[]` or `[3]` from a column type.
+pub fn base_type_name(coltype: &str) -> &str {
+    let trimmed = coltype.trim();
+    match trimmed.find('[') {
+        Some(idx) => trimmed[..idx].trim_end(),
+        None => trimmed,
+    }
+}
+
+/// Whether `coltype` names an enum, composite type or domain declared in
+/// the config.
+fn is_user_type(cfg: &Config, coltype: &str, table_schema: Option<&str>) -> bool {
+    find_enum_for_type(&cfg.enums, coltype, table_schema).is_some()
+        || cfg.types.iter().any(|t| {
+            let name = t.alt_name.as_deref().unwrap_or(&t.name);
+            type_matches(coltype, name, t.schema.as_deref())
+        })
+        || cfg.domains.iter().any(|d| {
+            let name = d.alt_name.as_deref().unwrap_or(&d.name);
+            type_matches(coltype, name, d.schema.as_deref())
+        })
+}
+
+/// Whether a (possibly schema-qualified) type reference names the type
+/// `name` in `schema` (default `public`).
+fn type_matches(coltype: &str, name: &str, schema: Option<&str>) -> bool {
+    let unquote = |s: &str| s.trim_matches('"').to_string();
+    match coltype.split_once('.') {
+        Some((s, n)) => unquote(n) == name && unquote(s) == schema.unwrap_or("public"),
+        None => unquote(coltype) == name,
+    }
+}
+
+/// Composite types in creation order: a type nested in another one's fields
+/// comes first. Fails if the nesting is cyclic.
+pub fn composite_type_order(types: &[CompositeTypeSpec]) -> Result<Vec<&CompositeTypeSpec>> {
+    fn visit<'a>(
+        i: usize,
+        types: &'a [CompositeTypeSpec],
+        state: &mut [u8],
+        out: &mut Vec<&'a CompositeTypeSpec>,
+    ) -> Result<()> {
+        match state[i] {
+            2 => return Ok(()),
+            1 => bail!("composite type '{}' contains itself", types[i].name),
+            _ => {}
+        }
+        state[i] = 1;
+        for field in &types[i].fields {
+            let base = base_type_name(&field.r#type);
+            for (j, t) in types.iter().enumerate() {
+                let name = t.alt_name.as_deref().unwrap_or(&t.name);
+                if type_matches(base, name, t.schema.as_deref()) {
+                    visit(j, types, state, out)?;
+                }
+            }
+        }
+        state[i] = 2;
+        out.push(&types[i]);
+        Ok(())
+    }
+
+    let mut state = vec![0u8; types.len()];
+    let mut out = Vec::with_capacity(types.len());
+    for i in 0..types.len() {
+        visit(i, types, &mut state, &mut out)?;
+    }
+    Ok(out)
+}
+
 pub fn find_enum_for_type<'a>(
     enums: &'a [EnumSpec],
     coltype: &str,
@@ -96,9 +601,257 @@ pub fn find_enum_for_type<'a>(
     })
 }
 
+/// The extension providing `coltype`, for well-known extension types. Type
+/// modifiers, array suffixes, quoting and a schema prefix are ignored.
+fn extension_for_type(coltype: &str) -> Option<&'static str> {
+    const TYPES: &[(&str, &str)] = &[
+        ("citext", "citext"),
+        ("hstore", "hstore"),
+        ("ltree", "ltree"),
+        ("lquery", "ltree"),
+        ("ltxtquery", "ltree"),
+        ("vector", "vector"),
+        ("halfvec", "vector"),
+        ("sparsevec", "vector"),
+        ("cube", "cube"),
+        ("geometry", "postgis"),
+        ("geography", "postgis"),
+    ];
+    let base = base_type_name(coltype);
+    let base = base.split('(').next().unwrap_or(base).trim();
+    let name = base.rsplit('.').next().unwrap_or(base).trim_matches('"');
+    TYPES
+        .iter()
+        .find(|(ty, _)| name.eq_ignore_ascii_case(ty))
+        .map(|(_, extension)| *extension)
+}
+
+/// Why the dimension of a pgvector `vector(N)` or `halfvec(N)` type is
+/// invalid, if it is.
+fn vector_dimension_error(coltype: &str) -> Option<String> {
+    const MAX_DIMENSIONS: u32 = 16000;
+    let base = base_type_name(coltype);
+    let (name, rest) = base.split_once('(')?;
+    let name = name
+        .trim()
+        .rsplit('.')
+        .next()
+        .unwrap_or(name)
+        .trim_matches('"');
+    if !name.eq_ignore_ascii_case("vector") && !name.eq_ignore_ascii_case("halfvec") {
+        return None;
+    }
+    let dimension = rest.strip_suffix(')').unwrap_or(rest).trim();
+    match dimension.parse::<u32>() {
+        Ok(n) if (1..=MAX_DIMENSIONS).contains(&n) => None,
+        _ => Some(format!(
+            "the dimension must be a whole number between 1 and {MAX_DIMENSIONS}"
+        )),
+    }
+}
+
+/// Collations that exist in every database: the fixed built-ins, the ICU
+/// collations imported by initdb (`*-x-icu`) and libc locales such as
+/// `en_US.utf8`.
+fn is_builtin_collation(name: &str) -> bool {
+    const FIXED: &[&str] = &["default", "C", "POSIX", "ucs_basic", "unicode", "pg_c_utf8"];
+    if FIXED.contains(&name) || name.ends_with("-x-icu") {
+        return true;
+    }
+    let (locale, encoding) = name.split_once('.').unwrap_or((name, ""));
+    let (lang, region) = locale.split_once('_').unwrap_or((locale, ""));
+    (2..=3).contains(&lang.len())
+        && lang.chars().all(|c| c.is_ascii_lowercase())
+        && (region.is_empty()
+            || region.len() == 2 && region.chars().all(|c| c.is_ascii_uppercase()))
+        && encoding
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Whether `name` can be written unquoted as a setting in `ALTER ROLE ... SET`:
+/// one or more dot-separated identifiers, as in `search_path` or `app.tenant`.
+fn is_setting_name(name: &str) -> bool {
+    name.split('.').all(|part| {
+        let mut chars = part.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    })
+}
+
 pub fn is_likely_enum(s: &str) -> bool {
     // Simple heuristic: starts with uppercase letter and contains only alphanumeric characters
     // This is a basic check and might need refinement based on actual enum naming conventions
     s.chars().next().map_or(false, |c| c.is_ascii_uppercase())
         && s.chars().all(|c| c.is_ascii_alphanumeric())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::load_hcl;
+
+    #[test]
+    fn strict_validation_flags_materialized_view_missing_relation() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "id" {
+                type = "integer"
+              }
+            }
+
+            materialized "user_orders" {
+              sql = <<-SQL
+                SELECT u.id, count(o.id) AS orders
+                FROM public.users u
+                LEFT JOIN "orders" o ON o.user_id = u.id
+                GROUP BY u.id
+              SQL
+            }
+            "#,
+        );
+        validate(&cfg, false).unwrap();
+        let err = validate(&cfg, true).unwrap_err().to_string();
+        assert!(
+            err.contains("materialized view 'user_orders' references unknown relation 'orders'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn strict_validation_ignores_cte_aliases_in_materialized_views() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "id" {
+                type = "integer"
+              }
+              column "created_at" {
+                type = "timestamptz"
+              }
+            }
+
+            view "active_users" {
+              sql = "SELECT id FROM users"
+            }
+
+            materialized "signups" {
+              sql = <<-SQL
+                WITH recent (id, day) AS (
+                  SELECT id, date_trunc('day', created_at) FROM users
+                ), counted AS (
+                  SELECT day, count(*) AS n FROM recent GROUP BY day
+                )
+                SELECT c.day, c.n, extract(dow FROM c.day) AS dow
+                FROM counted c, active_users a
+                WHERE EXISTS (SELECT 1 FROM pg_catalog.pg_class)
+              SQL
+            }
+            "#,
+        );
+        validate(&cfg, true).unwrap();
+    }
+
+    #[test]
+    fn partitioned_table_keys_must_include_partition_columns() {
+        let load = |keys: &str| {
+            load_hcl(&format!(
+                r#"
+                table "events" {{
+                  column "id" {{
+                    type     = "bigint"
+                    nullable = false
+                  }}
+                  column "created_at" {{
+                    type     = "timestamptz"
+                    nullable = false
+                  }}
+                  partition_by {{
+                    strategy = "RANGE"
+                    columns  = ["created_at"]
+                  }}
+                  {keys}
+                }}
+                "#
+            ))
+        };
+
+        let cfg = load(r#"primary_key { columns = ["id"] }"#);
+        let err = validate(&cfg, false).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "primary key of partitioned table 'events' must include partition column(s) created_at: got (id)"
+        );
+
+        let cfg = load(
+            r#"index "events_id_key" {
+                columns = ["id"]
+                unique  = true
+              }"#,
+        );
+        let err = validate(&cfg, false).unwrap_err().to_string();
+        assert!(err.contains("unique index 'events_id_key'"), "{err}");
+
+        let cfg = load(r#"primary_key { columns = ["id", "created_at"] }"#);
+        validate(&cfg, false).unwrap();
+    }
+
+    #[test]
+    fn extension_types_require_their_extension() {
+        let validate_hcl = |hcl: &str| {
+            let cfg = load_hcl(hcl);
+            validate(&cfg, false)?;
+            validate(&cfg, true)
+        };
+        let table = r#"
+            table "users" {
+              column "email" { type = "citext" }
+              column "embedding" { type = "extensions.vector(3)" }
+            }
+        "#;
+
+        let err = validate_hcl(table).unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "Strict mode: column 'email' on table 'users' uses type 'citext' from extension 'citext'"
+            ),
+            "{err}"
+        );
+        let err = validate_hcl(&format!("extension \"citext\" {{}}\n{table}")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("uses type 'extensions.vector(3)' from extension 'vector'"),
+            "{err}"
+        );
+        validate_hcl(&format!(
+            "extension \"citext\" {{}}\nextension \"vector\" {{ schema = \"extensions\" }}\n{table}"
+        ))
+        .unwrap();
+
+        validate_hcl(
+            r#"
+            domain "citext" { type = "text" }
+            table "users" {
+              column "email" { type = "citext" }
+            }
+            "#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn role_config_keys_must_be_setting_names() {
+        let role = |key: &str| format!("role \"app\" {{\n  config = {{ \"{key}\" = \"x\" }}\n}}");
+        validate(&load_hcl(&role("search_path")), false).unwrap();
+        validate(&load_hcl(&role("app.tenant_id")), false).unwrap();
+        for key in ["work_mem = 1; DROP ROLE app; --", "app.", "1abc"] {
+            let err = validate(&load_hcl(&role(key)), false)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("role 'app' has invalid config key"), "{err}");
+        }
+    }
+}
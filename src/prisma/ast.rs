@@ -163,6 +163,7 @@ pub enum BlockAttribute {
     Unique(Vec<Identifier>),
     Index(Vec<Identifier>),
     Map(String),
+    Schema(String),
     Raw(String),
 }
 
@@ -908,6 +908,10 @@ fn convert_block_attribute(attr: Attribute) -> BlockAttribute {
             })
             .unwrap_or_else(|| BlockAttribute::Raw(format_attribute("@@", &attr))),
         "map" => BlockAttribute::Raw(format_attribute("@@", &attr)),
+        "schema" if attr.arguments.len() == 1 => match &attr.arguments[0].value {
+            Value::String(value) => BlockAttribute::Schema(value.clone()),
+            _ => BlockAttribute::Raw(format_attribute("@@", &attr)),
+        },
         _ => BlockAttribute::Raw(format_attribute("@@", &attr)),
     }
 }
@@ -15,11 +15,20 @@ fn write_documentation(f: &mut fmt::Formatter<'_>, doc: &Option<String>) -> fmt:
     Ok(())
 }
 
+/// Write a block member indented by two spaces. Members may span several
+/// lines when they carry `///` documentation.
+fn write_member(f: &mut fmt::Formatter<'_>, member: &impl fmt::Display) -> fmt::Result {
+    for line in member.to_string().lines() {
+        writeln!(f, "  {}", line)?;
+    }
+    Ok(())
+}
+
 impl fmt::Display for Schema {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut needs_gap = false;
 
-        for block in &self.enums {
+        for block in &self.datasources {
             if needs_gap {
                 writeln!(f)?;
                 writeln!(f)?;
@@ -28,7 +37,7 @@ impl fmt::Display for Schema {
             needs_gap = true;
         }
 
-        for block in &self.models {
+        for block in &self.generators {
             if needs_gap {
                 writeln!(f)?;
                 writeln!(f)?;
@@ -37,7 +46,7 @@ impl fmt::Display for Schema {
             needs_gap = true;
         }
 
-        for block in &self.views {
+        for block in &self.enums {
             if needs_gap {
                 writeln!(f)?;
                 writeln!(f)?;
@@ -46,7 +55,7 @@ impl fmt::Display for Schema {
             needs_gap = true;
         }
 
-        for block in &self.composite_types {
+        for block in &self.models {
             if needs_gap {
                 writeln!(f)?;
                 writeln!(f)?;
@@ -55,16 +64,16 @@ impl fmt::Display for Schema {
             needs_gap = true;
         }
 
-        for alias in &self.type_aliases {
+        for block in &self.views {
             if needs_gap {
                 writeln!(f)?;
                 writeln!(f)?;
             }
-            write!(f, "{}", alias)?;
+            write!(f, "{}", block)?;
             needs_gap = true;
         }
 
-        for block in &self.custom_blocks {
+        for block in &self.composite_types {
             if needs_gap {
                 writeln!(f)?;
                 writeln!(f)?;
@@ -73,16 +82,16 @@ impl fmt::Display for Schema {
             needs_gap = true;
         }
 
-        for block in &self.datasources {
+        for alias in &self.type_aliases {
             if needs_gap {
                 writeln!(f)?;
                 writeln!(f)?;
             }
-            write!(f, "{}", block)?;
+            write!(f, "{}", alias)?;
             needs_gap = true;
         }
 
-        for block in &self.generators {
+        for block in &self.custom_blocks {
             if needs_gap {
                 writeln!(f)?;
                 writeln!(f)?;
@@ -100,7 +109,7 @@ impl fmt::Display for Enum {
         write_documentation(f, &self.documentation)?;
         writeln!(f, "enum {} {{", self.name)?;
         for value in &self.values {
-            writeln!(f, "  {}", value)?;
+            write_member(f, value)?;
         }
         for attr in &self.attributes {
             writeln!(f, "  {}", attr)?;
@@ -125,7 +134,7 @@ impl fmt::Display for Model {
         write_documentation(f, &self.documentation)?;
         writeln!(f, "model {} {{", self.name)?;
         for field in &self.fields {
-            writeln!(f, "  {}", field)?;
+            write_member(f, field)?;
         }
         for attr in &self.attributes {
             writeln!(f, "  {}", attr)?;
@@ -139,7 +148,7 @@ impl fmt::Display for View {
         write_documentation(f, &self.documentation)?;
         writeln!(f, "view {} {{", self.name)?;
         for field in &self.fields {
-            writeln!(f, "  {}", field)?;
+            write_member(f, field)?;
         }
         for attr in &self.attributes {
             writeln!(f, "  {}", attr)?;
@@ -153,7 +162,7 @@ impl fmt::Display for CompositeType {
         write_documentation(f, &self.documentation)?;
         writeln!(f, "type {} {{", self.name)?;
         for field in &self.fields {
-            writeln!(f, "  {}", field)?;
+            write_member(f, field)?;
         }
         write!(f, "}}")
     }
@@ -288,6 +297,7 @@ impl fmt::Display for BlockAttribute {
                     .join(", ")
             ),
             BlockAttribute::Map(value) => write!(f, "@@map(\"{}\")", value),
+            BlockAttribute::Schema(value) => write!(f, "@@schema(\"{}\")", value),
             BlockAttribute::Raw(value) => write!(f, "{value}"),
         }
     }
@@ -314,7 +324,7 @@ impl fmt::Display for ConfigBlock {
         };
         writeln!(f, "{} {} {{", keyword, self.name)?;
         for property in &self.properties {
-            writeln!(f, "  {}", property)?;
+            write_member(f, property)?;
         }
         write!(f, "}}")
     }
@@ -0,0 +1,127 @@
+//! Opt-in phase timings behind the CLI's `--profile` flag.
+//!
+//! Timings are collected per thread and only after [`enable`], so library
+//! users pay nothing by default. Repeated phases (e.g. `parse` for every
+//! module file) accumulate into one entry.
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static PHASES: RefCell<Option<Vec<(&'static str, Duration)>>> = const { RefCell::new(None) };
+}
+
+/// Start collecting timings on this thread, discarding earlier ones.
+pub fn enable() {
+    PHASES.with(|p| *p.borrow_mut() = Some(Vec::new()));
+}
+
+pub fn is_enabled() -> bool {
+    PHASES.with(|p| p.borrow().is_some())
+}
+
+/// Run `f`, adding its elapsed time to `phase` when profiling is enabled.
+pub fn time<T>(phase: &'static str, f: impl FnOnce() -> T) -> T {
+    if !is_enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let out = f();
+    record(phase, start.elapsed());
+    out
+}
+
+/// Add `elapsed` to `phase` when profiling is enabled.
+pub fn record(phase: &'static str, elapsed: Duration) {
+    PHASES.with(|p| {
+        if let Some(phases) = p.borrow_mut().as_mut() {
+            match phases.iter_mut().find(|(name, _)| *name == phase) {
+                Some((_, total)) => *total += elapsed,
+                None => phases.push((phase, elapsed)),
+            }
+        }
+    });
+}
+
+/// Time recorded so far for `phase`.
+pub fn elapsed(phase: &str) -> Duration {
+    PHASES.with(|p| {
+        p.borrow()
+            .as_ref()
+            .and_then(|phases| phases.iter().find(|(name, _)| *name == phase))
+            .map(|(_, total)| *total)
+            .unwrap_or_default()
+    })
+}
+
+/// Write one `phase: 1.234ms` line per phase, in the order first recorded.
+pub fn report(out: &mut dyn Write) -> io::Result<()> {
+    let phases = PHASES.with(|p| p.borrow().clone().unwrap_or_default());
+    for (phase, total) in phases {
+        writeln!(
+            out,
+            "profile: {phase}: {:.3}ms",
+            total.as_secs_f64() * 1000.0
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::env::EnvVars;
+    use crate::{MapLoader, generate_with_backend, load_config, validate};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn report_lists_phases_in_order() {
+        let path = PathBuf::from("/root/main.hcl");
+        let loader = MapLoader {
+            files: HashMap::from([(
+                path.clone(),
+                r#"table "users" {
+                  column "id" {
+                    type = "integer"
+                  }
+                }"#
+                .to_string(),
+            )]),
+        };
+
+        enable();
+        let cfg = load_config(&path, &loader, EnvVars::default()).unwrap();
+        time("validate", || validate(&cfg, false)).unwrap();
+        time("lint", || crate::lint::run(&cfg, &Default::default()));
+        time("generate", || {
+            generate_with_backend("postgres", &cfg, false)
+        })
+        .unwrap();
+
+        let mut stderr = Vec::new();
+        report(&mut stderr).unwrap();
+        let stderr = String::from_utf8(stderr).unwrap();
+        let labels: Vec<&str> = stderr
+            .lines()
+            .map(|l| l.split(':').nth(1).unwrap().trim())
+            .collect();
+        assert_eq!(
+            labels,
+            ["parse", "evaluate", "validate", "lint", "generate"]
+        );
+        assert!(
+            stderr
+                .lines()
+                .all(|l| l.starts_with("profile: ") && l.ends_with("ms"))
+        );
+    }
+
+    #[test]
+    fn nothing_is_recorded_unless_enabled() {
+        assert_eq!(time("validate", || 1 + 1), 2);
+        assert!(!is_enabled());
+        assert_eq!(elapsed("validate"), Duration::ZERO);
+    }
+}
@@ -0,0 +1,319 @@
+//! Apply a generated migration to a live database.
+
+use anyhow::{Context, Result};
+use log::info;
+use postgres::{Client, GenericClient};
+use sha2::{Digest, Sha256};
+
+use super::backend::{ResourceSql, to_sql_resources};
+use super::version::PgVersion;
+use crate::backends::Statement;
+use crate::ir::Config;
+
+/// SQL that takes the session-level advisory lock serializing applies to the
+/// current database.
+pub const LOCK_SQL: &str = "SELECT pg_advisory_lock(hashtext('dbschema:' || current_database()))";
+/// SQL that releases the lock taken by [`LOCK_SQL`].
+pub const UNLOCK_SQL: &str =
+    "SELECT pg_advisory_unlock(hashtext('dbschema:' || current_database()))";
+
+/// Table recording migrations applied with [`ApplyOptions::track`].
+pub const TRACKING_TABLE: &str = "dbschema_migrations";
+
+/// How [`apply_migration`] runs the generated SQL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplyOptions {
+    /// Run everything in one transaction with a savepoint per resource.
+    pub transactional: bool,
+    /// Hold an advisory lock for the duration of the apply so concurrent
+    /// applies to the same database run one after another.
+    pub lock: bool,
+    /// Record the migration under this name in [`TRACKING_TABLE`] and skip it
+    /// when a migration with the same SQL hash was already applied.
+    pub track: Option<String>,
+    /// Log each resource's SQL before running it.
+    pub verbose: bool,
+}
+
+impl Default for ApplyOptions {
+    fn default() -> Self {
+        Self {
+            transactional: true,
+            lock: true,
+            track: None,
+            verbose: false,
+        }
+    }
+}
+
+/// Apply the SQL generated for `cfg` resource by resource. Returns `false`
+/// when tracking is enabled and the migration had already been applied.
+///
+/// When transactional, everything runs inside one transaction and each
+/// resource inside its own savepoint, so a failure names the offending
+/// resource and rolls the whole migration back. Statements such as
+/// `CREATE INDEX CONCURRENTLY` that refuse to run inside a transaction run
+/// one at a time after the commit. Otherwise every statement runs and is
+/// committed on its own.
+pub fn apply_migration(client: &mut Client, cfg: &Config, options: &ApplyOptions) -> Result<bool> {
+    if !options.lock {
+        return apply_resources(client, cfg, options);
+    }
+    if options.verbose {
+        info!("-- acquiring advisory lock --");
+    }
+    client
+        .batch_execute(LOCK_SQL)
+        .context("acquiring advisory lock")?;
+    let result = apply_resources(client, cfg, options);
+    let unlocked = client.batch_execute(UNLOCK_SQL);
+    match result {
+        Ok(applied) => unlocked
+            .context("releasing advisory lock")
+            .map(|()| applied),
+        Err(e) => match unlocked {
+            Ok(()) => Err(e),
+            Err(unlock) => Err(e.context(format!("releasing advisory lock also failed: {unlock}"))),
+        },
+    }
+}
+
+/// Hex-encoded SHA-256 of the migration SQL, used to detect re-applies.
+pub fn migration_hash(resources: &[ResourceSql]) -> String {
+    let mut hasher = Sha256::new();
+    for r in resources {
+        hasher.update(r.sql.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// The target version matching the server `client` is connected to.
+fn server_version(client: &mut Client) -> Result<PgVersion> {
+    let row = client
+        .query_one("SELECT current_setting('server_version_num')::int", &[])
+        .context("reading server version")?;
+    let num: i32 = row.get(0);
+    PgVersion::for_server((num / 10000) as u32)
+}
+
+fn apply_resources(client: &mut Client, cfg: &Config, options: &ApplyOptions) -> Result<bool> {
+    let version = server_version(client)?;
+    let resources = to_sql_resources(cfg, version)?;
+    let hash = migration_hash(&resources);
+    if let Some(name) = &options.track {
+        client
+            .batch_execute(&format!(
+                "CREATE TABLE IF NOT EXISTS {TRACKING_TABLE} (\n  \
+                   id bigserial PRIMARY KEY,\n  \
+                   name text NOT NULL,\n  \
+                   hash text NOT NULL UNIQUE,\n  \
+                   applied_at timestamptz NOT NULL DEFAULT now()\n\
+                 );"
+            ))
+            .context("creating migration tracking table")?;
+        let applied = client
+            .query_opt(
+                &format!("SELECT name FROM {TRACKING_TABLE} WHERE hash = $1"),
+                &[&hash],
+            )
+            .context("reading migration tracking table")?;
+        if let Some(row) = applied {
+            let previous: String = row.get(0);
+            info!("migration '{name}' already applied as '{previous}' ({hash}); skipping");
+            return Ok(false);
+        }
+    }
+
+    if !options.transactional {
+        for r in &resources {
+            if options.verbose {
+                info!("-- applying {} --\n{}", r.resource, r.sql);
+            }
+            // One statement per call: several in one call form an implicit
+            // transaction block, which `CREATE INDEX CONCURRENTLY` refuses.
+            for statement in &r.statements {
+                client
+                    .batch_execute(&statement.sql)
+                    .with_context(|| format!("applying {}", r.resource))?;
+            }
+        }
+        record(client, options, &hash)?;
+        return Ok(true);
+    }
+
+    // Statements that cannot run in a transaction block run after the
+    // commit, keeping their relative order, like `--wrap-transaction`.
+    let mut deferred = Vec::new();
+    let mut tx = client
+        .transaction()
+        .context("starting migration transaction")?;
+    for (i, r) in resources.iter().enumerate() {
+        if options.verbose {
+            info!("-- applying {} --\n{}", r.resource, r.sql);
+        }
+        let (inside, after): (Vec<&Statement>, Vec<&Statement>) =
+            r.statements.iter().partition(|s| s.transactional);
+        deferred.extend(after.into_iter().map(|s| (&r.resource, s)));
+        if inside.is_empty() {
+            continue;
+        }
+        let sql: Vec<&str> = inside.iter().map(|s| s.sql.as_str()).collect();
+        let mut sp = tx
+            .savepoint(format!("dbschema_{i}"))
+            .with_context(|| format!("creating savepoint for {}", r.resource))?;
+        sp.batch_execute(&sql.join("\n"))
+            .with_context(|| format!("applying {}; migration rolled back", r.resource))?;
+        sp.commit()
+            .with_context(|| format!("releasing savepoint for {}", r.resource))?;
+    }
+    tx.commit().context("committing migration transaction")?;
+    for (resource, statement) in deferred {
+        client.batch_execute(&statement.sql).with_context(|| {
+            format!("applying {resource} after the migration transaction committed")
+        })?;
+    }
+    record(client, options, &hash)?;
+    Ok(true)
+}
+
+fn record(client: &mut impl GenericClient, options: &ApplyOptions, hash: &str) -> Result<()> {
+    if let Some(name) = &options.track {
+        client
+            .execute(
+                &format!("INSERT INTO {TRACKING_TABLE} (name, hash) VALUES ($1, $2)"),
+                &[name, &hash],
+            )
+            .context("recording applied migration")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::postgres::test_support::start_pg;
+    use crate::test_support::load_hcl;
+    use postgres::NoTls;
+
+    #[test]
+    fn failing_resource_is_named_and_rolled_back() {
+        let (_container, dsn) = start_pg();
+        let mut client = Client::connect(&dsn, NoTls).unwrap();
+        let cfg = load_hcl(
+            r#"
+            table "widgets" {
+              column "id" { type = "int" }
+            }
+            view "broken" {
+              sql = "SELECT missing_column FROM widgets"
+            }
+            "#,
+        );
+
+        let err = apply_migration(&mut client, &cfg, &ApplyOptions::default()).unwrap_err();
+        assert!(format!("{err:#}").contains("applying view \"broken\""));
+
+        let row = client
+            .query_one("SELECT to_regclass('public.widgets') IS NULL", &[])
+            .unwrap();
+        assert!(row.get::<_, bool>(0));
+    }
+
+    #[test]
+    fn concurrent_apply_waits_for_lock() {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let (_container, dsn) = start_pg();
+        let mut holder = Client::connect(&dsn, NoTls).unwrap();
+        holder.batch_execute(LOCK_SQL).unwrap();
+
+        let cfg = load_hcl(
+            r#"
+            table "widgets" {
+              column "id" { type = "int" }
+            }
+            "#,
+        );
+        let (done_tx, done_rx) = mpsc::channel();
+        let worker_dsn = dsn.clone();
+        let worker = std::thread::spawn(move || {
+            let mut client = Client::connect(&worker_dsn, NoTls).unwrap();
+            let result = apply_migration(&mut client, &cfg, &ApplyOptions::default());
+            done_tx.send(()).unwrap();
+            result
+        });
+
+        assert!(done_rx.recv_timeout(Duration::from_millis(500)).is_err());
+        holder.batch_execute(UNLOCK_SQL).unwrap();
+        done_rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(worker.join().unwrap().unwrap());
+
+        let row = holder
+            .query_one("SELECT to_regclass('public.widgets') IS NOT NULL", &[])
+            .unwrap();
+        assert!(row.get::<_, bool>(0));
+    }
+
+    #[test]
+    fn concurrent_index_applies_outside_the_transaction() {
+        let (_container, dsn) = start_pg();
+        let mut client = Client::connect(&dsn, NoTls).unwrap();
+        let cfg = load_hcl(
+            r#"
+            table "widgets" {
+              column "id" { type = "int" }
+              column "name" { type = "text" }
+            }
+            index "widgets_name_idx" {
+              table        = "widgets"
+              columns      = ["name"]
+              concurrently = true
+            }
+            "#,
+        );
+
+        assert!(apply_migration(&mut client, &cfg, &ApplyOptions::default()).unwrap());
+        let row = client
+            .query_one(
+                "SELECT to_regclass('public.widgets_name_idx') IS NOT NULL",
+                &[],
+            )
+            .unwrap();
+        assert!(row.get::<_, bool>(0));
+
+        let options = ApplyOptions {
+            transactional: false,
+            ..Default::default()
+        };
+        assert!(apply_migration(&mut client, &cfg, &options).unwrap());
+    }
+
+    #[test]
+    fn tracked_migration_is_applied_once() {
+        let (_container, dsn) = start_pg();
+        let mut client = Client::connect(&dsn, NoTls).unwrap();
+        // Without IF NOT EXISTS a second run would fail, so success proves it was skipped.
+        let cfg = load_hcl(
+            r#"
+            table "widgets" {
+              if_not_exists = false
+              column "id" { type = "int" }
+            }
+            "#,
+        );
+        let options = ApplyOptions {
+            track: Some("init".into()),
+            ..Default::default()
+        };
+
+        assert!(apply_migration(&mut client, &cfg, &options).unwrap());
+        assert!(!apply_migration(&mut client, &cfg, &options).unwrap());
+
+        let rows = client
+            .query("SELECT name FROM dbschema_migrations", &[])
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<_, String>(0), "init");
+    }
+}
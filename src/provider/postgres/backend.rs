@@ -1,8 +1,15 @@
-use crate::backends::{generate_header_comment, Backend, CommentStyle};
+use crate::backends::{
+    Backend, CommentStyle, GeneratedArtifact, Statement, generate_header_comment, join_statements,
+};
+use crate::provider::postgres::version::PgVersion;
 use crate::{ir::*, provider::postgres::generator as pg};
-use anyhow::Result;
+use anyhow::{Context, Result};
 
-pub struct PostgresBackend;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostgresBackend {
+    /// PostgreSQL major version the SQL must run on.
+    pub target_version: PgVersion,
+}
 
 impl Backend for PostgresBackend {
     fn name(&self) -> &'static str {
@@ -11,195 +18,256 @@ impl Backend for PostgresBackend {
     fn file_extension(&self) -> &'static str {
         "sql"
     }
+    fn content_type(&self) -> &'static str {
+        "text/sql"
+    }
     fn generate(&self, cfg: &Config, _strict: bool) -> Result<String> {
-        to_sql(cfg)
+        to_sql(cfg, self.target_version)
+    }
+    fn generate_artifact(&self, cfg: &Config, _strict: bool) -> Result<GeneratedArtifact> {
+        Ok(GeneratedArtifact {
+            statements: render(cfg, self.target_version)?.statements,
+            warnings: Vec::new(),
+        })
+    }
+    fn generate_down(&self, cfg: &Config, _strict: bool) -> Result<String> {
+        to_down_sql(cfg)
+    }
+    fn generate_in_transaction(&self, cfg: &Config, _strict: bool) -> Result<String> {
+        to_sql_in_transaction(cfg, self.target_version)
     }
 }
 
-fn to_sql(cfg: &Config) -> Result<String> {
+fn to_sql(cfg: &Config, version: PgVersion) -> Result<String> {
     let header = generate_header_comment("PostgreSQL", CommentStyle::Sql);
-    let mut out = header;
+    Ok(header + &join_statements(&render(cfg, version)?.statements))
+}
+
+/// Render the migration inside `BEGIN;`/`COMMIT;`. Statements PostgreSQL
+/// refuses to run in a transaction block keep their relative order and
+/// follow the `COMMIT`.
+fn to_sql_in_transaction(cfg: &Config, version: PgVersion) -> Result<String> {
+    let mut out = generate_header_comment("PostgreSQL", CommentStyle::Sql);
+    let (inside, after): (Vec<Statement>, Vec<Statement>) = render(cfg, version)?
+        .statements
+        .into_iter()
+        .partition(|s| s.transactional);
+    out.push_str("BEGIN;\n\n");
+    out.push_str(&join_statements(&inside));
+    out.push_str("COMMIT;\n");
+    if !after.is_empty() {
+        out.push('\n');
+        out.push_str(join_statements(&after).trim_end());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// The SQL generated for a single resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceSql {
+    /// Resource kind and HCL label, e.g. `table "users"`.
+    pub resource: String,
+    /// All of `statements`, joined as in the migration file.
+    pub sql: String,
+    pub statements: Vec<Statement>,
+}
+
+/// Split the generated migration into per-resource chunks, in apply order.
+/// Concatenating the chunks yields the body of [`PostgresBackend::generate`]
+/// for `version` without its header comment.
+pub fn to_sql_resources(cfg: &Config, version: PgVersion) -> Result<Vec<ResourceSql>> {
+    let Rendered { statements, marks } = render(cfg, version)?;
+    let mut resources = Vec::with_capacity(marks.len());
+    for (i, (resource, start)) in marks.iter().enumerate() {
+        let end = marks
+            .get(i + 1)
+            .map(|(_, e)| *e)
+            .unwrap_or(statements.len());
+        if *start == end {
+            continue;
+        }
+        resources.push(ResourceSql {
+            resource: resource.clone(),
+            sql: join_statements(&statements[*start..end]),
+            statements: statements[*start..end].to_vec(),
+        });
+    }
+    Ok(resources)
+}
+
+/// The migration body produced by [`render`].
+#[derive(Default)]
+struct Rendered {
+    statements: Vec<Statement>,
+    /// Index of the first statement of each resource.
+    marks: Vec<(String, usize)>,
+}
+
+impl Rendered {
+    /// Start the statements of `resource`.
+    fn mark(&mut self, resource: String) {
+        self.marks.push((resource, self.statements.len()));
+    }
+
+    fn push(&mut self, sql: String) {
+        self.statements.push(Statement::new(sql));
+    }
+
+    fn push_comment(&mut self, resource: &impl pg::Commented) {
+        if let Some(comment) = pg::Comment::of(resource) {
+            self.push(comment.to_string());
+        }
+    }
+
+    fn push_statement(&mut self, statement: Statement) {
+        self.statements.push(statement);
+    }
+
+    fn push_index(&mut self, index: pg::Index, version: PgVersion) -> Result<()> {
+        if index.nulls_not_distinct {
+            version.require(PgVersion::V15, "NULLS NOT DISTINCT")?;
+        }
+        let sql = index.to_string();
+        self.statements.push(if index.concurrently {
+            Statement::concurrent(sql)
+        } else {
+            Statement::new(sql)
+        });
+        Ok(())
+    }
+}
+
+/// Render the migration body for PostgreSQL `version`, recording where each
+/// resource's SQL starts.
+fn render(cfg: &Config, version: PgVersion) -> Result<Rendered> {
+    let mut out = Rendered::default();
 
     for r in &cfg.roles {
-        out.push_str(&format!("{}\n\n", pg::Role::from(r)));
-        if let Some(comment) = &r.comment {
-            let name = r.alt_name.clone().unwrap_or_else(|| r.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON ROLE {} IS {};\n\n",
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
+        out.mark(format!("role \"{}\"", r.name));
+        if r.password
+            .as_deref()
+            .is_some_and(|pw| !pg::is_password_hash(pw))
+        {
+            log::warn!(
+                "role '{}' has a plaintext password; use a SCRAM-SHA-256 hash instead",
+                r.name
+            );
         }
+        out.push(pg::Role::from(r).to_string());
+        out.push_comment(r);
     }
 
     for t in &cfg.tablespaces {
-        out.push_str(&format!("{}\n\n", pg::Tablespace::from(t)));
-        if let Some(comment) = &t.comment {
-            let name = t.alt_name.clone().unwrap_or_else(|| t.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON TABLESPACE {} IS {};\n\n",
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("tablespace \"{}\"", t.name));
+        out.push(pg::Tablespace::from(t).to_string());
+        out.push_comment(t);
     }
 
     for s in &cfg.schemas {
-        out.push_str(&format!("{}\n\n", pg::Schema::from(s)));
-        if let Some(comment) = &s.comment {
-            let name = s.alt_name.clone().unwrap_or_else(|| s.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON SCHEMA {} IS {};\n\n",
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("schema \"{}\"", s.name));
+        out.push(pg::Schema::from(s).to_string());
+        out.push_comment(s);
     }
 
+    // `CREATE EXTENSION ... SCHEMA` fails if the schema is missing, so create
+    // undeclared extension schemas first.
+    let mut bootstrapped = Vec::new();
     for e in &cfg.extensions {
-        out.push_str(&format!("{}\n\n", pg::Extension::from(e)));
-        if let Some(comment) = &e.comment {
-            let name = e.alt_name.clone().unwrap_or_else(|| e.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON EXTENSION {} IS {};\n\n",
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
+        out.mark(format!("extension \"{}\"", e.name));
+        if let Some(schema) = &e.schema
+            && !matches!(schema.as_str(), "public" | "pg_catalog")
+            && !bootstrapped.contains(schema)
+            && !cfg
+                .schemas
+                .iter()
+                .any(|s| s.alt_name.as_deref().unwrap_or(&s.name) == schema)
+        {
+            out.push(
+                pg::Schema {
+                    name: schema.clone(),
+                    if_not_exists: true,
+                    authorization: None,
+                }
+                .to_string(),
+            );
+            bootstrapped.push(schema.clone());
         }
+        let extension = pg::Extension::from(e);
+        out.push(extension.to_string());
+        if let Some(update) = extension.update_sql() {
+            out.push(update);
+        }
+        out.push_comment(e);
     }
 
     for c in &cfg.collations {
-        out.push_str(&format!("{}\n\n", pg::Collation::from(c)));
-        if let Some(comment) = &c.comment {
-            let schema = c.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = c.alt_name.clone().unwrap_or_else(|| c.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON COLLATION {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("collation \"{}\"", c.name));
+        out.push(pg::Collation::from(c).to_string());
+        out.push_comment(c);
     }
 
     for d in &cfg.text_search_dictionaries {
-        out.push_str(&format!("{}\n\n", pg::TextSearchDictionary::from(d)));
-        if let Some(comment) = &d.comment {
-            let schema = d.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = d.alt_name.clone().unwrap_or_else(|| d.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON TEXT SEARCH DICTIONARY {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("text_search_dictionary \"{}\"", d.name));
+        out.push(pg::TextSearchDictionary::from(d).to_string());
+        out.push_comment(d);
     }
 
     for t in &cfg.text_search_templates {
-        out.push_str(&format!("{}\n\n", pg::TextSearchTemplate::from(t)));
-        if let Some(comment) = &t.comment {
-            let schema = t.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = t.alt_name.clone().unwrap_or_else(|| t.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON TEXT SEARCH TEMPLATE {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("text_search_template \"{}\"", t.name));
+        out.push(pg::TextSearchTemplate::from(t).to_string());
+        out.push_comment(t);
     }
 
     for p in &cfg.text_search_parsers {
-        out.push_str(&format!("{}\n\n", pg::TextSearchParser::from(p)));
-        if let Some(comment) = &p.comment {
-            let schema = p.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = p.alt_name.clone().unwrap_or_else(|| p.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON TEXT SEARCH PARSER {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("text_search_parser \"{}\"", p.name));
+        out.push(pg::TextSearchParser::from(p).to_string());
+        out.push_comment(p);
     }
 
     for c in &cfg.text_search_configurations {
-        out.push_str(&format!("{}\n\n", pg::TextSearchConfiguration::from(c)));
-        if let Some(comment) = &c.comment {
-            let schema = c.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = c.alt_name.clone().unwrap_or_else(|| c.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON TEXT SEARCH CONFIGURATION {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("text_search_configuration \"{}\"", c.name));
+        out.push(pg::TextSearchConfiguration::from(c).to_string());
+        out.push_comment(c);
     }
 
     for s in &cfg.sequences {
-        out.push_str(&format!("{}\n\n", pg::Sequence::from(s)));
-        if let Some(comment) = &s.comment {
-            let schema = s.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = s.alt_name.clone().unwrap_or_else(|| s.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON SEQUENCE {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("sequence \"{}\"", s.name));
+        out.push(pg::Sequence::from(s).to_string());
+        out.push_comment(s);
     }
 
     for e in &cfg.enums {
-        out.push_str(&format!("{}\n\n", pg::Enum::from(e)));
-        if let Some(comment) = &e.comment {
-            let schema = e.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = e.alt_name.clone().unwrap_or_else(|| e.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON TYPE {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
+        out.mark(format!("enum \"{}\"", e.name));
+        let en = pg::Enum::from(e);
+        out.push(en.to_string());
+        let add_values = en.add_value_sql();
+        if !add_values.is_empty() {
+            out.push_statement(Statement::non_transactional(add_values.join("\n")));
         }
+        out.push_comment(e);
     }
 
     for d in &cfg.domains {
-        out.push_str(&format!("{}\n\n", pg::Domain::from(d)));
-        if let Some(comment) = &d.comment {
-            let schema = d.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = d.alt_name.clone().unwrap_or_else(|| d.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON DOMAIN {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("domain \"{}\"", d.name));
+        out.push(pg::Domain::from(d).to_string());
+        out.push_comment(d);
     }
 
-    for t in &cfg.types {
-        out.push_str(&format!("{}\n\n", pg::CompositeType::from(t)));
-        if let Some(comment) = &t.comment {
-            let schema = t.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = t.alt_name.clone().unwrap_or_else(|| t.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON TYPE {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+    for t in crate::passes::validate::composite_type_order(&cfg.types)? {
+        out.mark(format!("type \"{}\"", t.name));
+        out.push(pg::CompositeType::from(t).to_string());
+        out.push_comment(t);
     }
 
     for t in &cfg.tables {
-        out.push_str(&format!("{}\n\n", pg::Table::from(t)));
+        out.mark(format!("table \"{}\"", t.name));
+        out.push(pg::Table::from(t).to_string());
         let schema = t.schema.clone().unwrap_or_else(|| "public".to_string());
         let table_name = t.alt_name.clone().unwrap_or_else(|| t.name.clone());
         for idx in &t.indexes {
-            out.push_str(&format!("{}\n\n", pg::Index::from_specs(t, idx)));
+            out.push_index(pg::Index::from_specs(t, idx), version)
+                .with_context(|| format!("table '{}'", t.name))?;
         }
         for chk in &t.checks {
             let constraint = chk
@@ -207,37 +275,30 @@ fn to_sql(cfg: &Config) -> Result<String> {
                 .as_ref()
                 .map(|n| format!("CONSTRAINT {} ", pg::ident(n)))
                 .unwrap_or_default();
-            out.push_str(&format!(
-                "ALTER TABLE {}.{} ADD {constraint}CHECK ({});\n\n",
+            out.push(format!(
+                "ALTER TABLE {}.{} ADD {constraint}CHECK ({});",
                 pg::ident(&schema),
                 pg::ident(&table_name),
                 chk.expression,
                 constraint = constraint,
             ));
         }
-        if let Some(comment) = &t.comment {
-            out.push_str(&format!(
-                "COMMENT ON TABLE {}.{} IS {};\n\n",
+        if t.row_level_security {
+            out.push(format!(
+                "ALTER TABLE {}.{} ENABLE ROW LEVEL SECURITY;",
                 pg::ident(&schema),
-                pg::ident(&table_name),
-                pg::literal(comment)
+                pg::ident(&table_name)
             ));
         }
-        for c in &t.columns {
-            if let Some(comment) = &c.comment {
-                out.push_str(&format!(
-                    "COMMENT ON COLUMN {}.{}.{} IS {};\n\n",
-                    pg::ident(&schema),
-                    pg::ident(&table_name),
-                    pg::ident(&c.name),
-                    pg::literal(comment)
-                ));
-            }
+        out.push_comment(t);
+        for column in &t.columns {
+            out.push_comment(&pg::ColumnComment { table: t, column });
         }
     }
 
     // Apply sequence ownership after tables exist to avoid ordering issues
     for s in &cfg.sequences {
+        out.mark(format!("sequence \"{}\"", s.name));
         if let Some(ob) = &s.owned_by {
             let schema = s.schema.clone().unwrap_or_else(|| "public".to_string());
             let name = s.alt_name.clone().unwrap_or_else(|| s.name.clone());
@@ -256,8 +317,8 @@ fn to_sql(cfg: &Config) -> Result<String> {
                     _ => ob.to_string(),
                 }
             };
-            out.push_str(&format!(
-                "ALTER SEQUENCE {}.{} OWNED BY {};\n\n",
+            out.push(format!(
+                "ALTER SEQUENCE {}.{} OWNED BY {};",
                 pg::ident(&schema),
                 pg::ident(&name),
                 target
@@ -266,197 +327,747 @@ fn to_sql(cfg: &Config) -> Result<String> {
     }
 
     for idx in &cfg.indexes {
-        out.push_str(&format!("{}\n\n", pg::Index::from_standalone(idx)));
+        out.mark(format!("index \"{}\"", idx.name));
+        out.push_index(pg::Index::from_standalone(idx), version)
+            .with_context(|| format!("index '{}'", idx.name))?;
     }
 
     for s in &cfg.statistics {
-        out.push_str(&format!("{}\n\n", pg::Statistics::from(s)));
-        if let Some(comment) = &s.comment {
-            let schema = s.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = s.alt_name.clone().unwrap_or_else(|| s.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON STATISTICS {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("statistics \"{}\"", s.name));
+        out.push(pg::Statistics::from(s).to_string());
+        out.push_comment(s);
     }
 
     for p in &cfg.policies {
-        out.push_str(&format!("{}\n\n", pg::Policy::from(p)));
-        if let Some(comment) = &p.comment {
-            let schema = p.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = p.alt_name.clone().unwrap_or_else(|| p.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON POLICY {} ON {}.{} IS {};\n\n",
-                pg::ident(&name),
-                pg::ident(&schema),
-                pg::ident(&p.table),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("policy \"{}\"", p.name));
+        out.push(pg::Policy::from(p).to_string());
+        out.push_comment(p);
     }
 
     for f in &cfg.functions {
-        out.push_str(&format!("{}\n\n", pg::Function::from(f)));
-        if let Some(comment) = &f.comment {
-            let schema = f.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = f.alt_name.clone().unwrap_or_else(|| f.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON FUNCTION {}.{}() IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("function \"{}\"", f.name));
+        out.push(pg::Function::from(f).to_string());
+        out.push_comment(f);
     }
 
     for p in &cfg.procedures {
-        out.push_str(&format!("{}\n\n", pg::Procedure::from(p)));
-        if let Some(comment) = &p.comment {
-            let schema = p.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = p.alt_name.clone().unwrap_or_else(|| p.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON PROCEDURE {}.{}() IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("procedure \"{}\"", p.name));
+        out.push(pg::Procedure::from(p).to_string());
+        out.push_comment(p);
     }
 
     for a in &cfg.aggregates {
-        out.push_str(&format!("{}\n\n", pg::Aggregate::from(a)));
-        if let Some(comment) = &a.comment {
-            let schema = a.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = a.alt_name.clone().unwrap_or_else(|| a.name.clone());
-            let inputs = a.inputs.join(", ");
-            out.push_str(&format!(
-                "COMMENT ON AGGREGATE {}.{}({}) IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                inputs,
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("aggregate \"{}\"", a.name));
+        out.push(pg::Aggregate::from(a).to_string());
+        out.push_comment(a);
     }
 
     for o in &cfg.operators {
-        out.push_str(&format!("{}\n\n", pg::Operator::from(o)));
-        if let Some(comment) = &o.comment {
-            let schema = o.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = o.alt_name.clone().unwrap_or_else(|| o.name.clone());
-            let left = o.left.clone().unwrap_or_else(|| "NONE".to_string());
-            let right = o.right.clone().unwrap_or_else(|| "NONE".to_string());
-            out.push_str(&format!(
-                "COMMENT ON OPERATOR OPERATOR({}.{}) ({}, {}) IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                left,
-                right,
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("operator \"{}\"", o.name));
+        out.push(pg::Operator::from(o).to_string());
+        out.push_comment(o);
     }
 
     for v in &cfg.views {
-        out.push_str(&format!("{}\n\n", pg::View::from(v)));
-        if let Some(comment) = &v.comment {
-            let schema = v.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = v.alt_name.clone().unwrap_or_else(|| v.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON VIEW {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("view \"{}\"", v.name));
+        out.push(pg::View::from(v).to_string());
+        out.push_comment(v);
     }
 
     for mv in &cfg.materialized {
-        out.push_str(&format!("{}\n\n", pg::MaterializedView::from(mv)));
-        if let Some(comment) = &mv.comment {
-            let schema = mv.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = mv.alt_name.clone().unwrap_or_else(|| mv.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON MATERIALIZED VIEW {}.{} IS {};\n\n",
-                pg::ident(&schema),
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
+        out.mark(format!("materialized \"{}\"", mv.name));
+        out.push(pg::MaterializedView::from(mv).to_string());
+        for idx in &mv.indexes {
+            let index = pg::Index::from_materialized(mv, idx);
+            out.push_index(index, version)
+                .with_context(|| format!("materialized view '{}'", mv.name))?;
         }
+        out.push_comment(mv);
     }
 
     for e in &cfg.event_triggers {
-        out.push_str(&format!("{}\n\n", pg::EventTrigger::from(e)));
-        if let Some(comment) = &e.comment {
-            let name = e.alt_name.clone().unwrap_or_else(|| e.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON EVENT TRIGGER {} IS {};\n\n",
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("event_trigger \"{}\"", e.name));
+        out.push(pg::EventTrigger::from(e).to_string());
+        out.push_comment(e);
     }
 
     for t in &cfg.triggers {
-        out.push_str(&format!("{}\n\n", pg::Trigger::from(t)));
-        if let Some(comment) = &t.comment {
-            let schema = t.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = t.alt_name.clone().unwrap_or_else(|| t.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON TRIGGER {} ON {}.{} IS {};\n\n",
-                pg::ident(&name),
-                pg::ident(&schema),
-                pg::ident(&t.table),
-                pg::literal(comment)
-            ));
+        out.mark(format!("trigger \"{}\"", t.name));
+        let mut trigger = pg::Trigger::from(t);
+        // Before CREATE OR REPLACE TRIGGER, replacing means dropping first.
+        if trigger.create == TriggerCreate::CreateOrReplace && version < PgVersion::V14 {
+            trigger.create = TriggerCreate::DropAndCreate;
+        }
+        if trigger.create == TriggerCreate::DropAndCreate {
+            out.push(trigger.drop_sql());
         }
+        out.push(trigger.to_string());
+        out.push_comment(t);
     }
 
     for r in &cfg.rules {
-        out.push_str(&format!("{}\n\n", pg::Rule::from(r)));
-        if let Some(comment) = &r.comment {
-            let schema = r.schema.clone().unwrap_or_else(|| "public".to_string());
-            let name = r.alt_name.clone().unwrap_or_else(|| r.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON RULE {} ON {}.{} IS {};\n\n",
-                pg::ident(&name),
-                pg::ident(&schema),
-                pg::ident(&r.table),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("rule \"{}\"", r.name));
+        out.push(pg::Rule::from(r).to_string());
+        out.push_comment(r);
     }
 
     for g in &cfg.grants {
-        out.push_str(&format!("{}\n\n", pg::Grant::from(g)));
+        out.mark(format!("grant \"{}\"", g.name));
+        out.push(pg::Grant::from(g).to_string());
     }
 
     for p in &cfg.publications {
-        out.push_str(&format!("{}\n\n", pg::Publication::from(p)));
-        if let Some(comment) = &p.comment {
-            let name = p.alt_name.clone().unwrap_or_else(|| p.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON PUBLICATION {} IS {};\n\n",
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
-        }
+        out.mark(format!("publication \"{}\"", p.name));
+        out.push(pg::Publication::from(p).to_string());
+        out.push_comment(p);
     }
 
     for s in &cfg.subscriptions {
-        out.push_str(&format!("{}\n\n", pg::Subscription::from(s)));
-        if let Some(comment) = &s.comment {
-            let name = s.alt_name.clone().unwrap_or_else(|| s.name.clone());
-            out.push_str(&format!(
-                "COMMENT ON SUBSCRIPTION {} IS {};\n\n",
-                pg::ident(&name),
-                pg::literal(comment)
-            ));
+        out.mark(format!("subscription \"{}\"", s.name));
+        out.push(pg::Subscription::from(s).to_string());
+        out.push_comment(s);
+    }
+
+    Ok(out)
+}
+
+/// `"schema"."name"` for a resource, defaulting the schema to `public` and
+/// preferring `alt_name` over the HCL label.
+fn qualified(schema: &Option<String>, name: &str, alt_name: &Option<String>) -> String {
+    format!(
+        "{}.{}",
+        pg::ident(schema.as_deref().unwrap_or("public")),
+        pg::ident(alt_name.as_deref().unwrap_or(name))
+    )
+}
+
+/// Tables ordered so that every table comes before the tables it references.
+/// Tables caught in a foreign key cycle are returned last, in reverse
+/// declaration order, flagged so the caller can drop them with CASCADE.
+fn tables_in_drop_order(tables: &[TableSpec]) -> Vec<(&TableSpec, bool)> {
+    let key = |t: &TableSpec| {
+        (
+            t.schema.clone().unwrap_or_else(|| "public".to_string()),
+            t.alt_name.clone().unwrap_or_else(|| t.name.clone()),
+        )
+    };
+    let mut remaining: Vec<&TableSpec> = tables.iter().collect();
+    let mut ordered = Vec::with_capacity(tables.len());
+    loop {
+        // A table can go once no other remaining table references it.
+        let referenced = |t: &TableSpec| {
+            let k = key(t);
+            remaining.iter().any(|other| {
+                key(other) != k
+                    && other.foreign_keys.iter().any(|fk| {
+                        fk.ref_schema.as_deref().unwrap_or("public") == k.0 && fk.ref_table == k.1
+                    })
+            })
+        };
+        let (ready, rest): (Vec<&TableSpec>, Vec<&TableSpec>) =
+            remaining.iter().partition(|t| !referenced(t));
+        if ready.is_empty() {
+            break;
         }
+        ordered.extend(ready.into_iter().map(|t| (t, false)));
+        remaining = rest;
+    }
+    ordered.extend(remaining.into_iter().rev().map(|t| (t, true)));
+    ordered
+}
+
+/// Render statements that drop everything [`to_sql`] creates, in reverse
+/// dependency order.
+fn to_down_sql(cfg: &Config) -> Result<String> {
+    let header = generate_header_comment("PostgreSQL", CommentStyle::Sql);
+    let mut out = header;
+    let mut push = |stmt: String| {
+        out.push_str(&stmt);
+        out.push_str("\n\n");
+    };
+
+    for s in cfg.subscriptions.iter().rev() {
+        let name = s.alt_name.as_deref().unwrap_or(&s.name);
+        push(format!("DROP SUBSCRIPTION IF EXISTS {};", pg::ident(name)));
+    }
+    for p in cfg.publications.iter().rev() {
+        let name = p.alt_name.as_deref().unwrap_or(&p.name);
+        push(format!("DROP PUBLICATION IF EXISTS {};", pg::ident(name)));
+    }
+    for g in cfg.grants.iter().rev() {
+        if let Some(stmt) = pg::Grant::from(g).undo_sql() {
+            push(stmt);
+        }
+    }
+    for r in cfg.rules.iter().rev() {
+        push(format!(
+            "DROP RULE IF EXISTS {} ON {};",
+            pg::ident(r.alt_name.as_deref().unwrap_or(&r.name)),
+            qualified(&r.schema, &r.table, &None)
+        ));
+    }
+    for t in cfg.triggers.iter().rev() {
+        push(format!(
+            "DROP TRIGGER IF EXISTS {} ON {};",
+            pg::ident(t.alt_name.as_deref().unwrap_or(&t.name)),
+            qualified(&t.schema, &t.table, &None)
+        ));
+    }
+    for e in cfg.event_triggers.iter().rev() {
+        let name = e.alt_name.as_deref().unwrap_or(&e.name);
+        push(format!("DROP EVENT TRIGGER IF EXISTS {};", pg::ident(name)));
+    }
+    for mv in cfg.materialized.iter().rev() {
+        push(format!(
+            "DROP MATERIALIZED VIEW IF EXISTS {};",
+            qualified(&mv.schema, &mv.name, &mv.alt_name)
+        ));
+    }
+    for v in cfg.views.iter().rev() {
+        push(format!(
+            "DROP VIEW IF EXISTS {};",
+            qualified(&v.schema, &v.name, &v.alt_name)
+        ));
+    }
+    for o in cfg.operators.iter().rev() {
+        push(format!(
+            "DROP OPERATOR IF EXISTS {}.{} ({}, {});",
+            pg::ident(o.schema.as_deref().unwrap_or("public")),
+            o.alt_name.as_deref().unwrap_or(&o.name),
+            o.left.as_deref().unwrap_or("NONE"),
+            o.right.as_deref().unwrap_or("NONE")
+        ));
+    }
+    for a in cfg.aggregates.iter().rev() {
+        let inputs = if a.inputs.is_empty() {
+            "*".to_string()
+        } else {
+            a.inputs.join(", ")
+        };
+        push(format!(
+            "DROP AGGREGATE IF EXISTS {}({});",
+            qualified(&a.schema, &a.name, &a.alt_name),
+            inputs
+        ));
+    }
+    for p in cfg.procedures.iter().rev() {
+        push(format!(
+            "DROP PROCEDURE IF EXISTS {}({});",
+            qualified(&p.schema, &p.name, &p.alt_name),
+            p.parameters.join(", ")
+        ));
+    }
+    for f in cfg.functions.iter().rev() {
+        push(format!(
+            "DROP FUNCTION IF EXISTS {}({});",
+            qualified(&f.schema, &f.name, &f.alt_name),
+            f.parameters
+                .iter()
+                .map(|p| pg::function_parameter(p, false))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    for p in cfg.policies.iter().rev() {
+        push(format!(
+            "DROP POLICY IF EXISTS {} ON {};",
+            pg::ident(p.alt_name.as_deref().unwrap_or(&p.name)),
+            qualified(&p.schema, &p.table, &None)
+        ));
+    }
+    for s in cfg.statistics.iter().rev() {
+        push(format!(
+            "DROP STATISTICS IF EXISTS {};",
+            qualified(&s.schema, &s.name, &s.alt_name)
+        ));
+    }
+    for idx in cfg.indexes.iter().rev() {
+        push(format!(
+            "DROP INDEX IF EXISTS {};",
+            qualified(&idx.schema, &idx.name, &None)
+        ));
+    }
+    for (t, cascade) in tables_in_drop_order(&cfg.tables) {
+        push(format!(
+            "DROP TABLE IF EXISTS {}{};",
+            qualified(&t.schema, &t.name, &t.alt_name),
+            if cascade { " CASCADE" } else { "" }
+        ));
+    }
+    for t in crate::passes::validate::composite_type_order(&cfg.types)?
+        .into_iter()
+        .rev()
+    {
+        push(format!(
+            "DROP TYPE IF EXISTS {};",
+            qualified(&t.schema, &t.name, &t.alt_name)
+        ));
+    }
+    for d in cfg.domains.iter().rev() {
+        push(format!(
+            "DROP DOMAIN IF EXISTS {};",
+            qualified(&d.schema, &d.name, &d.alt_name)
+        ));
+    }
+    for e in cfg.enums.iter().rev() {
+        push(format!(
+            "DROP TYPE IF EXISTS {};",
+            qualified(&e.schema, &e.name, &e.alt_name)
+        ));
+    }
+    for s in cfg.sequences.iter().rev() {
+        push(format!(
+            "DROP SEQUENCE IF EXISTS {};",
+            qualified(&s.schema, &s.name, &s.alt_name)
+        ));
+    }
+    for c in cfg.text_search_configurations.iter().rev() {
+        push(format!(
+            "DROP TEXT SEARCH CONFIGURATION IF EXISTS {};",
+            qualified(&c.schema, &c.name, &c.alt_name)
+        ));
+    }
+    for p in cfg.text_search_parsers.iter().rev() {
+        push(format!(
+            "DROP TEXT SEARCH PARSER IF EXISTS {};",
+            qualified(&p.schema, &p.name, &p.alt_name)
+        ));
+    }
+    for t in cfg.text_search_templates.iter().rev() {
+        push(format!(
+            "DROP TEXT SEARCH TEMPLATE IF EXISTS {};",
+            qualified(&t.schema, &t.name, &t.alt_name)
+        ));
+    }
+    for d in cfg.text_search_dictionaries.iter().rev() {
+        push(format!(
+            "DROP TEXT SEARCH DICTIONARY IF EXISTS {};",
+            qualified(&d.schema, &d.name, &d.alt_name)
+        ));
+    }
+    for c in cfg.collations.iter().rev() {
+        push(format!(
+            "DROP COLLATION IF EXISTS {};",
+            qualified(&c.schema, &c.name, &c.alt_name)
+        ));
+    }
+    for e in cfg.extensions.iter().rev() {
+        let name = e.alt_name.as_deref().unwrap_or(&e.name);
+        push(format!("DROP EXTENSION IF EXISTS {};", pg::ident(name)));
+    }
+    for s in cfg.schemas.iter().rev() {
+        let name = s.alt_name.as_deref().unwrap_or(&s.name);
+        push(format!("DROP SCHEMA IF EXISTS {};", pg::ident(name)));
+    }
+    for t in cfg.tablespaces.iter().rev() {
+        let name = t.alt_name.as_deref().unwrap_or(&t.name);
+        push(format!("DROP TABLESPACE IF EXISTS {};", pg::ident(name)));
+    }
+    for r in cfg.roles.iter().rev() {
+        let name = r.alt_name.as_deref().unwrap_or(&r.name);
+        push(format!("DROP ROLE IF EXISTS {};", pg::ident(name)));
     }
 
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::get_backend_for_version;
+    use crate::test_support::{load_hcl, try_load_hcl};
+    use crate::{generate_with_backend, validate};
+
+    #[test]
+    fn procedure_generates_sql() {
+        let cfg = load_hcl(
+            r#"
+            procedure "archive_orders" {
+              schema = "app"
+              language = "plpgsql"
+              parameters = ["cutoff date"]
+              body = "BEGIN DELETE FROM orders WHERE created_at < cutoff; END;"
+            }
+            "#,
+        );
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains(
+            "CREATE OR REPLACE PROCEDURE \"app\".\"archive_orders\"(cutoff date) LANGUAGE plpgsql AS $$\nBEGIN DELETE FROM orders WHERE created_at < cutoff; END;\n$$;"
+        ));
+
+        let err = try_load_hcl(
+            r#"
+            procedure "bad" {
+              returns = "int"
+              body = "BEGIN END;"
+            }
+            "#,
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("procedure 'bad' cannot declare 'returns'"));
+    }
+
+    #[test]
+    fn legacy_oids_storage_parameter_is_dropped() {
+        let load = |params: &str| {
+            try_load_hcl(&format!(
+                r#"
+                table "events" {{
+                  column "id" {{ type = "int" }}
+                  storage_parameters = {params}
+                }}
+                "#
+            ))
+        };
+
+        let cfg = load("{ OIDS = false, fillfactor = 70 }").unwrap();
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("\n) WITH (fillfactor = 70);"), "{sql}");
+
+        let cfg = load("{ oids = false }").unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(!sql.contains("WITH ("), "{sql}");
+
+        let err = load("{ oids = true }").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("storage parameter 'oids' is no longer supported"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn nulls_not_distinct_requires_postgres_15() {
+        let cfg = load_hcl(
+            r#"
+            table "users" {
+              column "email" { type = "text" }
+              index "users_email_key" {
+                columns            = ["email"]
+                unique             = true
+                nulls_not_distinct = true
+              }
+            }
+            "#,
+        );
+        validate(&cfg, false).unwrap();
+
+        let pg14 = get_backend_for_version("postgres", PgVersion::V14).unwrap();
+        let err = pg14.generate(&cfg, false).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("NULLS NOT DISTINCT requires PostgreSQL 15 or newer"),
+            "{err:#}"
+        );
+
+        let pg15 = get_backend_for_version("postgres", PgVersion::V15).unwrap();
+        let sql = pg15.generate(&cfg, false).unwrap();
+        assert!(
+            sql.contains(
+                "CREATE UNIQUE INDEX IF NOT EXISTS \"users_email_key\" ON \"public\".\"users\" (\"email\") NULLS NOT DISTINCT;"
+            ),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn replace_trigger_drops_and_recreates_before_postgres_14() {
+        let cfg = load_hcl(
+            r#"
+            function "touch" {
+              language = "plpgsql"
+              returns  = "trigger"
+              body     = "BEGIN RETURN NEW; END;"
+            }
+            trigger "users_touch" {
+              table    = "users"
+              function = "touch"
+              replace  = true
+            }
+            "#,
+        );
+        validate(&cfg, false).unwrap();
+
+        let pg12 = get_backend_for_version("postgres", PgVersion::V12).unwrap();
+        let sql = pg12.generate(&cfg, false).unwrap();
+        let drop = sql
+            .find("DROP TRIGGER IF EXISTS \"users_touch\" ON \"public\".\"users\";")
+            .expect(&sql);
+        let create = sql
+            .find("CREATE TRIGGER \"users_touch\"\n    BEFORE UPDATE ON \"public\".\"users\"")
+            .expect(&sql);
+        assert!(drop < create, "{sql}");
+        assert!(!sql.contains("OR REPLACE TRIGGER"), "{sql}");
+
+        let pg14 = get_backend_for_version("postgres", PgVersion::V14).unwrap();
+        let sql = pg14.generate(&cfg, false).unwrap();
+        assert!(
+            sql.contains("CREATE OR REPLACE TRIGGER \"users_touch\"\n    BEFORE UPDATE ON \"public\".\"users\""),
+            "{sql}"
+        );
+        assert!(!sql.contains("pg_trigger"), "{sql}");
+    }
+
+    #[test]
+    fn drop_replace_trigger_drops_before_create() {
+        let cfg = load_hcl(
+            r#"
+            function "touch" {
+              language = "plpgsql"
+              returns  = "trigger"
+              body     = "BEGIN RETURN NEW; END;"
+            }
+            trigger "users_touch" {
+              table    = "users"
+              function = "touch"
+              replace  = "drop"
+            }
+            "#,
+        );
+        validate(&cfg, false).unwrap();
+
+        // Unlike CREATE OR REPLACE, this works on any supported version.
+        let pg12 = get_backend_for_version("postgres", PgVersion::V12).unwrap();
+        let sql = pg12.generate(&cfg, false).unwrap();
+        let drop = sql
+            .find("DROP TRIGGER IF EXISTS \"users_touch\" ON \"public\".\"users\";")
+            .expect(&sql);
+        let create = sql
+            .find("CREATE TRIGGER \"users_touch\"\n    BEFORE UPDATE ON \"public\".\"users\"")
+            .expect(&sql);
+        assert!(drop < create, "{sql}");
+        assert!(!sql.contains("pg_trigger"), "{sql}");
+
+        let err = try_load_hcl(
+            r#"
+            trigger "users_touch" {
+              table    = "users"
+              function = "touch"
+              replace  = "yes"
+            }
+            "#,
+        )
+        .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("replace must be true, false or \"drop\""),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn comments_follow_each_resource() {
+        let cfg = load_hcl(
+            r#"
+            schema "app" {
+              comment = "Application data"
+            }
+            extension "pgcrypto" {
+              comment = "Hashing, it's needed"
+            }
+            enum "status" {
+              schema  = "app"
+              values  = ["active"]
+              comment = "Lifecycle"
+            }
+            function "add" {
+              schema     = "app"
+              language   = "sql"
+              parameters = ["a integer", "b integer"]
+              returns    = "integer"
+              body       = "SELECT a + b"
+              comment    = "Adds"
+            }
+            procedure "archive_orders" {
+              schema     = "app"
+              language   = "plpgsql"
+              parameters = ["cutoff date"]
+              body       = "BEGIN DELETE FROM orders WHERE created_at < cutoff; END;"
+              comment    = "Archives"
+            }
+            "#,
+        );
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        let positions: Vec<usize> = [
+            "CREATE SCHEMA IF NOT EXISTS \"app\"",
+            "COMMENT ON SCHEMA \"app\" IS 'Application data';",
+            "CREATE EXTENSION IF NOT EXISTS \"pgcrypto\"",
+            "COMMENT ON EXTENSION \"pgcrypto\" IS 'Hashing, it''s needed';",
+            "CREATE TYPE \"app\".\"status\"",
+            "COMMENT ON TYPE \"app\".\"status\" IS 'Lifecycle';",
+            "CREATE OR REPLACE FUNCTION \"app\".\"add\"",
+            "COMMENT ON FUNCTION \"app\".\"add\"(a integer, b integer) IS 'Adds';",
+            "CREATE OR REPLACE PROCEDURE \"app\".\"archive_orders\"",
+            "COMMENT ON PROCEDURE \"app\".\"archive_orders\"(cutoff date) IS 'Archives';",
+        ]
+        .iter()
+        .map(|needle| {
+            sql.find(needle)
+                .unwrap_or_else(|| panic!("missing {needle}:\n{sql}"))
+        })
+        .collect();
+        assert!(positions.is_sorted(), "{sql}");
+    }
+
+    #[test]
+    fn extension_version_bump_updates_installed_extension() {
+        let generate = |version: &str| {
+            let cfg = load_hcl(&format!(
+                r#"
+                extension "pgcrypto" {{
+                  version = "{version}"
+                }}
+                extension "citext" {{}}
+                "#
+            ));
+            generate_with_backend("postgres", &cfg, false).unwrap()
+        };
+
+        let before = generate("1.2");
+        let after = generate("1.3");
+        assert!(
+            before.contains(
+                "CREATE EXTENSION IF NOT EXISTS \"pgcrypto\" WITH VERSION '1.2';\n\nDO $$\nBEGIN\n  IF EXISTS (\n    SELECT 1 FROM pg_extension\n    WHERE extname = 'pgcrypto'\n      AND CASE WHEN extversion ~ '^[0-9]+(\\.[0-9]+)*$'\n        THEN string_to_array(extversion, '.')::int[] < ARRAY[1, 2]\n      END\n  ) THEN\n    ALTER EXTENSION \"pgcrypto\" UPDATE TO '1.2';"
+            ),
+            "{before}"
+        );
+        assert!(
+            after.contains("< ARRAY[1, 3]\n      END\n  ) THEN\n    ALTER EXTENSION \"pgcrypto\" UPDATE TO '1.3';"),
+            "{after}"
+        );
+        // Unpinned extensions stay at whatever version is installed.
+        assert!(!after.contains("ALTER EXTENSION \"citext\""), "{after}");
+        // Versions that are not dotted numbers cannot be compared.
+        assert!(!generate("1.0beta").contains("ALTER EXTENSION"));
+    }
+
+    #[test]
+    fn hnsw_vector_index_generates_sql() {
+        let hcl = |dimension: &str, params: &str| {
+            format!(
+                r#"
+                extension "vector" {{}}
+                table "items" {{
+                  column "id" {{ type = "bigint" }}
+                  column "embedding" {{ type = "vector({dimension})" }}
+                  index "items_embedding_idx" {{
+                    columns            = ["embedding"]
+                    operator_classes   = ["vector_cosine_ops"]
+                    method             = "hnsw"
+                    storage_parameters = {params}
+                  }}
+                }}
+                index "items_embedding_ivf" {{
+                  table              = "items"
+                  columns            = ["embedding"]
+                  operator_classes   = ["vector_l2_ops"]
+                  method             = "ivfflat"
+                  storage_parameters = {{ lists = 100 }}
+                }}
+                "#
+            )
+        };
+
+        let cfg = load_hcl(&hcl("1536", "{ m = 16, ef_construction = 64 }"));
+        validate(&cfg, false).unwrap();
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        assert!(sql.contains("\"embedding\" vector(1536)"), "{sql}");
+        assert!(
+            sql.contains(
+                "CREATE INDEX IF NOT EXISTS \"items_embedding_idx\" ON \"public\".\"items\" USING hnsw (\"embedding\" vector_cosine_ops) WITH (m = 16, ef_construction = 64);"
+            ),
+            "{sql}"
+        );
+        assert!(
+            sql.contains(
+                "ON \"public\".\"items\" USING ivfflat (\"embedding\" vector_l2_ops) WITH (lists = 100);"
+            ),
+            "{sql}"
+        );
+
+        let prisma = generate_with_backend("prisma", &cfg, false).unwrap();
+        assert!(!prisma.contains("@@index([embedding])"), "{prisma}");
+
+        let err = validate(&load_hcl(&hcl("1536", "{ lists = 100 }")), false).unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "index 'items_embedding_idx' on table 'items' uses hnsw, which does not accept storage parameter 'lists' (expected m or ef_construction)"
+            ),
+            "{err}"
+        );
+        let err = validate(&load_hcl(&hcl("0", "{ m = 16 }")), false).unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "column 'embedding' on table 'items' has type 'vector(0)': the dimension must be a whole number between 1 and 16000"
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn extension_schema_is_created_before_extension() {
+        let cfg = load_hcl(
+            r#"
+            table "items" {
+              column "id" {
+                type    = "uuid"
+                default = "extensions.gen_random_uuid()"
+              }
+            }
+            extension "pgcrypto" {
+              schema = "extensions"
+            }
+            extension "citext" {
+              schema = "extensions"
+            }
+            extension "ltree" {
+              schema = "app"
+            }
+            schema "app" {}
+            "#,
+        );
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+
+        let schema = sql
+            .find("CREATE SCHEMA IF NOT EXISTS \"extensions\";")
+            .expect(&sql);
+        let pgcrypto = sql
+            .find("CREATE EXTENSION IF NOT EXISTS \"pgcrypto\" WITH SCHEMA \"extensions\";")
+            .expect(&sql);
+        let table = sql.find("CREATE TABLE").expect(&sql);
+        assert!(schema < pgcrypto && pgcrypto < table, "{sql}");
+        assert_eq!(
+            sql.matches("CREATE SCHEMA IF NOT EXISTS \"extensions\"")
+                .count(),
+            1,
+            "{sql}"
+        );
+        // Declared schemas are not created twice.
+        assert_eq!(
+            sql.matches("CREATE SCHEMA IF NOT EXISTS \"app\"").count(),
+            1,
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn row_level_security_is_enabled_after_table() {
+        let cfg = load_hcl(
+            r#"
+            table "docs" {
+              table_name         = "documents"
+              row_level_security = true
+              column "owner" { type = "text" }
+            }
+            table "tags" {
+              column "name" { type = "text" }
+            }
+            "#,
+        );
+        let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+        let table = sql.find("\"public\".\"documents\" (").expect(&sql);
+        let rls = sql
+            .find("ALTER TABLE \"public\".\"documents\" ENABLE ROW LEVEL SECURITY;")
+            .expect(&sql);
+        assert!(table < rls, "{sql}");
+        assert_eq!(sql.matches("ENABLE ROW LEVEL SECURITY").count(), 1, "{sql}");
+    }
+}
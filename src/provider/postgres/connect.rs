@@ -0,0 +1,155 @@
+//! Open Postgres connections, picking a TLS connector from the DSN's `sslmode`.
+
+use anyhow::{Context, Result, bail};
+use log::warn;
+use postgres::config::SslMode;
+use postgres::{Client, NoTls};
+use std::thread;
+use std::time::Duration;
+
+/// Which connector a DSN needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connector {
+    Plain,
+    Tls,
+}
+
+/// Choose a connector for `dsn`.
+///
+/// `sslmode=disable` never uses TLS and `sslmode=require` always does. The
+/// default, `prefer`, uses TLS only when dbschema is built with the `tls`
+/// feature and otherwise falls back to a plain connection.
+pub fn select_connector(dsn: &str) -> Result<Connector> {
+    let config: postgres::Config = dsn.parse().context("parsing DSN")?;
+    Ok(match config.get_ssl_mode() {
+        SslMode::Disable => Connector::Plain,
+        SslMode::Prefer if !cfg!(feature = "tls") => Connector::Plain,
+        _ => Connector::Tls,
+    })
+}
+
+/// Timeout and retry settings applied when opening a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectOptions {
+    /// Give up on a single connection attempt after this long.
+    pub timeout: Duration,
+    /// Total number of attempts before giving up (at least one is made).
+    pub attempts: u32,
+    /// Delay before the first retry; doubled after every failed attempt.
+    pub backoff: Duration,
+}
+
+impl ConnectOptions {
+    pub const DEFAULT: ConnectOptions = ConnectOptions {
+        timeout: Duration::from_secs(10),
+        attempts: 3,
+        backoff: Duration::from_millis(250),
+    };
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Connect to `dsn`, honoring its `sslmode` and retrying with exponential
+/// backoff as configured by `options`.
+pub fn connect(dsn: &str, options: &ConnectOptions) -> Result<Client> {
+    let connector = select_connector(dsn)?;
+    if connector == Connector::Tls && !cfg!(feature = "tls") {
+        bail!(
+            "the DSN requires TLS (sslmode=require), but dbschema was built without the `tls` feature"
+        );
+    }
+    let mut config: postgres::Config = dsn.parse().context("parsing DSN")?;
+    config.connect_timeout(options.timeout);
+
+    let attempts = options.attempts.max(1);
+    let mut delay = options.backoff;
+    let mut attempt = 1;
+    loop {
+        let result = match connector {
+            Connector::Plain => config.connect(NoTls),
+            #[cfg(feature = "tls")]
+            Connector::Tls => config.connect(tls_connector()?),
+            #[cfg(not(feature = "tls"))]
+            Connector::Tls => unreachable!("rejected above"),
+        };
+        match result {
+            Ok(client) => return Ok(client),
+            Err(e) if attempt >= attempts => {
+                return Err(e)
+                    .with_context(|| format!("giving up after {attempts} connection attempt(s)"));
+            }
+            Err(e) => {
+                warn!("connection attempt {attempt}/{attempts} failed: {e}; retrying in {delay:?}");
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tls")]
+fn tls_connector() -> Result<postgres_native_tls::MakeTlsConnector> {
+    let connector = native_tls::TlsConnector::new().context("initializing TLS")?;
+    Ok(postgres_native_tls::MakeTlsConnector::new(connector))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sslmode_selects_connector() {
+        let tls = |dsn: &str| select_connector(dsn).unwrap();
+        assert_eq!(
+            tls("postgres://u@localhost/db?sslmode=require"),
+            Connector::Tls
+        );
+        assert_eq!(
+            tls("host=localhost dbname=db sslmode=require"),
+            Connector::Tls
+        );
+        assert_eq!(
+            tls("postgres://u@localhost/db?sslmode=disable"),
+            Connector::Plain
+        );
+        let default = if cfg!(feature = "tls") {
+            Connector::Tls
+        } else {
+            Connector::Plain
+        };
+        assert_eq!(tls("postgres://u@localhost/db"), default);
+    }
+
+    #[cfg(not(feature = "tls"))]
+    #[test]
+    fn require_without_tls_feature_errors() {
+        let err = connect(
+            "postgres://u@localhost/db?sslmode=require",
+            &ConnectOptions::default(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("`tls` feature"));
+    }
+
+    #[test]
+    fn gives_up_after_configured_attempts() {
+        let options = ConnectOptions {
+            timeout: Duration::from_secs(1),
+            attempts: 2,
+            backoff: Duration::from_millis(1),
+        };
+        let err = connect("postgres://u@127.0.0.1:1/db?sslmode=disable", &options)
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string()
+                .contains("giving up after 2 connection attempt(s)")
+        );
+    }
+}
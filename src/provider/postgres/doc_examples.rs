@@ -0,0 +1,102 @@
+//! Applies the SQL generated for the README and the bundled examples to a
+//! fresh PostgreSQL database, so generation bugs that only surface when the
+//! database runs the statements are caught.
+//!
+//! The crate has no PGlite runtime, so this uses the same PostgreSQL
+//! container as the other live tests (see [`start_pg`]). One container is
+//! started for all examples, and each example gets its own database.
+
+use super::test_support::start_pg;
+use crate::test_support::load_hcl;
+use crate::{generate_with_backend, validate};
+use postgres::{Client, NoTls};
+
+/// Generate SQL for `hcl` and run it in a new database `name` on the server
+/// at `dsn`, returning a client connected to that database.
+fn apply(dsn: &str, name: &str, hcl: &str) -> Client {
+    let cfg = load_hcl(hcl);
+    validate(&cfg, false).unwrap();
+    let sql = generate_with_backend("postgres", &cfg, false).unwrap();
+
+    let mut admin = Client::connect(dsn, NoTls).unwrap();
+    admin
+        .batch_execute(&format!("CREATE DATABASE {name}"))
+        .unwrap();
+    let (server, _) = dsn.rsplit_once('/').unwrap();
+    let mut client = Client::connect(&format!("{server}/{name}"), NoTls).unwrap();
+    client
+        .batch_execute(&sql)
+        .unwrap_or_else(|e| panic!("applying generated SQL for {name} failed: {e}\n{sql}"));
+    client
+}
+
+fn holds(client: &mut Client, query: &str) -> bool {
+    client.query_one(query, &[]).unwrap().get(0)
+}
+
+/// The first ```hcl block of `markdown`.
+fn first_hcl_block(markdown: &str) -> String {
+    let start = markdown.find("```hcl\n").expect("no hcl block") + "```hcl\n".len();
+    let end = markdown[start..].find("```").expect("unterminated hcl block");
+    markdown[start..start + end].to_string()
+}
+
+#[test]
+fn examples_apply() {
+    let (_container, dsn) = start_pg();
+
+    let hcl = first_hcl_block(include_str!("../../../Readme.md"));
+    let mut client = apply(&dsn, "readme", &hcl);
+
+    assert!(holds(
+        &mut client,
+        "SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'pgcrypto')"
+    ));
+    assert!(holds(
+        &mut client,
+        "SELECT to_regtype('public.\"Status\"') IS NOT NULL"
+    ));
+    assert!(holds(
+        &mut client,
+        "SELECT to_regclass('public.users') IS NOT NULL"
+    ));
+    assert!(holds(
+        &mut client,
+        "SELECT to_regclass('public.users_email_key') IS NOT NULL"
+    ));
+    assert!(holds(
+        &mut client,
+        "SELECT to_regproc('public.set_updated_at') IS NOT NULL"
+    ));
+    assert!(holds(
+        &mut client,
+        "SELECT EXISTS (SELECT 1 FROM pg_trigger WHERE tgname = 'users_set_updated_at')"
+    ));
+
+    let examples = [
+        (
+            "table",
+            include_str!("../../../examples/table.hcl"),
+            "SELECT to_regclass('public.users') IS NOT NULL",
+        ),
+        (
+            "enum",
+            include_str!("../../../examples/enum.hcl"),
+            "SELECT to_regtype('public.\"StatusType\"') IS NOT NULL",
+        ),
+        (
+            "function",
+            include_str!("../../../examples/function.hcl"),
+            "SELECT to_regproc('public.now_utc') IS NOT NULL",
+        ),
+        (
+            "trigger",
+            include_str!("../../../examples/trigger.hcl"),
+            "SELECT EXISTS (SELECT 1 FROM pg_trigger WHERE tgname = 'users_updated_at')",
+        ),
+    ];
+    for (name, hcl, check) in examples {
+        let mut client = apply(&dsn, name, hcl);
+        assert!(holds(&mut client, check), "{check}\n{hcl}");
+    }
+}
@@ -0,0 +1,212 @@
+//! `COMMENT ON` statements. Every resource with a `comment` goes through
+//! [`Comment`], so escaping and naming the commented object live in one place.
+
+use std::fmt;
+
+use super::{function_parameter, ident, literal};
+use crate::ir::*;
+
+/// A resource that can carry a `comment`.
+pub trait Commented {
+    fn comment(&self) -> Option<&str>;
+    /// The object as `COMMENT ON` names it, e.g. `TABLE "public"."users"`.
+    fn comment_object(&self) -> String;
+}
+
+#[derive(Debug, Clone)]
+pub struct Comment {
+    pub object: String,
+    pub text: String,
+}
+
+impl Comment {
+    /// The comment statement for `r`, if it has a comment.
+    pub fn of(r: &impl Commented) -> Option<Self> {
+        r.comment().map(|text| Self {
+            object: r.comment_object(),
+            text: text.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Comment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "COMMENT ON {} IS {};", self.object, literal(&self.text))
+    }
+}
+
+/// A column of `table`, commented as `COLUMN "schema"."table"."column"`.
+pub struct ColumnComment<'a> {
+    pub table: &'a TableSpec,
+    pub column: &'a ColumnSpec,
+}
+
+fn schema_of(schema: &Option<String>) -> &str {
+    schema.as_deref().unwrap_or("public")
+}
+
+fn name_of<'a>(name: &'a str, alt_name: &'a Option<String>) -> &'a str {
+    alt_name.as_deref().unwrap_or(name)
+}
+
+/// Objects that live outside any schema, such as roles and extensions.
+macro_rules! global_object {
+    ($($spec:ty => $kind:literal),* $(,)?) => {$(
+        impl Commented for $spec {
+            fn comment(&self) -> Option<&str> {
+                self.comment.as_deref()
+            }
+            fn comment_object(&self) -> String {
+                format!("{} {}", $kind, ident(name_of(&self.name, &self.alt_name)))
+            }
+        }
+    )*};
+}
+
+/// Objects qualified by their schema.
+macro_rules! schema_object {
+    ($($spec:ty => $kind:literal),* $(,)?) => {$(
+        impl Commented for $spec {
+            fn comment(&self) -> Option<&str> {
+                self.comment.as_deref()
+            }
+            fn comment_object(&self) -> String {
+                format!(
+                    "{} {}.{}",
+                    $kind,
+                    ident(schema_of(&self.schema)),
+                    ident(name_of(&self.name, &self.alt_name))
+                )
+            }
+        }
+    )*};
+}
+
+/// Objects named relative to the table they belong to.
+macro_rules! table_object {
+    ($($spec:ty => $kind:literal),* $(,)?) => {$(
+        impl Commented for $spec {
+            fn comment(&self) -> Option<&str> {
+                self.comment.as_deref()
+            }
+            fn comment_object(&self) -> String {
+                format!(
+                    "{} {} ON {}.{}",
+                    $kind,
+                    ident(name_of(&self.name, &self.alt_name)),
+                    ident(schema_of(&self.schema)),
+                    ident(&self.table)
+                )
+            }
+        }
+    )*};
+}
+
+global_object! {
+    RoleSpec => "ROLE",
+    TablespaceSpec => "TABLESPACE",
+    SchemaSpec => "SCHEMA",
+    ExtensionSpec => "EXTENSION",
+    EventTriggerSpec => "EVENT TRIGGER",
+    PublicationSpec => "PUBLICATION",
+    SubscriptionSpec => "SUBSCRIPTION",
+}
+
+schema_object! {
+    CollationSpec => "COLLATION",
+    TextSearchDictionarySpec => "TEXT SEARCH DICTIONARY",
+    TextSearchTemplateSpec => "TEXT SEARCH TEMPLATE",
+    TextSearchParserSpec => "TEXT SEARCH PARSER",
+    TextSearchConfigurationSpec => "TEXT SEARCH CONFIGURATION",
+    SequenceSpec => "SEQUENCE",
+    EnumSpec => "TYPE",
+    DomainSpec => "DOMAIN",
+    CompositeTypeSpec => "TYPE",
+    TableSpec => "TABLE",
+    StatisticsSpec => "STATISTICS",
+    ViewSpec => "VIEW",
+    MaterializedViewSpec => "MATERIALIZED VIEW",
+}
+
+table_object! {
+    PolicySpec => "POLICY",
+    TriggerSpec => "TRIGGER",
+    RuleSpec => "RULE",
+}
+
+impl Commented for FunctionSpec {
+    fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+    fn comment_object(&self) -> String {
+        let signature = self
+            .parameters
+            .iter()
+            .map(|p| function_parameter(p, false))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "FUNCTION {}.{}({signature})",
+            ident(schema_of(&self.schema)),
+            ident(name_of(&self.name, &self.alt_name))
+        )
+    }
+}
+
+impl Commented for ProcedureSpec {
+    fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+    fn comment_object(&self) -> String {
+        format!(
+            "PROCEDURE {}.{}({})",
+            ident(schema_of(&self.schema)),
+            ident(name_of(&self.name, &self.alt_name)),
+            self.parameters.join(", ")
+        )
+    }
+}
+
+impl Commented for AggregateSpec {
+    fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+    fn comment_object(&self) -> String {
+        format!(
+            "AGGREGATE {}.{}({})",
+            ident(schema_of(&self.schema)),
+            ident(name_of(&self.name, &self.alt_name)),
+            self.inputs.join(", ")
+        )
+    }
+}
+
+impl Commented for OperatorSpec {
+    fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+    fn comment_object(&self) -> String {
+        // Operator names are symbols and cannot be quoted.
+        format!(
+            "OPERATOR {}.{} ({}, {})",
+            ident(schema_of(&self.schema)),
+            name_of(&self.name, &self.alt_name),
+            self.left.as_deref().unwrap_or("NONE"),
+            self.right.as_deref().unwrap_or("NONE")
+        )
+    }
+}
+
+impl Commented for ColumnComment<'_> {
+    fn comment(&self) -> Option<&str> {
+        self.column.comment.as_deref()
+    }
+    fn comment_object(&self) -> String {
+        format!(
+            "COLUMN {}.{}.{}",
+            ident(schema_of(&self.table.schema)),
+            ident(name_of(&self.table.name, &self.table.alt_name)),
+            ident(&self.column.name)
+        )
+    }
+}
@@ -1,4 +1,5 @@
 pub mod collation;
+pub mod comment;
 pub mod foreign_data_wrapper;
 pub mod foreign_server;
 pub mod foreign_table;
@@ -6,7 +7,10 @@ pub mod text_search;
 
 use std::fmt;
 
+use crate::ir::TriggerCreate;
+
 pub use collation::Collation;
+pub use comment::{ColumnComment, Comment, Commented};
 pub use foreign_data_wrapper::ForeignDataWrapper;
 pub use foreign_server::ForeignServer;
 pub use foreign_table::ForeignTable;
@@ -24,12 +28,40 @@ pub fn literal(s: &str) -> String {
     format!("'{}'", escaped)
 }
 
+/// Render a storage parameter value: numbers and booleans verbatim, anything
+/// else as a quoted literal.
+pub fn storage_value(v: &hcl::Value) -> String {
+    match v {
+        hcl::Value::Number(n) => n.to_string(),
+        hcl::Value::Bool(b) => b.to_string(),
+        hcl::Value::String(s) => literal(s),
+        other => literal(&other.to_string()),
+    }
+}
+
+/// `(key, rendered value)` pairs for a `WITH (...)` clause.
+fn storage_parameters(params: &hcl::Map<String, hcl::Value>) -> Vec<(String, String)> {
+    params
+        .iter()
+        .map(|(k, v)| (k.clone(), storage_value(v)))
+        .collect()
+}
+
 pub fn format_type_name(raw: &str) -> String {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
         return raw.to_string();
     }
 
+    // Format the element type of an array, keeping the `[]` suffixes.
+    if let Some(idx) = trimmed.find('[') {
+        let (base, suffix) = trimmed.split_at(idx);
+        let dims_only = suffix.chars().all(|c| matches!(c, '[' | ']' | '0'..='9'));
+        if !base.is_empty() && dims_only {
+            return format!("{}{}", format_type_name(base), suffix);
+        }
+    }
+
     // Leave complex type expressions unchanged.
     if trimmed.contains(' ') || trimmed.contains('(') || trimmed.contains(')') || trimmed.contains("::") || trimmed.contains('[') {
         return raw.to_string();
@@ -82,7 +114,21 @@ pub struct Role {
     pub createrole: bool,
     pub replication: bool,
     pub password: Option<String>,
+    pub valid_until: Option<String>,
     pub in_role: Vec<String>,
+    pub config: Vec<(String, String)>,
+}
+
+/// Whether a role password is already a SCRAM or MD5 hash as stored in
+/// `pg_authid`. Postgres recognises these and stores them unchanged, and as
+/// they never contain quotes the `PASSWORD` literal carries them verbatim.
+pub fn is_password_hash(password: &str) -> bool {
+    if password.starts_with("SCRAM-SHA-256$") {
+        return true;
+    }
+    password
+        .strip_prefix("md5")
+        .is_some_and(|h| h.len() == 32 && h.chars().all(|c| c.is_ascii_hexdigit()))
 }
 
 impl From<&crate::ir::RoleSpec> for Role {
@@ -95,7 +141,13 @@ impl From<&crate::ir::RoleSpec> for Role {
             createrole: r.createrole,
             replication: r.replication,
             password: r.password.clone(),
+            valid_until: r.valid_until.clone(),
             in_role: r.in_role.clone(),
+            config: r
+                .config
+                .iter()
+                .map(|(k, v)| (k.clone(), storage_value(v)))
+                .collect(),
         }
     }
 }
@@ -121,6 +173,9 @@ impl fmt::Display for Role {
         if let Some(pw) = &self.password {
             parts.push(format!("PASSWORD {}", literal(pw)));
         }
+        if let Some(until) = &self.valid_until {
+            parts.push(format!("VALID UNTIL {}", literal(until)));
+        }
         if !self.in_role.is_empty() {
             let roles = self
                 .in_role
@@ -141,7 +196,11 @@ impl fmt::Display for Role {
             name_lit = literal(&self.name),
             name_ident = ident(&self.name),
             attrs = attrs,
-        )
+        )?;
+        for (key, value) in &self.config {
+            write!(f, "\nALTER ROLE {} SET {key} = {value};", ident(&self.name))?;
+        }
+        Ok(())
     }
 }
 
@@ -201,6 +260,31 @@ impl From<&crate::ir::ExtensionSpec> for Extension {
     }
 }
 
+impl Extension {
+    /// `ALTER EXTENSION ... UPDATE TO` a pinned version, run only when the
+    /// installed version is older. `CREATE EXTENSION IF NOT EXISTS` leaves an
+    /// installed extension alone, so a version bump needs this to upgrade it.
+    /// Lowering the pin never downgrades. Versions are compared as dotted
+    /// numbers, so a version such as `1.0beta` is never updated.
+    pub fn update_sql(&self) -> Option<String> {
+        let version = self.version.as_ref().filter(|_| self.if_not_exists)?;
+        let parts: Vec<&str> = version.split('.').collect();
+        if !parts
+            .iter()
+            .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return None;
+        }
+        Some(format!(
+            "DO $$\nBEGIN\n  IF EXISTS (\n    SELECT 1 FROM pg_extension\n    WHERE extname = {name_lit}\n      AND CASE WHEN extversion ~ '^[0-9]+(\\.[0-9]+)*$'\n        THEN string_to_array(extversion, '.')::int[] < ARRAY[{parts}]\n      END\n  ) THEN\n    ALTER EXTENSION {name} UPDATE TO {version};\n  END IF;\nEND$$;",
+            name_lit = literal(&self.name),
+            parts = parts.join(", "),
+            name = ident(&self.name),
+            version = literal(version),
+        ))
+    }
+}
+
 impl fmt::Display for Extension {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "CREATE EXTENSION ")?;
@@ -241,14 +325,22 @@ impl From<&crate::ir::SchemaSpec> for Schema {
 
 impl fmt::Display for Schema {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // With an owner, create the schema only if missing and then always
+        // reassert ownership, so re-applying fixes a drifted owner.
+        if let Some(auth) = &self.authorization {
+            return write!(
+                f,
+                "DO $$\nBEGIN\n  IF NOT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = {name_lit}) THEN\n    CREATE SCHEMA {name_ident} AUTHORIZATION {owner};\n  END IF;\nEND$$;\nALTER SCHEMA {name_ident} OWNER TO {owner};",
+                name_lit = literal(&self.name),
+                name_ident = ident(&self.name),
+                owner = ident(auth),
+            );
+        }
         if self.if_not_exists {
             write!(f, "CREATE SCHEMA IF NOT EXISTS {}", ident(&self.name))?;
         } else {
             write!(f, "CREATE SCHEMA {}", ident(&self.name))?;
         }
-        if let Some(auth) = &self.authorization {
-            write!(f, " AUTHORIZATION {}", ident(auth))?;
-        }
         write!(f, ";")
     }
 }
@@ -409,7 +501,7 @@ impl fmt::Display for CompositeType {
         let fields = self
             .fields
             .iter()
-            .map(|c| format!("{} {}", ident(&c.name), c.r#type))
+            .map(|c| format!("{} {}", ident(&c.name), format_type_name(&c.r#type)))
             .collect::<Vec<_>>()
             .join(", ");
         write!(
@@ -429,6 +521,7 @@ pub struct Enum {
     pub schema: String,
     pub name: String,
     pub values: Vec<String>,
+    pub evolve: bool,
 }
 
 impl From<&crate::ir::EnumSpec> for Enum {
@@ -437,10 +530,40 @@ impl From<&crate::ir::EnumSpec> for Enum {
             schema: e.schema.clone().unwrap_or_else(|| "public".to_string()),
             name: e.alt_name.clone().unwrap_or_else(|| e.name.clone()),
             values: e.values.clone(),
+            evolve: e.evolve,
         }
     }
 }
 
+impl Enum {
+    /// `ALTER TYPE ... ADD VALUE IF NOT EXISTS` for every value, each placed
+    /// after its predecessor so values inserted mid-list keep their order.
+    /// Empty unless `evolve` is set. These must stay top-level statements:
+    /// `ADD VALUE` is not allowed inside a `DO` block.
+    pub fn add_value_sql(&self) -> Vec<String> {
+        if !self.evolve {
+            return Vec::new();
+        }
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let position = match i {
+                    0 => String::new(),
+                    _ => format!(" AFTER {}", literal(&self.values[i - 1])),
+                };
+                format!(
+                    "ALTER TYPE {}.{} ADD VALUE IF NOT EXISTS {}{};",
+                    ident(&self.schema),
+                    ident(&self.name),
+                    literal(v),
+                    position
+                )
+            })
+            .collect()
+    }
+}
+
 impl fmt::Display for Enum {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let values = self
@@ -577,7 +700,7 @@ impl fmt::Display for Operator {
         if let Some(r) = &self.right {
             parts.push(format!("RIGHTARG = {}", r));
         }
-        parts.push(format!("PROCEDURE = {}", self.procedure));
+        parts.push(format!("FUNCTION = {}", self.procedure));
         if let Some(c) = &self.commutator {
             parts.push(format!("COMMUTATOR = {}", c));
         }
@@ -590,23 +713,49 @@ impl fmt::Display for Operator {
         if let Some(j) = &self.join {
             parts.push(format!("JOIN = {}", j));
         }
+        // Operator names are symbols and cannot be quoted.
         write!(
             f,
             "CREATE OPERATOR {}.{} ({});",
             ident(&self.schema),
-            ident(&self.name),
+            self.name,
             parts.join(", ")
         )
     }
 }
 
+/// Render a function parameter as `[MODE] [name] type [DEFAULT expr]`.
+/// Signatures used to identify the function (`DROP FUNCTION`, `COMMENT ON`)
+/// must not carry defaults, so those pass `with_default = false`.
+pub fn function_parameter(p: &crate::ir::FunctionParameterSpec, with_default: bool) -> String {
+    let mut out = String::new();
+    if let Some(mode) = &p.mode {
+        out.push_str(&mode.to_uppercase());
+        out.push(' ');
+    }
+    if let Some(name) = &p.name {
+        out.push_str(name);
+        out.push(' ');
+    }
+    out.push_str(&p.r#type);
+    if with_default && let Some(default) = &p.default {
+        out.push_str(" DEFAULT ");
+        out.push_str(default);
+    }
+    out
+}
+
 impl From<&crate::ir::FunctionSpec> for Function {
     fn from(f: &crate::ir::FunctionSpec) -> Self {
         Self {
             schema: f.schema.clone().unwrap_or_else(|| "public".to_string()),
             name: f.alt_name.clone().unwrap_or_else(|| f.name.clone()),
             language: f.language.clone(),
-            parameters: f.parameters.clone(),
+            parameters: f
+                .parameters
+                .iter()
+                .map(|p| function_parameter(p, true))
+                .collect(),
             returns: f.returns.clone(),
             replace: f.replace,
             volatility: f.volatility.clone(),
@@ -696,8 +845,10 @@ impl fmt::Display for Procedure {
 pub struct View {
     pub schema: String,
     pub name: String,
+    pub columns: Vec<String>,
     pub sql: String,
     pub replace: bool,
+    pub check_option: Option<String>,
 }
 
 impl From<&crate::ir::ViewSpec> for View {
@@ -705,8 +856,10 @@ impl From<&crate::ir::ViewSpec> for View {
         Self {
             schema: v.schema.clone().unwrap_or_else(|| "public".to_string()),
             name: v.alt_name.clone().unwrap_or_else(|| v.name.clone()),
+            columns: v.columns.clone(),
             sql: v.sql.clone(),
             replace: v.replace,
+            check_option: v.check_option.clone(),
         }
     }
 }
@@ -714,13 +867,28 @@ impl From<&crate::ir::ViewSpec> for View {
 impl fmt::Display for View {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let or_replace = if self.replace { "OR REPLACE " } else { "" };
+        let columns = if self.columns.is_empty() {
+            String::new()
+        } else {
+            let cols = self.columns.iter().map(|c| ident(c)).collect::<Vec<_>>();
+            format!(" ({})", cols.join(", "))
+        };
+        let body = match &self.check_option {
+            Some(opt) => format!(
+                "{}\nWITH {} CHECK OPTION",
+                self.sql.trim_end(),
+                opt.to_uppercase()
+            ),
+            None => self.sql.clone(),
+        };
         write!(
             f,
-            "CREATE {or_replace}VIEW {schema}.{name} AS\n{body};",
+            "CREATE {or_replace}VIEW {schema}.{name}{columns} AS\n{body};",
             or_replace = or_replace,
             schema = ident(&self.schema),
             name = ident(&self.name),
-            body = self.sql,
+            columns = columns,
+            body = body,
         )
     }
 }
@@ -769,6 +937,7 @@ pub struct Column {
     pub name: String,
     pub r#type: String,
     pub db_type: Option<String>,
+    pub collation: Option<String>,
     pub nullable: bool,
     pub default: Option<String>,
 }
@@ -779,6 +948,7 @@ impl From<&crate::ir::ColumnSpec> for Column {
             name: c.name.clone(),
             r#type: c.r#type.clone(),
             db_type: c.db_type.clone(),
+            collation: c.collation.clone(),
             nullable: c.nullable,
             default: c.default.clone(),
         }
@@ -793,6 +963,9 @@ impl fmt::Display for Column {
             .map(|s| s.clone())
             .unwrap_or_else(|| format_type_name(&self.r#type));
         write!(f, "{} {}", ident(&self.name), data_type)?;
+        if let Some(c) = &self.collation {
+            write!(f, " COLLATE {}", ident(c))?;
+        }
         if !self.nullable {
             write!(f, " NOT NULL")?;
         }
@@ -833,6 +1006,66 @@ impl fmt::Display for PrimaryKey {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct ExclusionConstraint {
+    pub name: Option<String>,
+    pub method: String,
+    pub elements: Vec<ExclusionElement>,
+    pub r#where: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExclusionElement {
+    pub column: Option<String>,
+    pub expression: Option<String>,
+    pub operator: String,
+}
+
+impl From<&crate::ir::ExclusionConstraintSpec> for ExclusionConstraint {
+    fn from(e: &crate::ir::ExclusionConstraintSpec) -> Self {
+        Self {
+            name: e.name.clone(),
+            method: e.method.clone(),
+            elements: e
+                .elements
+                .iter()
+                .map(|el| ExclusionElement {
+                    column: el.column.clone(),
+                    expression: el.expression.clone(),
+                    operator: el.operator.clone(),
+                })
+                .collect(),
+            r#where: e.r#where.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ExclusionConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elements = self
+            .elements
+            .iter()
+            .map(|el| {
+                let target = match (&el.column, &el.expression) {
+                    (Some(c), _) => ident(c),
+                    (None, Some(e)) => format!("({e})"),
+                    (None, None) => String::new(),
+                };
+                format!("{target} WITH {}", el.operator)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Some(n) = &self.name {
+            write!(f, "CONSTRAINT {} ", ident(n))?;
+        }
+        write!(f, "EXCLUDE USING {} ({})", self.method, elements)?;
+        if let Some(w) = &self.r#where {
+            write!(f, " WHERE ({})", w)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ForeignKey {
     pub name: Option<String>,
@@ -840,8 +1073,11 @@ pub struct ForeignKey {
     pub ref_schema: String,
     pub ref_table: String,
     pub ref_columns: Vec<String>,
+    pub r#match: Option<String>,
     pub on_delete: Option<String>,
     pub on_update: Option<String>,
+    pub deferrable: bool,
+    pub initially_deferred: bool,
 }
 
 impl From<&crate::ir::ForeignKeySpec> for ForeignKey {
@@ -855,8 +1091,11 @@ impl From<&crate::ir::ForeignKeySpec> for ForeignKey {
                 .unwrap_or_else(|| "public".to_string()),
             ref_table: fk.ref_table.clone(),
             ref_columns: fk.ref_columns.clone(),
+            r#match: fk.r#match.clone(),
             on_delete: fk.on_delete.clone(),
             on_update: fk.on_update.clone(),
+            deferrable: fk.deferrable,
+            initially_deferred: fk.initially_deferred,
         }
     }
 }
@@ -886,12 +1125,23 @@ impl fmt::Display for ForeignKey {
             rtable = ident(&self.ref_table),
             rcols = ref_cols,
         )?;
+        if let Some(m) = &self.r#match {
+            write!(f, " MATCH {}", m.to_uppercase())?;
+        }
         if let Some(od) = &self.on_delete {
             write!(f, " ON DELETE {}", od)?;
         }
         if let Some(ou) = &self.on_update {
             write!(f, " ON UPDATE {}", ou)?;
         }
+        if self.deferrable {
+            let initially = if self.initially_deferred {
+                "DEFERRED"
+            } else {
+                "IMMEDIATE"
+            };
+            write!(f, " DEFERRABLE INITIALLY {}", initially)?;
+        }
         Ok(())
     }
 }
@@ -933,9 +1183,12 @@ pub struct Table {
     pub if_not_exists: bool,
     pub columns: Vec<Column>,
     pub primary_key: Option<PrimaryKey>,
+    pub exclusions: Vec<ExclusionConstraint>,
     pub foreign_keys: Vec<ForeignKey>,
     pub partition_by: Option<PartitionBy>,
     pub partitions: Vec<Partition>,
+    pub storage_parameters: Vec<(String, String)>,
+    pub tablespace: Option<String>,
 }
 
 impl From<&crate::ir::TableSpec> for Table {
@@ -946,9 +1199,12 @@ impl From<&crate::ir::TableSpec> for Table {
             if_not_exists: t.if_not_exists,
             columns: t.columns.iter().map(Column::from).collect(),
             primary_key: t.primary_key.as_ref().map(PrimaryKey::from),
+            exclusions: t.exclusions.iter().map(ExclusionConstraint::from).collect(),
             foreign_keys: t.foreign_keys.iter().map(ForeignKey::from).collect(),
             partition_by: t.partition_by.as_ref().map(PartitionBy::from),
             partitions: t.partitions.iter().map(Partition::from).collect(),
+            storage_parameters: storage_parameters(&t.storage_parameters),
+            tablespace: t.tablespace.clone(),
         }
     }
 }
@@ -959,6 +1215,9 @@ impl fmt::Display for Table {
         if let Some(pk) = &self.primary_key {
             lines.push(format!("{}", pk));
         }
+        for ex in &self.exclusions {
+            lines.push(format!("{}", ex));
+        }
         for fk in &self.foreign_keys {
             lines.push(format!("{}", fk));
         }
@@ -989,13 +1248,24 @@ impl fmt::Display for Table {
                 .join(", ");
             write!(
                 f,
-                " PARTITION BY {strategy} ({cols});",
+                " PARTITION BY {strategy} ({cols})",
                 strategy = pb.strategy,
                 cols = cols
             )?;
-        } else {
-            write!(f, ";")?;
         }
+        if !self.storage_parameters.is_empty() {
+            let params = self
+                .storage_parameters
+                .iter()
+                .map(|(k, v)| format!("{k} = {v}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, " WITH ({params})")?;
+        }
+        if let Some(ts) = &self.tablespace {
+            write!(f, " TABLESPACE {}", ident(ts))?;
+        }
+        write!(f, ";")?;
         for p in &self.partitions {
             write!(
                 f,
@@ -1021,6 +1291,11 @@ pub struct Index {
     pub orders: Vec<String>,
     pub operator_classes: Vec<String>,
     pub unique: bool,
+    pub nulls_not_distinct: bool,
+    pub concurrently: bool,
+    pub method: Option<String>,
+    pub storage_parameters: Vec<(String, String)>,
+    pub tablespace: Option<String>,
 }
 
 impl Index {
@@ -1035,6 +1310,33 @@ impl Index {
             orders: idx.orders.clone(),
             operator_classes: idx.operator_classes.clone(),
             unique: idx.unique,
+            nulls_not_distinct: idx.nulls_not_distinct,
+            concurrently: idx.concurrently,
+            method: idx.method.clone(),
+            storage_parameters: storage_parameters(&idx.storage_parameters),
+            tablespace: idx.tablespace.clone(),
+        }
+    }
+
+    pub fn from_materialized(
+        mv: &crate::ir::MaterializedViewSpec,
+        idx: &crate::ir::IndexSpec,
+    ) -> Self {
+        Self {
+            table_schema: mv.schema.clone().unwrap_or_else(|| "public".to_string()),
+            table_name: mv.alt_name.clone().unwrap_or_else(|| mv.name.clone()),
+            name: idx.name.clone(),
+            columns: idx.columns.clone(),
+            expressions: idx.expressions.clone(),
+            r#where: idx.r#where.clone(),
+            orders: idx.orders.clone(),
+            operator_classes: idx.operator_classes.clone(),
+            unique: idx.unique,
+            nulls_not_distinct: idx.nulls_not_distinct,
+            concurrently: idx.concurrently,
+            method: idx.method.clone(),
+            storage_parameters: storage_parameters(&idx.storage_parameters),
+            tablespace: idx.tablespace.clone(),
         }
     }
 
@@ -1049,6 +1351,11 @@ impl Index {
             orders: idx.orders.clone(),
             operator_classes: idx.operator_classes.clone(),
             unique: idx.unique,
+            nulls_not_distinct: idx.nulls_not_distinct,
+            concurrently: idx.concurrently,
+            method: idx.method.clone(),
+            storage_parameters: storage_parameters(&idx.storage_parameters),
+            tablespace: idx.tablespace.clone(),
         }
     }
 }
@@ -1091,6 +1398,11 @@ impl fmt::Display for Index {
             .collect::<Vec<_>>()
             .join(", ");
         let unique = if self.unique { "UNIQUE " } else { "" };
+        let concurrently = if self.concurrently {
+            "CONCURRENTLY "
+        } else {
+            ""
+        };
         let name = match &self.name {
             Some(n) => ident(n),
             None => {
@@ -1104,13 +1416,37 @@ impl fmt::Display for Index {
                 ident(&n)
             }
         };
+        let nulls = if self.nulls_not_distinct {
+            " NULLS NOT DISTINCT"
+        } else {
+            ""
+        };
+        let method = match &self.method {
+            Some(m) => format!(" USING {m}"),
+            None => String::new(),
+        };
+        let with = if self.storage_parameters.is_empty() {
+            String::new()
+        } else {
+            let params = self
+                .storage_parameters
+                .iter()
+                .map(|(k, v)| format!("{k} = {v}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(" WITH ({params})")
+        };
+        let tablespace = match &self.tablespace {
+            Some(ts) => format!(" TABLESPACE {}", ident(ts)),
+            None => String::new(),
+        };
         let where_clause = match &self.r#where {
             Some(w) => format!(" WHERE {w}"),
             None => String::new(),
         };
         write!(
             f,
-            "CREATE {unique}INDEX IF NOT EXISTS {name} ON {schema}.{table} ({cols}){where_clause};",
+            "CREATE {unique}INDEX {concurrently}IF NOT EXISTS {name} ON {schema}.{table}{method} ({cols}){nulls}{with}{tablespace}{where_clause};",
             unique = unique,
             name = name,
             schema = ident(&self.table_schema),
@@ -1176,10 +1512,19 @@ pub struct Trigger {
     pub name: String,
     pub timing: String,
     pub events: Vec<String>,
+    pub update_columns: Vec<String>,
     pub level: String,
     pub function: String,
     pub function_schema: String,
     pub when: Option<String>,
+    pub referencing_old_table: Option<String>,
+    pub referencing_new_table: Option<String>,
+    pub constraint: bool,
+    pub deferrable: bool,
+    pub initially_deferred: bool,
+    /// A plain `CREATE TRIGGER` for [`TriggerCreate::DropAndCreate`] is
+    /// meant to follow [`Trigger::drop_sql`].
+    pub create: TriggerCreate,
 }
 
 impl From<&crate::ir::TriggerSpec> for Trigger {
@@ -1190,6 +1535,7 @@ impl From<&crate::ir::TriggerSpec> for Trigger {
             name: t.alt_name.clone().unwrap_or_else(|| t.name.clone()),
             timing: t.timing.clone(),
             events: t.events.clone(),
+            update_columns: t.update_columns.clone(),
             level: t.level.clone(),
             function: t.function.clone(),
             function_schema: t
@@ -1197,16 +1543,46 @@ impl From<&crate::ir::TriggerSpec> for Trigger {
                 .clone()
                 .unwrap_or_else(|| t.schema.clone().unwrap_or_else(|| "public".to_string())),
             when: t.when.clone(),
+            referencing_old_table: t.referencing_old_table.clone(),
+            referencing_new_table: t.referencing_new_table.clone(),
+            constraint: t.constraint,
+            deferrable: t.deferrable,
+            initially_deferred: t.initially_deferred,
+            create: t.create,
         }
     }
 }
 
+impl Trigger {
+    pub fn drop_sql(&self) -> String {
+        format!(
+            "DROP TRIGGER IF EXISTS {} ON {}.{};",
+            ident(&self.name),
+            ident(&self.schema),
+            ident(&self.table)
+        )
+    }
+}
+
 impl fmt::Display for Trigger {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let events = self
             .events
             .iter()
-            .map(|e| e.to_uppercase())
+            .map(|e| {
+                let e = e.to_uppercase();
+                if e == "UPDATE" && !self.update_columns.is_empty() {
+                    let cols = self
+                        .update_columns
+                        .iter()
+                        .map(|c| ident(c))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("UPDATE OF {cols}")
+                } else {
+                    e
+                }
+            })
             .collect::<Vec<_>>()
             .join(" OR ");
         let when = self
@@ -1214,21 +1590,48 @@ impl fmt::Display for Trigger {
             .as_ref()
             .map(|w| format!("\n    WHEN ({})", w))
             .unwrap_or_default();
-        write!(
-            f,
-            "DO $$\nBEGIN\n  IF NOT EXISTS (\n    SELECT 1 FROM pg_trigger tg\n    JOIN pg_class c ON c.oid = tg.tgrelid\n    JOIN pg_namespace n ON n.oid = c.relnamespace\n    WHERE tg.tgname = {tgname}\n      AND n.nspname = {schema_lit}\n      AND c.relname = {table_lit}\n  ) THEN\n    CREATE TRIGGER {tg}\n    {timing} {events} ON {schema_ident}.{table_ident}\n    FOR EACH {for_each}{when}\n    EXECUTE FUNCTION {fn_schema_ident}.{fn_name}();\n  END IF;\nEND$$;",
-            tgname = literal(&self.name),
-            schema_lit = literal(&self.schema),
-            table_lit = literal(&self.table),
+        let mut transition = Vec::new();
+        if let Some(old) = &self.referencing_old_table {
+            transition.push(format!("OLD TABLE AS {}", ident(old)));
+        }
+        if let Some(new) = &self.referencing_new_table {
+            transition.push(format!("NEW TABLE AS {}", ident(new)));
+        }
+        let referencing = if transition.is_empty() {
+            String::new()
+        } else {
+            format!("\n    REFERENCING {}", transition.join(" "))
+        };
+        let constraint = if self.constraint { "CONSTRAINT " } else { "" };
+        let deferrable = match (self.deferrable, self.initially_deferred) {
+            (false, _) => "",
+            (true, false) => "\n    DEFERRABLE INITIALLY IMMEDIATE",
+            (true, true) => "\n    DEFERRABLE INITIALLY DEFERRED",
+        };
+        let or_replace = if self.create == TriggerCreate::CreateOrReplace {
+            "OR REPLACE "
+        } else {
+            ""
+        };
+        let statement = format!(
+            "CREATE {or_replace}{constraint}TRIGGER {tg}\n    {timing} {events} ON {schema_ident}.{table_ident}{deferrable}{referencing}\n    FOR EACH {for_each}{when}\n    EXECUTE FUNCTION {fn_schema_ident}.{fn_name}();",
             tg = ident(&self.name),
             timing = self.timing.to_uppercase(),
-            events = events,
             for_each = self.level.to_uppercase(),
-            when = when,
             schema_ident = ident(&self.schema),
             table_ident = ident(&self.table),
             fn_schema_ident = ident(&self.function_schema),
             fn_name = ident(&self.function),
+        );
+        if self.create != TriggerCreate::Create {
+            return f.write_str(&statement);
+        }
+        write!(
+            f,
+            "DO $$\nBEGIN\n  IF NOT EXISTS (\n    SELECT 1 FROM pg_trigger tg\n    JOIN pg_class c ON c.oid = tg.tgrelid\n    JOIN pg_namespace n ON n.oid = c.relnamespace\n    WHERE tg.tgname = {tgname}\n      AND n.nspname = {schema_lit}\n      AND c.relname = {table_lit}\n  ) THEN\n    {statement}\n  END IF;\nEND$$;",
+            tgname = literal(&self.name),
+            schema_lit = literal(&self.schema),
+            table_lit = literal(&self.table),
         )
     }
 }
@@ -1242,6 +1645,7 @@ pub struct Rule {
     pub r#where: Option<String>,
     pub instead: bool,
     pub command: String,
+    pub replace: bool,
 }
 
 impl From<&crate::ir::RuleSpec> for Rule {
@@ -1254,6 +1658,7 @@ impl From<&crate::ir::RuleSpec> for Rule {
             r#where: r.r#where.clone(),
             instead: r.instead,
             command: r.command.clone(),
+            replace: r.replace,
         }
     }
 }
@@ -1266,16 +1671,24 @@ impl fmt::Display for Rule {
             .map(|w| format!(" WHERE {}", w))
             .unwrap_or_default();
         let action = if self.instead { "INSTEAD" } else { "ALSO" };
+        // `NOTHING` is a keyword, not a command, and cannot be parenthesized.
+        let command = if self.command.trim().eq_ignore_ascii_case("nothing") {
+            "NOTHING".to_string()
+        } else {
+            format!("({})", self.command.trim().trim_end_matches(';'))
+        };
+        let or_replace = if self.replace { "OR REPLACE " } else { "" };
         write!(
             f,
-            "CREATE RULE {name} AS ON {event} TO {schema}.{table}{where_clause} DO {action} {command};",
+            "CREATE {or_replace}RULE {name} AS ON {event} TO {schema}.{table}{where_clause} DO {action} {command};",
+            or_replace = or_replace,
             name = ident(&self.name),
             event = self.event.to_uppercase(),
             schema = ident(&self.schema),
             table = ident(&self.table),
             where_clause = where_clause,
             action = action,
-            command = self.command,
+            command = command,
         )
     }
 }
@@ -1413,9 +1826,13 @@ pub struct Grant {
     pub privileges: Vec<String>,
     pub schema: Option<String>,
     pub table: Option<String>,
+    pub columns: Vec<String>,
     pub function: Option<String>,
     pub database: Option<String>,
     pub sequence: Option<String>,
+    pub all_in_schema: Option<String>,
+    pub with_grant_option: bool,
+    pub revoke: bool,
 }
 
 impl From<&crate::ir::GrantSpec> for Grant {
@@ -1425,9 +1842,13 @@ impl From<&crate::ir::GrantSpec> for Grant {
             privileges: g.privileges.clone(),
             schema: g.schema.clone(),
             table: g.table.clone(),
+            columns: g.columns.clone(),
             function: g.function.clone(),
             database: g.database.clone(),
             sequence: g.sequence.clone(),
+            all_in_schema: g.all_in_schema.clone(),
+            with_grant_option: g.with_grant_option,
+            revoke: g.revoke,
         }
     }
 }
@@ -1533,67 +1954,118 @@ impl fmt::Display for Subscription {
     }
 }
 
-impl fmt::Display for Grant {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let privs = if self.privileges.len() == 1 && self.privileges[0].eq_ignore_ascii_case("all")
-        {
-            "ALL PRIVILEGES".to_string()
+impl Grant {
+    /// Privilege list; with column-level grants each privilege carries the
+    /// column list, as in `SELECT ("a", "b"), UPDATE ("a", "b")`.
+    fn privileges_sql(&self) -> String {
+        let columns = if self.columns.is_empty() {
+            String::new()
+        } else {
+            let cols = self
+                .columns
+                .iter()
+                .map(|c| ident(c))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(" ({cols})")
+        };
+        if self.privileges.len() == 1 && self.privileges[0].eq_ignore_ascii_case("all") {
+            format!("ALL PRIVILEGES{columns}")
         } else {
             self.privileges
                 .iter()
-                .map(|p| p.to_uppercase())
+                .map(|p| format!("{}{columns}", p.to_uppercase()))
                 .collect::<Vec<_>>()
                 .join(", ")
-        };
-        let role = ident(&self.role);
-        if let Some(table) = &self.table {
-            let schema = self.schema.clone().unwrap_or_else(|| "public".to_string());
-            write!(
-                f,
-                "GRANT {privs} ON TABLE {schema_ident}.{table_ident} TO {role};",
-                privs = privs,
-                schema_ident = ident(&schema),
-                table_ident = ident(table),
-                role = role,
-            )
+        }
+    }
+
+    /// The `ON ...` target of the grant, or `None` if no object is set.
+    fn object_sql(&self) -> Option<String> {
+        let schema = || self.schema.clone().unwrap_or_else(|| "public".to_string());
+        if let Some(kind) = &self.all_in_schema {
+            Some(format!(
+                "ALL {} IN SCHEMA {}",
+                kind.to_uppercase(),
+                ident(&schema())
+            ))
+        } else if let Some(table) = &self.table {
+            Some(format!("TABLE {}.{}", ident(&schema()), ident(table)))
         } else if let Some(function) = &self.function {
-            let schema = self.schema.clone().unwrap_or_else(|| "public".to_string());
-            write!(
-                f,
-                "GRANT {privs} ON FUNCTION {schema_ident}.{fn_ident}() TO {role};",
-                privs = privs,
-                schema_ident = ident(&schema),
-                fn_ident = ident(function),
-                role = role,
-            )
+            Some(format!(
+                "FUNCTION {}.{}()",
+                ident(&schema()),
+                ident(function)
+            ))
         } else if let Some(sequence) = &self.sequence {
-            let schema = self.schema.clone().unwrap_or_else(|| "public".to_string());
-            write!(
-                f,
-                "GRANT {privs} ON SEQUENCE {schema_ident}.{seq_ident} TO {role};",
-                privs = privs,
-                schema_ident = ident(&schema),
-                seq_ident = ident(sequence),
-                role = role,
-            )
+            Some(format!("SEQUENCE {}.{}", ident(&schema()), ident(sequence)))
         } else if let Some(schema) = &self.schema {
-            write!(
-                f,
-                "GRANT {privs} ON SCHEMA {schema_ident} TO {role};",
-                privs = privs,
-                schema_ident = ident(schema),
-                role = role,
-            )
-        } else if let Some(database) = &self.database {
-            write!(
-                f,
-                "GRANT {privs} ON DATABASE {db_ident} TO {role};",
-                privs = privs,
-                db_ident = ident(database),
-                role = role,
-            )
+            Some(format!("SCHEMA {}", ident(schema)))
         } else {
-            Ok(())
+            self.database
+                .as_ref()
+                .map(|database| format!("DATABASE {}", ident(database)))
+        }
+    }
+
+    /// `PUBLIC` is a keyword, not a role name, and must stay unquoted.
+    fn grantee_sql(&self) -> String {
+        if self.role.eq_ignore_ascii_case("public") {
+            "PUBLIC".to_string()
+        } else {
+            ident(&self.role)
+        }
+    }
+
+    fn grant_statement(&self, object: &str) -> String {
+        format!(
+            "GRANT {} ON {} TO {}{};",
+            self.privileges_sql(),
+            object,
+            self.grantee_sql(),
+            if self.with_grant_option {
+                " WITH GRANT OPTION"
+            } else {
+                ""
+            }
+        )
+    }
+
+    /// Privileges held `WITH GRANT OPTION` revoke with `CASCADE` so
+    /// re-grants go too.
+    fn revoke_statement(&self, object: &str) -> String {
+        format!(
+            "REVOKE {} ON {} FROM {}{};",
+            self.privileges_sql(),
+            object,
+            self.grantee_sql(),
+            if self.with_grant_option {
+                " CASCADE"
+            } else {
+                ""
+            }
+        )
+    }
+
+    /// Render the statement undoing this resource: a `REVOKE` for a grant,
+    /// or a `GRANT` restoring the privileges for a revoke.
+    pub fn undo_sql(&self) -> Option<String> {
+        self.object_sql().map(|object| {
+            if self.revoke {
+                self.grant_statement(&object)
+            } else {
+                self.revoke_statement(&object)
+            }
+        })
+    }
+}
+
+impl fmt::Display for Grant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.object_sql() {
+            Some(object) if self.revoke => f.write_str(&self.revoke_statement(&object)),
+            Some(object) => f.write_str(&self.grant_statement(&object)),
+            None => Ok(()),
         }
     }
 }
@@ -1615,6 +2087,8 @@ mod tests {
                 nullable: false,
                 default: None,
                 db_type: None,
+                collation: None,
+                updated_at: false,
                 lint_ignore: vec![],
                 comment: None,
                 count: 1,
@@ -1622,6 +2096,7 @@ mod tests {
             primary_key: None,
             indexes: vec![],
             checks: vec![],
+            exclusions: vec![],
             foreign_keys: vec![],
             partition_by: Some(crate::ir::PartitionBySpec {
                 strategy: "RANGE".into(),
@@ -1631,6 +2106,9 @@ mod tests {
                 name: "t_p1".into(),
                 values: "FROM (0) TO (10)".into(),
             }],
+            storage_parameters: Default::default(),
+            row_level_security: false,
+            tablespace: None,
             back_references: vec![],
             lint_ignore: vec![],
             comment: None,
@@ -1642,6 +2120,57 @@ mod tests {
         assert!(sql.contains("CREATE TABLE \"public\".\"t_p1\" PARTITION OF \"public\".\"t\" FOR VALUES FROM (0) TO (10);"));
     }
 
+    #[test]
+    fn view_sql_with_columns_and_check_option() {
+        let vspec = crate::ir::ViewSpec {
+            name: "active_users".into(),
+            alt_name: None,
+            schema: Some("api".into()),
+            replace: true,
+            columns: vec!["id".into(), "email".into()],
+            sql: "SELECT id, email FROM users WHERE active\n".into(),
+            check_option: Some("cascaded".into()),
+            comment: None,
+        };
+        assert_eq!(
+            View::from(&vspec).to_string(),
+            "CREATE OR REPLACE VIEW \"api\".\"active_users\" (\"id\", \"email\") AS\nSELECT id, email FROM users WHERE active\nWITH CASCADED CHECK OPTION;"
+        );
+    }
+
+    #[test]
+    fn view_sql_with_local_check_option_only() {
+        let vspec = crate::ir::ViewSpec {
+            name: "v".into(),
+            alt_name: None,
+            schema: None,
+            replace: false,
+            columns: vec![],
+            sql: "SELECT 1 AS x".into(),
+            check_option: Some("LOCAL".into()),
+            comment: None,
+        };
+        assert_eq!(
+            View::from(&vspec).to_string(),
+            "CREATE VIEW \"public\".\"v\" AS\nSELECT 1 AS x\nWITH LOCAL CHECK OPTION;"
+        );
+    }
+
+    #[test]
+    fn schema_sql_with_authorization_reasserts_owner() {
+        let sspec = crate::ir::SchemaSpec {
+            name: "app".into(),
+            alt_name: None,
+            if_not_exists: false,
+            authorization: Some("app_owner".into()),
+            comment: None,
+        };
+        let sql = Schema::from(&sspec).to_string();
+        assert!(sql.contains("IF NOT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = 'app')"));
+        assert!(sql.contains("CREATE SCHEMA \"app\" AUTHORIZATION \"app_owner\";"));
+        assert!(sql.ends_with("END$$;\nALTER SCHEMA \"app\" OWNER TO \"app_owner\";"));
+    }
+
     #[test]
     fn role_sql_with_flags() {
         let rspec = crate::ir::RoleSpec {
@@ -1653,7 +2182,9 @@ mod tests {
             createrole: true,
             replication: false,
             password: Some("secret".into()),
+            valid_until: None,
             in_role: vec!["base".into()],
+            config: Default::default(),
             comment: None,
         };
         let role = Role::from(&rspec);
@@ -1661,6 +2192,58 @@ mod tests {
         assert!(sql.contains("CREATE ROLE \"r\" LOGIN SUPERUSER CREATEDB CREATEROLE PASSWORD 'secret' IN ROLE \"base\";"));
     }
 
+    #[test]
+    fn role_sql_with_config() {
+        let mut config = hcl::Map::new();
+        config.insert("search_path".into(), hcl::Value::from("app, public"));
+        config.insert("statement_timeout".into(), hcl::Value::from(5000));
+        let rspec = crate::ir::RoleSpec {
+            name: "app".into(),
+            alt_name: None,
+            login: true,
+            superuser: false,
+            createdb: false,
+            createrole: false,
+            replication: false,
+            password: None,
+            valid_until: None,
+            in_role: vec![],
+            config,
+            comment: None,
+        };
+        let sql = Role::from(&rspec).to_string();
+        assert!(sql.ends_with(
+            "END$$;\nALTER ROLE \"app\" SET search_path = 'app, public';\nALTER ROLE \"app\" SET statement_timeout = 5000;"
+        ));
+    }
+
+    #[test]
+    fn role_sql_with_password_hash_and_valid_until() {
+        let scram = "SCRAM-SHA-256$4096:c2FsdA==$c3RvcmVkS2V5:c2VydmVyS2V5";
+        let rspec = crate::ir::RoleSpec {
+            name: "app".into(),
+            alt_name: None,
+            login: true,
+            superuser: false,
+            createdb: false,
+            createrole: false,
+            replication: false,
+            password: Some(scram.into()),
+            valid_until: Some("2030-01-01".into()),
+            in_role: vec![],
+            config: Default::default(),
+            comment: None,
+        };
+        let sql = Role::from(&rspec).to_string();
+        assert!(sql.contains(&format!(
+            "CREATE ROLE \"app\" LOGIN PASSWORD '{scram}' VALID UNTIL '2030-01-01';"
+        )));
+
+        assert!(is_password_hash("md5a3556571e93b0d20722ba62be61e8c2d"));
+        assert!(!is_password_hash("md5-not-a-hash"));
+        assert!(!is_password_hash("secret"));
+    }
+
     #[test]
     fn grant_sql_all_database_and_sequence() {
         let gdb = crate::ir::GrantSpec {
@@ -1669,9 +2252,13 @@ mod tests {
             privileges: vec!["ALL".into()],
             schema: None,
             table: None,
+            columns: vec![],
             function: None,
             database: Some("appdb".into()),
             sequence: None,
+            all_in_schema: None,
+            with_grant_option: false,
+            revoke: false,
         };
         let grant_db = Grant::from(&gdb);
         assert_eq!(
@@ -1685,9 +2272,13 @@ mod tests {
             privileges: vec!["USAGE".into()],
             schema: Some("public".into()),
             table: None,
+            columns: vec![],
             function: None,
             database: None,
             sequence: Some("s".into()),
+            all_in_schema: None,
+            with_grant_option: false,
+            revoke: false,
         };
         let grant_seq = Grant::from(&gseq);
         assert_eq!(
@@ -1696,6 +2287,124 @@ mod tests {
         );
     }
 
+    #[test]
+    fn grant_sql_with_grant_option() {
+        let gspec = crate::ir::GrantSpec {
+            name: "admin_docs".into(),
+            role: "app_admin".into(),
+            privileges: vec!["SELECT".into(), "UPDATE".into()],
+            schema: Some("public".into()),
+            table: Some("docs".into()),
+            columns: vec![],
+            function: None,
+            database: None,
+            sequence: None,
+            all_in_schema: None,
+            with_grant_option: true,
+            revoke: false,
+        };
+        let grant = Grant::from(&gspec);
+        assert_eq!(
+            grant.to_string(),
+            "GRANT SELECT, UPDATE ON TABLE \"public\".\"docs\" TO \"app_admin\" WITH GRANT OPTION;"
+        );
+        assert_eq!(
+            grant.undo_sql().unwrap(),
+            "REVOKE SELECT, UPDATE ON TABLE \"public\".\"docs\" FROM \"app_admin\" CASCADE;"
+        );
+    }
+
+    #[test]
+    fn grant_sql_column_privileges() {
+        let gspec = crate::ir::GrantSpec {
+            name: "reporting_users".into(),
+            role: "reporting".into(),
+            privileges: vec!["SELECT".into(), "UPDATE".into()],
+            schema: Some("crm".into()),
+            table: Some("users".into()),
+            columns: vec!["id".into(), "country".into()],
+            function: None,
+            database: None,
+            sequence: None,
+            all_in_schema: None,
+            with_grant_option: false,
+            revoke: false,
+        };
+        let grant = Grant::from(&gspec);
+        assert_eq!(
+            grant.to_string(),
+            "GRANT SELECT (\"id\", \"country\"), UPDATE (\"id\", \"country\") ON TABLE \"crm\".\"users\" TO \"reporting\";"
+        );
+        assert_eq!(
+            grant.undo_sql().unwrap(),
+            "REVOKE SELECT (\"id\", \"country\"), UPDATE (\"id\", \"country\") ON TABLE \"crm\".\"users\" FROM \"reporting\";"
+        );
+    }
+
+    #[test]
+    fn grant_sql_all_in_schema() {
+        let grant = |kind: &str, privileges: &[&str]| {
+            Grant::from(&crate::ir::GrantSpec {
+                name: "g".into(),
+                role: "reader".into(),
+                privileges: privileges.iter().map(|p| p.to_string()).collect(),
+                schema: Some("public".into()),
+                table: None,
+                columns: vec![],
+                function: None,
+                database: None,
+                sequence: None,
+                all_in_schema: Some(kind.into()),
+                with_grant_option: false,
+                revoke: false,
+            })
+        };
+        assert_eq!(
+            grant("TABLES", &["SELECT"]).to_string(),
+            "GRANT SELECT ON ALL TABLES IN SCHEMA \"public\" TO \"reader\";"
+        );
+        let seq = grant("sequences", &["USAGE", "SELECT"]);
+        assert_eq!(
+            seq.to_string(),
+            "GRANT USAGE, SELECT ON ALL SEQUENCES IN SCHEMA \"public\" TO \"reader\";"
+        );
+        assert_eq!(
+            seq.undo_sql().unwrap(),
+            "REVOKE USAGE, SELECT ON ALL SEQUENCES IN SCHEMA \"public\" FROM \"reader\";"
+        );
+        assert_eq!(
+            grant("FUNCTIONS", &["EXECUTE"]).to_string(),
+            "GRANT EXECUTE ON ALL FUNCTIONS IN SCHEMA \"public\" TO \"reader\";"
+        );
+    }
+
+    #[test]
+    fn revoke_sql_from_public() {
+        let gspec = crate::ir::GrantSpec {
+            name: "lock_down_public".into(),
+            role: "PUBLIC".into(),
+            privileges: vec!["ALL".into()],
+            schema: Some("public".into()),
+            table: None,
+            columns: vec![],
+            function: None,
+            database: None,
+            sequence: None,
+            all_in_schema: None,
+            with_grant_option: false,
+            revoke: true,
+        };
+        let revoke = Grant::from(&gspec);
+        assert_eq!(
+            revoke.to_string(),
+            "REVOKE ALL PRIVILEGES ON SCHEMA \"public\" FROM PUBLIC;"
+        );
+        assert_eq!(
+            revoke.undo_sql().unwrap(),
+            "GRANT ALL PRIVILEGES ON SCHEMA \"public\" TO PUBLIC;"
+        );
+    }
+
     #[test]
     fn table_quotes_mixed_case_enum_type() {
         let tspec = crate::ir::TableSpec {
@@ -1710,6 +2419,8 @@ mod tests {
                     nullable: false,
                     default: None,
                     db_type: None,
+                    collation: None,
+                    updated_at: false,
                     lint_ignore: vec![],
                     comment: None,
                     count: 1,
@@ -1720,6 +2431,8 @@ mod tests {
                     nullable: false,
                     default: None,
                     db_type: None,
+                    collation: None,
+                    updated_at: false,
                     lint_ignore: vec![],
                     comment: None,
                     count: 1,
@@ -1731,9 +2444,13 @@ mod tests {
             }),
             indexes: vec![],
             checks: vec![],
+            exclusions: vec![],
             foreign_keys: vec![],
             partition_by: None,
             partitions: vec![],
+            storage_parameters: Default::default(),
+            row_level_security: false,
+            tablespace: None,
             back_references: vec![],
             lint_ignore: vec![],
             comment: None,
@@ -1,6 +1,14 @@
+pub mod apply;
 pub mod backend;
+pub mod connect;
+#[cfg(test)]
+mod doc_examples;
 pub mod generator;
 pub mod test_backend;
+#[cfg(test)]
+mod test_support;
+pub mod verify;
+pub mod version;
 
 use crate::provider::Provider;
 
@@ -38,17 +46,20 @@ impl Provider for PostgresProvider {
 
     fn register_backends(&self, registry: &mut crate::backends::BackendRegistry) {
         // Register the main postgres backend
-        registry.register(Box::new(backend::PostgresBackend));
+        registry.register(Box::new(backend::PostgresBackend::default()));
 
         // Register "pg" as an alias
-        registry.register_alias("pg", Box::new(backend::PostgresBackend));
+        registry.register_alias("pg", Box::new(backend::PostgresBackend::default()));
     }
 
     fn register_test_backends(&self, registry: &mut crate::test_runner::TestBackendRegistry) {
         // Register the postgres test backend
-        registry.register("postgres", Box::new(test_backend::PostgresTestBackend));
+        registry.register(
+            "postgres",
+            Box::new(test_backend::PostgresTestBackend::default()),
+        );
 
         // Register "pg" as an alias
-        registry.register("pg", Box::new(test_backend::PostgresTestBackend));
+        registry.register("pg", Box::new(test_backend::PostgresTestBackend::default()));
     }
 }
@@ -1,12 +1,12 @@
 use anyhow::{Context, Result, anyhow};
 use fallible_iterator::FallibleIterator;
-use postgres::{Client, NoTls, Row, Transaction};
-use std::collections::HashSet;
+use postgres::{Row, Transaction};
 use std::time::Duration;
 use url::Url;
 
+use super::connect::{ConnectOptions, connect};
 use crate::ir::{Config, InvariantSpec, TestSpec};
-use crate::test_runner::{TestBackend, TestResult, TestSummary, is_verbose};
+use crate::test_runner::{TestBackend, TestFilter, TestResult, TestSummary, is_verbose};
 use log::info;
 
 /// Run assert, assert_eq, assert_fail, and assert_error against a transaction.
@@ -138,20 +138,19 @@ fn run_assertions(tx: &mut Transaction, t: &TestSpec, invariants: &[InvariantSpe
     Ok(())
 }
 
-pub struct PostgresTestBackend;
+/// Runs HCL tests against PostgreSQL, opening connections with `connect`.
+#[derive(Debug, Clone, Default)]
+pub struct PostgresTestBackend {
+    pub connect: ConnectOptions,
+}
 
 impl TestBackend for PostgresTestBackend {
-    fn run(&self, cfg: &Config, dsn: &str, only: Option<&HashSet<String>>) -> Result<TestSummary> {
-        let mut client = Client::connect(dsn, NoTls)
+    fn run(&self, cfg: &Config, dsn: &str, filter: &TestFilter) -> Result<TestSummary> {
+        let mut client = connect(dsn, &self.connect)
             .with_context(|| format!("connecting to database: {}", redacted(dsn)))?;
         let mut results = Vec::new();
         let mut passed = 0usize;
-        for t in &cfg.tests {
-            if let Some(only) = only {
-                if !only.contains(&t.name) {
-                    continue;
-                }
-            }
+        for t in cfg.tests.iter().filter(|t| filter.matches(t)) {
             let name = t.name.clone();
             let mut failed_msg = String::new();
             let mut ok = true;
@@ -162,7 +161,7 @@ impl TestBackend for PostgresTestBackend {
                 // use the normal rollback-based isolation for these tests.
 
                 // 1. Open a dedicated listener connection and LISTEN on each channel
-                let mut listener = Client::connect(dsn, NoTls)
+                let mut listener = connect(dsn, &self.connect)
                     .with_context(|| format!("notify listener: connecting to {}", redacted(dsn)))?;
                 for na in &t.assert_notify {
                     let listen_sql = format!("LISTEN {}", na.channel);
@@ -306,7 +305,7 @@ impl TestBackend for PostgresTestBackend {
         let mut admin_base = base.clone();
         admin_base.set_path("/postgres");
         let admin_dsn = admin_base.as_str().to_string();
-        let mut admin = Client::connect(&admin_dsn, NoTls)
+        let mut admin = connect(&admin_dsn, &self.connect)
             .with_context(|| format!("connecting to admin database: {}", redacted(&admin_dsn)))?;
         if verbose {
             info!("-- admin: DROP DATABASE IF EXISTS \"{}\";", database_name);
@@ -328,7 +327,7 @@ impl TestBackend for PostgresTestBackend {
         if let Ok(mut base) = Url::parse(dsn) {
             base.set_path("/postgres");
             let admin_dsn = base.as_str().to_string();
-            if let Ok(mut admin) = Client::connect(&admin_dsn, NoTls) {
+            if let Ok(mut admin) = connect(&admin_dsn, &self.connect) {
                 if verbose {
                     info!("-- admin: DROP DATABASE IF EXISTS \"{}\";", database_name);
                 }
@@ -434,10 +433,12 @@ fn redacted(dsn: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use postgres::{Client, NoTls};
     use crate::ir::{
         Config, EqAssertSpec, ErrorAssertSpec, InvariantSpec, NotifyAssertSpec,
         SnapshotAssertSpec, TestSpec,
     };
+    use crate::provider::postgres::test_support::start_pg;
     use crate::test_runner::TestBackend;
 
     #[test]
@@ -469,19 +470,10 @@ mod tests {
 
     // --- Integration tests using testcontainers ---
 
-    fn start_pg() -> (testcontainers_modules::testcontainers::Container<testcontainers_modules::postgres::Postgres>, String) {
-        use testcontainers_modules::postgres::Postgres as PgImage;
-        use testcontainers_modules::testcontainers::runners::SyncRunner;
-        let container = PgImage::default().start().unwrap();
-        let host = container.get_host().unwrap();
-        let port = container.get_host_port_ipv4(5432).unwrap();
-        let dsn = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
-        (container, dsn)
-    }
-
     fn test_spec(name: &str) -> TestSpec {
         TestSpec {
             name: name.into(),
+            tags: vec![],
             setup: vec![],
             asserts: vec![],
             assert_fail: vec![],
@@ -498,7 +490,9 @@ mod tests {
             tests: vec![test],
             ..Default::default()
         };
-        let summary = PostgresTestBackend.run(&cfg, dsn, None).unwrap();
+        let summary = PostgresTestBackend::default()
+            .run(&cfg, dsn, &TestFilter::default())
+            .unwrap();
         summary.results.into_iter().next().unwrap()
     }
 
@@ -508,7 +502,9 @@ mod tests {
             invariants,
             ..Default::default()
         };
-        let summary = PostgresTestBackend.run(&cfg, dsn, None).unwrap();
+        let summary = PostgresTestBackend::default()
+            .run(&cfg, dsn, &TestFilter::default())
+            .unwrap();
         summary.results.into_iter().next().unwrap()
     }
 
@@ -742,8 +738,10 @@ mod tests {
 
     // -- only filtering --
 
-    fn run_cfg(dsn: &str, cfg: Config, only: Option<&HashSet<String>>) -> crate::test_runner::TestSummary {
-        PostgresTestBackend.run(&cfg, dsn, only).unwrap()
+    fn run_cfg(dsn: &str, cfg: Config, filter: &TestFilter) -> crate::test_runner::TestSummary {
+        PostgresTestBackend::default()
+            .run(&cfg, dsn, filter)
+            .unwrap()
     }
 
     #[test]
@@ -757,8 +755,7 @@ mod tests {
             tests: vec![t1, t2],
             ..Default::default()
         };
-        let only: HashSet<String> = ["included".to_string()].into();
-        let summary = run_cfg(&dsn, cfg, Some(&only));
+        let summary = run_cfg(&dsn, cfg, &TestFilter::names(["included"]));
         assert_eq!(summary.total, 1);
         assert_eq!(summary.passed, 1);
         assert_eq!(summary.results[0].name, "included");
@@ -773,8 +770,7 @@ mod tests {
             tests: vec![t],
             ..Default::default()
         };
-        let only: HashSet<String> = ["nonexistent".to_string()].into();
-        let summary = run_cfg(&dsn, cfg, Some(&only));
+        let summary = run_cfg(&dsn, cfg, &TestFilter::names(["nonexistent"]));
         assert_eq!(summary.total, 0);
         assert_eq!(summary.passed, 0);
         assert_eq!(summary.failed, 0);
@@ -910,7 +906,7 @@ mod tests {
             tests: vec![t1, t2, t3],
             ..Default::default()
         };
-        let summary = run_cfg(&dsn, cfg, None);
+        let summary = run_cfg(&dsn, cfg, &TestFilter::default());
         assert_eq!(summary.total, 3);
         assert_eq!(summary.passed, 2);
         assert_eq!(summary.failed, 1);
@@ -932,7 +928,7 @@ mod tests {
             tests: vec![t1, t2, t3],
             ..Default::default()
         };
-        let summary = run_cfg(&dsn, cfg, None);
+        let summary = run_cfg(&dsn, cfg, &TestFilter::default());
         let names: Vec<&str> = summary.results.iter().map(|r| r.name.as_str()).collect();
         assert_eq!(names, vec!["alpha", "beta", "gamma"]);
     }
@@ -943,7 +939,7 @@ mod tests {
     fn temporary_database_setup_and_cleanup() {
         let (_c, dsn) = start_pg();
         let db_name = "test_temp_db_integration";
-        let new_dsn = PostgresTestBackend
+        let new_dsn = PostgresTestBackend::default()
             .setup_temporary_database(&dsn, db_name, false)
             .expect("setup_temporary_database failed");
         assert!(new_dsn.contains(db_name), "DSN should contain db name: {}", new_dsn);
@@ -954,7 +950,7 @@ mod tests {
         drop(client);
 
         // Cleanup
-        PostgresTestBackend
+        PostgresTestBackend::default()
             .cleanup_temporary_database(&dsn, db_name, false)
             .expect("cleanup failed");
 
@@ -968,7 +964,7 @@ mod tests {
         let (_c, dsn) = start_pg();
         let db_name = "test_temp_replace_db";
         // Create once
-        let dsn1 = PostgresTestBackend
+        let dsn1 = PostgresTestBackend::default()
             .setup_temporary_database(&dsn, db_name, false)
             .expect("first setup failed");
         // Create table in the first database
@@ -977,7 +973,7 @@ mod tests {
         drop(c1);
 
         // Setup again — should DROP and recreate, losing the table
-        let dsn2 = PostgresTestBackend
+        let dsn2 = PostgresTestBackend::default()
             .setup_temporary_database(&dsn, db_name, false)
             .expect("second setup failed");
         let mut c2 = Client::connect(&dsn2, NoTls).unwrap();
@@ -985,7 +981,7 @@ mod tests {
         assert!(result.is_err(), "table should not exist after re-setup");
         drop(c2);
 
-        PostgresTestBackend
+        PostgresTestBackend::default()
             .cleanup_temporary_database(&dsn, db_name, false)
             .unwrap();
     }
@@ -993,14 +989,14 @@ mod tests {
     #[test]
     fn cleanup_nonexistent_database_is_ok() {
         let (_c, dsn) = start_pg();
-        let result = PostgresTestBackend
+        let result = PostgresTestBackend::default()
             .cleanup_temporary_database(&dsn, "db_that_does_not_exist", false);
         assert!(result.is_ok(), "cleanup of nonexistent db should succeed");
     }
 
     #[test]
     fn supports_temporary_database_is_true() {
-        assert!(PostgresTestBackend.supports_temporary_database());
+        assert!(PostgresTestBackend::default().supports_temporary_database());
     }
 
     // -- assert_snapshot edge cases --
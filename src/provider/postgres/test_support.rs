@@ -0,0 +1,19 @@
+//! Helpers shared by the tests that need a live PostgreSQL.
+
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::Container;
+use testcontainers_modules::testcontainers::runners::SyncRunner;
+
+/// A running PostgreSQL container. It stops when dropped, so keep it alive for
+/// as long as any client connected to it.
+pub(crate) type PgContainer = Container<Postgres>;
+
+/// Start a PostgreSQL container and return it with a DSN for its `postgres`
+/// database.
+pub(crate) fn start_pg() -> (PgContainer, String) {
+    let container = Postgres::default().start().unwrap();
+    let host = container.get_host().unwrap();
+    let port = container.get_host_port_ipv4(5432).unwrap();
+    let dsn = format!("postgres://postgres:postgres@{}:{}/postgres", host, port);
+    (container, dsn)
+}
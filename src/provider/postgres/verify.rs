@@ -0,0 +1,456 @@
+//! Compare a live database against the intended [`Config`] (drift detection).
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use postgres::Client;
+use postgres::types::Oid;
+
+use crate::ir::Config;
+
+/// A schema-qualified object name.
+type QualifiedName = (String, String);
+
+/// A column as recorded in `pg_attribute`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogColumn {
+    pub name: String,
+    pub not_null: bool,
+    pub type_oid: Oid,
+    /// `format_type` of the column, used in drift messages.
+    pub type_name: String,
+}
+
+/// The parts of a database's catalog that [`diff`] compares against, read in
+/// one pass by [`introspect`]. System schemas are left out.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    pub schemas: HashSet<String>,
+    /// Installed extensions and their versions.
+    pub extensions: HashMap<String, String>,
+    /// Enum labels in sort order.
+    pub enums: HashMap<QualifiedName, Vec<String>>,
+    /// `pg_class.relkind` of every relation.
+    pub relations: HashMap<QualifiedName, String>,
+    /// Columns of ordinary and partitioned tables, in attribute order.
+    pub columns: HashMap<QualifiedName, Vec<CatalogColumn>>,
+    pub functions: HashSet<QualifiedName>,
+    /// OIDs of the column types declared in the config. Names that don't
+    /// resolve, such as the `serial` pseudo-types, are absent.
+    pub types: HashMap<String, Oid>,
+}
+
+/// Introspect the database behind `client` and describe every way it differs
+/// from `cfg`. An empty result means no drift was found.
+pub fn verify(client: &mut Client, cfg: &Config) -> Result<Vec<String>> {
+    let mut catalog = introspect(client)?;
+    catalog.types = resolve_types(client, cfg)?;
+    Ok(diff(cfg, &catalog))
+}
+
+const USER_SCHEMAS: &str =
+    "n.nspname NOT IN ('pg_catalog', 'information_schema') AND n.nspname NOT LIKE 'pg\\_toast%'";
+
+/// Read the schemas, extensions, enums, relations, table columns and
+/// functions of the database behind `client`.
+pub fn introspect(client: &mut Client) -> Result<Catalog> {
+    let mut catalog = Catalog::default();
+
+    for row in client
+        .query(
+            &format!("SELECT n.nspname FROM pg_namespace n WHERE {USER_SCHEMAS}"),
+            &[],
+        )
+        .context("introspecting schemas")?
+    {
+        catalog.schemas.insert(row.get(0));
+    }
+
+    for row in client
+        .query("SELECT extname, extversion FROM pg_extension", &[])
+        .context("introspecting extensions")?
+    {
+        catalog.extensions.insert(row.get(0), row.get(1));
+    }
+
+    for row in client
+        .query(
+            "SELECT n.nspname, t.typname, e.enumlabel FROM pg_enum e \
+             JOIN pg_type t ON t.oid = e.enumtypid \
+             JOIN pg_namespace n ON n.oid = t.typnamespace \
+             ORDER BY n.nspname, t.typname, e.enumsortorder",
+            &[],
+        )
+        .context("introspecting enums")?
+    {
+        catalog
+            .enums
+            .entry((row.get(0), row.get(1)))
+            .or_default()
+            .push(row.get(2));
+    }
+
+    for row in client
+        .query(
+            &format!(
+                "SELECT n.nspname, c.relname, c.relkind::text FROM pg_class c \
+                 JOIN pg_namespace n ON n.oid = c.relnamespace WHERE {USER_SCHEMAS}"
+            ),
+            &[],
+        )
+        .context("introspecting relations")?
+    {
+        catalog
+            .relations
+            .insert((row.get(0), row.get(1)), row.get(2));
+    }
+
+    for row in client
+        .query(
+            &format!(
+                "SELECT n.nspname, c.relname, a.attname, a.attnotnull, a.atttypid, \
+                 format_type(a.atttypid, a.atttypmod) \
+                 FROM pg_attribute a \
+                 JOIN pg_class c ON c.oid = a.attrelid \
+                 JOIN pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE {USER_SCHEMAS} AND c.relkind IN ('r', 'p') \
+                 AND a.attnum > 0 AND NOT a.attisdropped \
+                 ORDER BY n.nspname, c.relname, a.attnum"
+            ),
+            &[],
+        )
+        .context("introspecting columns")?
+    {
+        catalog
+            .columns
+            .entry((row.get(0), row.get(1)))
+            .or_default()
+            .push(CatalogColumn {
+                name: row.get(2),
+                not_null: row.get(3),
+                type_oid: row.get(4),
+                type_name: row.get(5),
+            });
+    }
+
+    for row in client
+        .query(
+            &format!(
+                "SELECT DISTINCT n.nspname, p.proname FROM pg_proc p \
+                 JOIN pg_namespace n ON n.oid = p.pronamespace WHERE {USER_SCHEMAS}"
+            ),
+            &[],
+        )
+        .context("introspecting functions")?
+    {
+        catalog.functions.insert((row.get(0), row.get(1)));
+    }
+
+    Ok(catalog)
+}
+
+/// Resolve every column type declared in `cfg` to its OID with `to_regtype`.
+fn resolve_types(client: &mut Client, cfg: &Config) -> Result<HashMap<String, Oid>> {
+    let names: Vec<String> = cfg
+        .tables
+        .iter()
+        .flat_map(|t| &t.columns)
+        .map(|c| c.db_type.clone().unwrap_or_else(|| c.r#type.clone()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let rows = client
+        .query(
+            "SELECT name, to_regtype(name)::oid FROM unnest($1::text[]) AS name",
+            &[&names],
+        )
+        .context("resolving column types")?;
+    Ok(rows
+        .iter()
+        .filter_map(|r| Some((r.get(0), r.get::<_, Option<Oid>>(1)?)))
+        .collect())
+}
+
+/// Describe every way `catalog` differs from `cfg`.
+///
+/// Covers schemas, extensions (and pinned versions), enums (labels and
+/// order), sequences, tables and their columns (presence, base type and
+/// nullability), named indexes, views, materialized views and functions.
+/// Extra objects are only reported for table columns; everything else is
+/// checked for presence.
+pub fn diff(cfg: &Config, catalog: &Catalog) -> Vec<String> {
+    let mut drift = Vec::new();
+    let name_of = |name: &String, alt: &Option<String>| alt.clone().unwrap_or_else(|| name.clone());
+    let schema_of =
+        |schema: &Option<String>| schema.clone().unwrap_or_else(|| "public".to_string());
+    let relkind = |schema: &str, name: &str| {
+        catalog
+            .relations
+            .get(&(schema.to_string(), name.to_string()))
+            .map(String::as_str)
+    };
+
+    for s in &cfg.schemas {
+        let name = name_of(&s.name, &s.alt_name);
+        if !catalog.schemas.contains(&name) {
+            drift.push(format!("schema \"{name}\" is missing"));
+        }
+    }
+
+    for e in &cfg.extensions {
+        let name = name_of(&e.name, &e.alt_name);
+        match (catalog.extensions.get(&name), &e.version) {
+            (None, _) => drift.push(format!("extension \"{name}\" is missing")),
+            (Some(installed), Some(expected)) if installed != expected => drift.push(format!(
+                "extension \"{name}\" is at version {installed}, expected {expected}"
+            )),
+            _ => {}
+        }
+    }
+
+    for e in &cfg.enums {
+        let (schema, name) = (schema_of(&e.schema), name_of(&e.name, &e.alt_name));
+        match catalog.enums.get(&(schema.clone(), name.clone())) {
+            None => drift.push(format!("enum \"{schema}\".\"{name}\" is missing")),
+            Some(labels) if labels != &e.values => drift.push(format!(
+                "enum \"{schema}\".\"{name}\" has values {labels:?}, expected {:?}",
+                e.values
+            )),
+            Some(_) => {}
+        }
+    }
+
+    for s in &cfg.sequences {
+        let (schema, name) = (schema_of(&s.schema), name_of(&s.name, &s.alt_name));
+        if relkind(&schema, &name) != Some("S") {
+            drift.push(format!("sequence \"{schema}\".\"{name}\" is missing"));
+        }
+    }
+
+    for t in &cfg.tables {
+        let (schema, name) = (schema_of(&t.schema), name_of(&t.name, &t.alt_name));
+        if !matches!(relkind(&schema, &name), Some("r" | "p")) {
+            drift.push(format!("table \"{schema}\".\"{name}\" is missing"));
+            continue;
+        }
+        let actual = catalog
+            .columns
+            .get(&(schema.clone(), name.clone()))
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        let pk_columns = t
+            .primary_key
+            .as_ref()
+            .map(|pk| pk.columns.as_slice())
+            .unwrap_or(&[]);
+        for col in &t.columns {
+            let Some(found) = actual.iter().find(|c| c.name == col.name) else {
+                drift.push(format!(
+                    "column \"{schema}\".\"{name}\".\"{}\" is missing",
+                    col.name
+                ));
+                continue;
+            };
+            let expected_not_null = !col.nullable || pk_columns.contains(&col.name);
+            if found.not_null != expected_not_null {
+                drift.push(format!(
+                    "column \"{schema}\".\"{name}\".\"{}\" is {}, expected {}",
+                    col.name,
+                    if found.not_null {
+                        "NOT NULL"
+                    } else {
+                        "nullable"
+                    },
+                    if expected_not_null {
+                        "NOT NULL"
+                    } else {
+                        "nullable"
+                    },
+                ));
+            }
+            // Pseudo-types such as `serial` don't resolve; skip the type check for them.
+            let expected_type = col.db_type.as_deref().unwrap_or(&col.r#type);
+            if let Some(expected_oid) = catalog.types.get(expected_type)
+                && *expected_oid != found.type_oid
+            {
+                drift.push(format!(
+                    "column \"{schema}\".\"{name}\".\"{}\" has type {}, expected {expected_type}",
+                    col.name, found.type_name
+                ));
+            }
+        }
+        for col in actual {
+            if !t.columns.iter().any(|c| c.name == col.name) {
+                drift.push(format!(
+                    "column \"{schema}\".\"{name}\".\"{}\" is not declared",
+                    col.name
+                ));
+            }
+        }
+        for idx in &t.indexes {
+            if let Some(idx_name) = &idx.name
+                && relkind(&schema, idx_name) != Some("i")
+            {
+                drift.push(format!("index \"{schema}\".\"{idx_name}\" is missing"));
+            }
+        }
+    }
+
+    for idx in &cfg.indexes {
+        let schema = schema_of(&idx.schema);
+        if relkind(&schema, &idx.name) != Some("i") {
+            drift.push(format!("index \"{schema}\".\"{}\" is missing", idx.name));
+        }
+    }
+
+    for v in &cfg.views {
+        let (schema, name) = (schema_of(&v.schema), name_of(&v.name, &v.alt_name));
+        if relkind(&schema, &name) != Some("v") {
+            drift.push(format!("view \"{schema}\".\"{name}\" is missing"));
+        }
+    }
+
+    for mv in &cfg.materialized {
+        let (schema, name) = (schema_of(&mv.schema), name_of(&mv.name, &mv.alt_name));
+        if relkind(&schema, &name) != Some("m") {
+            drift.push(format!(
+                "materialized view \"{schema}\".\"{name}\" is missing"
+            ));
+        }
+    }
+
+    for f in &cfg.functions {
+        let (schema, name) = (schema_of(&f.schema), name_of(&f.name, &f.alt_name));
+        if !catalog.functions.contains(&(schema.clone(), name.clone())) {
+            drift.push(format!("function \"{schema}\".\"{name}\" is missing"));
+        }
+    }
+
+    drift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::postgres::apply::{ApplyOptions, apply_migration};
+    use crate::provider::postgres::test_support::start_pg;
+    use crate::test_support::load_hcl;
+    use postgres::NoTls;
+    use std::collections::HashMap;
+
+    #[test]
+    fn diff_compares_config_with_catalog() {
+        let cfg = load_hcl(
+            r#"
+            enum "status" {
+              values = ["active", "inactive"]
+            }
+            table "widgets" {
+              column "id" {
+                type = "int"
+                nullable = false
+              }
+              column "name" { type = "text" }
+            }
+            view "recent" {
+              sql = "SELECT 1"
+            }
+            "#,
+        );
+        let key = |n: &str| ("public".to_string(), n.to_string());
+        let column = |name: &str, not_null: bool, type_oid: Oid, type_name: &str| CatalogColumn {
+            name: name.into(),
+            not_null,
+            type_oid,
+            type_name: type_name.into(),
+        };
+        let catalog = Catalog {
+            enums: HashMap::from([(key("status"), vec!["inactive".into(), "active".into()])]),
+            relations: HashMap::from([(key("widgets"), "r".to_string())]),
+            columns: HashMap::from([(
+                key("widgets"),
+                vec![
+                    column("id", true, 23, "integer"),
+                    column("name", false, 1043, "character varying"),
+                    column("extra", false, 23, "integer"),
+                ],
+            )]),
+            types: HashMap::from([("int".to_string(), 23), ("text".to_string(), 25)]),
+            ..Catalog::default()
+        };
+        assert_eq!(
+            diff(&cfg, &catalog),
+            vec![
+                "enum \"public\".\"status\" has values [\"inactive\", \"active\"], expected [\"active\", \"inactive\"]",
+                "column \"public\".\"widgets\".\"name\" has type character varying, expected text",
+                "column \"public\".\"widgets\".\"extra\" is not declared",
+                "view \"public\".\"recent\" is missing",
+            ]
+        );
+    }
+
+    #[test]
+    fn manually_altered_database_is_reported_as_drifted() {
+        let (_container, dsn) = start_pg();
+        let mut client = Client::connect(&dsn, NoTls).unwrap();
+        let cfg = load_hcl(
+            r#"
+            enum "status" {
+              values = ["active", "inactive"]
+            }
+            table "widgets" {
+              column "id" {
+                type = "int"
+                nullable = false
+              }
+              column "name" { type = "varchar(50)" }
+              column "status" { type = "status" }
+            }
+            "#,
+        );
+        apply_migration(&mut client, &cfg, &ApplyOptions::default()).unwrap();
+        assert_eq!(verify(&mut client, &cfg).unwrap(), Vec::<String>::new());
+
+        client
+            .batch_execute(
+                "ALTER TABLE widgets ADD COLUMN extra int; \
+                 ALTER TABLE widgets ALTER COLUMN name TYPE text; \
+                 ALTER TABLE widgets ALTER COLUMN id DROP NOT NULL;",
+            )
+            .unwrap();
+        let drift = verify(&mut client, &cfg).unwrap();
+        assert!(
+            drift.contains(&"column \"public\".\"widgets\".\"extra\" is not declared".to_string())
+        );
+        assert!(drift.iter().any(|d| d.contains("\"name\" has type text")));
+        assert!(
+            drift
+                .iter()
+                .any(|d| d.contains("\"id\" is nullable, expected NOT NULL"))
+        );
+    }
+
+    #[test]
+    fn extension_version_drift_is_reported() {
+        let (_container, dsn) = start_pg();
+        let mut client = Client::connect(&dsn, NoTls).unwrap();
+        client.batch_execute("CREATE EXTENSION pgcrypto").unwrap();
+        let cfg = load_hcl(
+            r#"
+            extension "pgcrypto" {
+              version = "1.0"
+            }
+            extension "citext" {}
+            "#,
+        );
+        let drift = verify(&mut client, &cfg).unwrap();
+        assert!(
+            drift
+                .iter()
+                .any(|d| d.starts_with("extension \"pgcrypto\" is at version ")
+                    && d.ends_with(", expected 1.0")),
+            "{drift:?}"
+        );
+        assert!(drift.contains(&"extension \"citext\" is missing".to_string()));
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{Result, anyhow, bail};
+use std::fmt;
+use std::str::FromStr;
+
+/// PostgreSQL major version the generated SQL targets. Only the features
+/// dbschema generates that are newer than 12 depend on it:
+/// `CREATE OR REPLACE TRIGGER` (14) falls back to dropping and recreating
+/// the trigger, and `NULLS NOT DISTINCT` (15), which has no equivalent, is
+/// rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum PgVersion {
+    V12,
+    V14,
+    V15,
+    #[default]
+    V16,
+}
+
+impl PgVersion {
+    pub const ALL: [PgVersion; 4] = [
+        PgVersion::V12,
+        PgVersion::V14,
+        PgVersion::V15,
+        PgVersion::V16,
+    ];
+
+    pub fn major(self) -> u32 {
+        match self {
+            PgVersion::V12 => 12,
+            PgVersion::V14 => 14,
+            PgVersion::V15 => 15,
+            PgVersion::V16 => 16,
+        }
+    }
+
+    /// The newest target whose SQL runs on a PostgreSQL `major` server.
+    pub fn for_server(major: u32) -> Result<Self> {
+        PgVersion::ALL
+            .into_iter()
+            .rev()
+            .find(|v| v.major() <= major)
+            .ok_or_else(|| {
+                anyhow!(
+                    "PostgreSQL {major} is older than the oldest supported target version {}",
+                    PgVersion::V12
+                )
+            })
+    }
+
+    /// Error unless `feature`, added in PostgreSQL `since`, is available.
+    pub fn require(self, since: PgVersion, feature: &str) -> Result<()> {
+        if self < since {
+            bail!("{feature} requires PostgreSQL {since} or newer (target version is {self})");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PgVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.major())
+    }
+}
+
+impl TryFrom<u32> for PgVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(major: u32) -> Result<Self> {
+        PgVersion::ALL
+            .into_iter()
+            .find(|v| v.major() == major)
+            .ok_or_else(|| {
+                let supported: Vec<String> =
+                    PgVersion::ALL.iter().map(|v| v.to_string()).collect();
+                anyhow!(
+                    "unsupported PostgreSQL target version {major} (expected one of {})",
+                    supported.join(", ")
+                )
+            })
+    }
+}
+
+impl FromStr for PgVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let major: u32 = s
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("invalid PostgreSQL target version '{s}'"))?;
+        PgVersion::try_from(major)
+    }
+}
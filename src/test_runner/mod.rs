@@ -1,8 +1,9 @@
 use anyhow::{anyhow, Result};
+use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::ir::Config;
+use crate::ir::{Config, TestSpec};
 
 pub struct TestResult {
     pub name: String,
@@ -18,7 +19,8 @@ pub struct TestSummary {
 }
 
 pub trait TestBackend {
-    fn run(&self, cfg: &Config, dsn: &str, only: Option<&HashSet<String>>) -> Result<TestSummary>;
+    /// Run the tests in `cfg` selected by `filter`.
+    fn run(&self, cfg: &Config, dsn: &str, filter: &TestFilter) -> Result<TestSummary>;
 
     /// Returns `true` if this backend knows how to provision temporary databases.
     fn supports_temporary_database(&self) -> bool {
@@ -42,6 +44,76 @@ pub trait TestBackend {
     }
 }
 
+/// Selects which tests to run. The default runs every test; each non-empty
+/// dimension narrows the selection further.
+#[derive(Debug, Clone, Default)]
+pub struct TestFilter {
+    /// Only run tests with these names; empty means no restriction.
+    pub names: HashSet<String>,
+    /// Only run tests carrying at least one of these tags.
+    pub tags: HashSet<String>,
+    /// Skip tests carrying any of these tags.
+    pub exclude_tags: HashSet<String>,
+    /// Only run tests whose name matches this pattern.
+    pub name_regex: Option<Regex>,
+}
+
+impl TestFilter {
+    /// Run only the named tests.
+    pub fn names<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            names: names.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether `test` is selected by every dimension of the filter.
+    pub fn matches(&self, test: &TestSpec) -> bool {
+        (self.names.is_empty() || self.names.contains(&test.name))
+            && (self.tags.is_empty() || test.tags.iter().any(|t| self.tags.contains(t)))
+            && !test.tags.iter().any(|t| self.exclude_tags.contains(t))
+            && self
+                .name_regex
+                .as_ref()
+                .is_none_or(|re| re.is_match(&test.name))
+    }
+}
+
+/// Run the tests in `cfg` against `backend` and return the structured results.
+///
+/// `dsn` falls back to the `DATABASE_URL` environment variable. Failing tests
+/// are reported in the returned [`TestSummary`], not as an `Err`; errors are
+/// reserved for problems such as a missing DSN or an unreachable database.
+///
+/// ```no_run
+/// use dbschema::test_runner::{TestFilter, get_default_test_backend_registry, run_tests};
+/// # fn main() -> anyhow::Result<()> {
+/// # let cfg = dbschema::Config::default();
+/// let registry = get_default_test_backend_registry();
+/// let backend = registry.get("postgres").unwrap();
+/// let summary = run_tests(&cfg, backend, Some("postgres://localhost/app"), TestFilter::default())?;
+/// assert_eq!(summary.failed, 0);
+/// # Ok(())
+/// # }
+/// ```
+pub fn run_tests(
+    cfg: &Config,
+    backend: &dyn TestBackend,
+    dsn: Option<&str>,
+    filter: TestFilter,
+) -> Result<TestSummary> {
+    let dsn = match dsn {
+        Some(dsn) => dsn.to_string(),
+        None => std::env::var("DATABASE_URL")
+            .map_err(|_| anyhow!("missing DSN: pass a DSN or set DATABASE_URL"))?,
+    };
+    backend.run(cfg, &dsn, &filter)
+}
+
 /// Registry for managing test backends provided by providers.
 pub struct TestBackendRegistry {
     backends: HashMap<String, Box<dyn TestBackend>>,
@@ -101,3 +173,140 @@ pub fn set_verbose(v: bool) {
 pub fn is_verbose() -> bool {
     VERBOSE.load(Ordering::Relaxed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::load_hcl;
+    use std::sync::Mutex;
+
+    /// Passes every selected test and records the DSN it was called with.
+    #[derive(Default)]
+    struct RecordingBackend {
+        dsns: Mutex<Vec<String>>,
+    }
+
+    impl TestBackend for RecordingBackend {
+        fn run(&self, cfg: &Config, dsn: &str, filter: &TestFilter) -> Result<TestSummary> {
+            self.dsns.lock().unwrap().push(dsn.to_string());
+            let results: Vec<TestResult> = cfg
+                .tests
+                .iter()
+                .filter(|t| filter.matches(t))
+                .map(|t| TestResult {
+                    name: t.name.clone(),
+                    passed: true,
+                    message: String::new(),
+                })
+                .collect();
+            Ok(TestSummary {
+                total: results.len(),
+                passed: results.len(),
+                failed: 0,
+                results,
+            })
+        }
+    }
+
+    fn selected(cfg: &Config, filter: TestFilter) -> Vec<String> {
+        run_tests(
+            cfg,
+            &RecordingBackend::default(),
+            Some("postgres://db"),
+            filter,
+        )
+        .unwrap()
+        .results
+        .into_iter()
+        .map(|r| r.name)
+        .collect()
+    }
+
+    #[test]
+    fn run_tests_passes_dsn_and_filter_to_backend() {
+        let cfg = load_hcl(
+            r#"
+            test "first" { assert = "SELECT true" }
+            test "second" { assert = "SELECT true" }
+            "#,
+        );
+        let backend = RecordingBackend::default();
+
+        let summary =
+            run_tests(&cfg, &backend, Some("postgres://db"), TestFilter::default()).unwrap();
+        assert_eq!(summary.total, 2);
+
+        let summary = run_tests(
+            &cfg,
+            &backend,
+            Some("postgres://db"),
+            TestFilter::names(["second"]),
+        )
+        .unwrap();
+        assert_eq!(summary.total, 1);
+        assert_eq!(summary.results[0].name, "second");
+
+        assert_eq!(
+            *backend.dsns.lock().unwrap(),
+            ["postgres://db", "postgres://db"]
+        );
+    }
+
+    #[test]
+    fn name_regex_selects_matching_tests() {
+        let cfg = load_hcl(
+            r#"
+            test "users_insert" { assert = "SELECT true" }
+            test "users_delete" { assert = "SELECT true" }
+            test "orders_insert" { assert = "SELECT true" }
+            "#,
+        );
+        let filter = |pattern: &str| TestFilter {
+            name_regex: Some(Regex::new(pattern).unwrap()),
+            ..TestFilter::default()
+        };
+        assert_eq!(
+            selected(&cfg, filter("^users_")),
+            ["users_insert", "users_delete"]
+        );
+        assert_eq!(
+            selected(&cfg, filter("_insert$")),
+            ["users_insert", "orders_insert"]
+        );
+        assert!(selected(&cfg, filter("^payments")).is_empty());
+
+        // Combined with names, both must match.
+        let combined = TestFilter {
+            name_regex: Some(Regex::new("insert").unwrap()),
+            ..TestFilter::names(["users_insert", "users_delete"])
+        };
+        assert_eq!(selected(&cfg, combined), ["users_insert"]);
+    }
+
+    #[test]
+    fn tags_include_and_exclude_tests() {
+        let cfg = load_hcl(
+            r#"
+            test "fast" {
+              tags = ["unit"]
+              assert = "SELECT true"
+            }
+            test "slow" {
+              tags = ["unit", "slow"]
+              assert = "SELECT true"
+            }
+            test "untagged" { assert = "SELECT true" }
+            "#,
+        );
+        let tags = TestFilter {
+            tags: HashSet::from(["unit".to_string()]),
+            ..TestFilter::default()
+        };
+        assert_eq!(selected(&cfg, tags.clone()), ["fast", "slow"]);
+        let exclude = TestFilter {
+            exclude_tags: HashSet::from(["slow".to_string()]),
+            ..tags
+        };
+        assert_eq!(selected(&cfg, exclude), ["fast"]);
+    }
+}
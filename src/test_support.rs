@@ -0,0 +1,24 @@
+//! Helpers shared by unit tests across the crate.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::frontend::env::EnvVars;
+use crate::ir::Config;
+use crate::{MapLoader, load_config};
+
+/// Load `hcl` as the root file `/root/main.hcl`.
+pub(crate) fn try_load_hcl(hcl: &str) -> Result<Config> {
+    let path = PathBuf::from("/root/main.hcl");
+    let loader = MapLoader {
+        files: HashMap::from([(path.clone(), hcl.to_string())]),
+    };
+    load_config(&path, &loader, EnvVars::default())
+}
+
+/// Like [`try_load_hcl`], panicking if the config doesn't load.
+pub(crate) fn load_hcl(hcl: &str) -> Config {
+    try_load_hcl(hcl).unwrap()
+}